@@ -1,6 +1,8 @@
 use std::env;
 
+use tune::layout::IsomorphicKeyboard;
 use tune::layout::IsomorphicLayout;
+use tune::layout::KeyGrid;
 
 fn main() {
     let mut args = env::args();
@@ -30,7 +32,7 @@ pub fn print_hex_keyboard(num_steps_per_octave: u16) {
         );
         println!();
 
-        let mos = layout.mos().coprime();
+        let keyboard = IsomorphicKeyboard::from_mos(KeyGrid::Hex, layout.mos().coprime());
 
         for y in -10i16..=10 {
             let div = y.div_euclid(2);
@@ -41,7 +43,8 @@ pub fn print_hex_keyboard(num_steps_per_octave: u16) {
             for x in 0..20 {
                 print!(
                     "{:>4}",
-                    mos.get_key(x - div, y)
+                    keyboard
+                        .get_key(x - div, y)
                         .rem_euclid(i32::from(num_steps_per_octave)),
                 );
             }
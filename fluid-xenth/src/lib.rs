@@ -316,6 +316,11 @@ impl TunableSynth for TunableFluid {
         Ok(())
     }
 
+    fn channel_attr(&mut self, channel: usize, mut command: ChannelCommand) -> SendCommandResult {
+        let channel = self.get_channel(channel);
+        self.send_command(move |s| command(s, channel))
+    }
+
     fn global_attr(&mut self, mut command: ChannelCommand) -> SendCommandResult {
         let channels = (self.get_channel(0)..).take(self.polyphony);
         self.send_command(move |s| {
@@ -325,6 +330,10 @@ impl TunableSynth for TunableFluid {
             Ok(())
         })
     }
+
+    fn note_velocity(&self, velocity: &u8) -> u8 {
+        *velocity
+    }
 }
 
 impl TunableFluid {
@@ -580,6 +580,9 @@ fn handle_pipeline_events(
                         Some(TuningMethod::Octave2Rt) => "Scale/Octave Tuning (2-Byte) (real-time)",
                         Some(TuningMethod::ChannelFineTuning) => "Channel Fine Tuning",
                         Some(TuningMethod::PitchBend) => "Pitch Bend",
+                        Some(TuningMethod::Mpe) => "MIDI Polyphonic Expression",
+                        Some(TuningMethod::Midi2PitchAttribute) => "MIDI 2.0 Pitch Attribute",
+                        Some(TuningMethod::Midi2PerNotePitch) => "MIDI 2.0 Per-Note Pitch",
                         None => "Warning: Tuning channels exceeded! Change tuning mode.",
                     },
                 );
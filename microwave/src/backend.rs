@@ -4,6 +4,7 @@ use serde::Serialize;
 use tune::pitch::Pitch;
 use tune::scala::KbmRoot;
 use tune::scala::Scl;
+use tune::tuner::AdaptiveOptions;
 
 use crate::toggle::Direction;
 
@@ -18,6 +19,8 @@ pub trait Backend<K>: Send {
 
     fn set_no_tuning(&mut self);
 
+    fn set_adaptive_tuning(&mut self, options: AdaptiveOptions);
+
     fn request_status(&mut self);
 
     fn start(&mut self, key_id: K, degree: i32, pitch: Pitch, velocity: u8);
@@ -73,6 +76,8 @@ impl<K, E: From<M> + Send, M: Send + Clone> Backend<K> for IdleBackend<E, M> {
 
     fn set_no_tuning(&mut self) {}
 
+    fn set_adaptive_tuning(&mut self, _options: AdaptiveOptions) {}
+
     fn request_status(&mut self) {
         self.events.send(self.message.clone().into()).unwrap();
     }
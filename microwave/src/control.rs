@@ -2,18 +2,24 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 use serde::Serialize;
+use tune::midi::ChannelMessage;
+use tune::midi::ChannelMessageType;
+use tune::midi::ControlChangeDecoder;
+use tune::midi::ControlEventType;
 
 use crate::magnetron::source::StorageAccess;
 
 #[derive(Clone)]
 pub struct LiveParameterMapper {
     ccn_mapping: HashMap<LiveParameter, u8>,
+    decoder: ControlChangeDecoder,
 }
 
 impl LiveParameterMapper {
     pub fn new() -> Self {
         Self {
             ccn_mapping: HashMap::new(),
+            decoder: ControlChangeDecoder::new(),
         }
     }
 
@@ -31,6 +37,20 @@ impl LiveParameterMapper {
             .filter_map(move |(&parameter, &ccn)| (ccn == controller).then_some(parameter))
             .collect()
     }
+
+    /// Like [`LiveParameterMapper::resolve_ccn`] but data entry messages addressing an RPN/NRPN are not mapped.
+    pub fn resolve_control_change(&mut self, message: ChannelMessage) -> Vec<LiveParameter> {
+        let ChannelMessageType::ControlChange { controller, .. } = message.message_type() else {
+            return Vec::new();
+        };
+
+        match self.decoder.decode(message).map(|event| event.event_type) {
+            Some(
+                ControlEventType::Controller7Bit { .. } | ControlEventType::Controller14Bit { .. },
+            ) => self.resolve_ccn(controller),
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -17,6 +17,8 @@ use serde::Serialize;
 use tune::pitch::Pitch;
 use tune::scala::KbmRoot;
 use tune::scala::Scl;
+use tune::tuner::AdaptiveOptions;
+use tune::tuner::PoolingMode;
 use tune_cli::shared::error::ResultExt;
 
 use crate::backend::Backend;
@@ -78,7 +80,10 @@ impl<A: AutomatableParam> FluidSpec<A> {
 
         let mut backend = FluidBackend {
             note_input: self.note_input,
-            backend: TunableBackend::new(xenth_control.into_iter().next().unwrap()),
+            backend: TunableBackend::new(
+                xenth_control.into_iter().next().unwrap(),
+                PoolingMode::Stop,
+            ),
             soundfont_location: self.soundfont_location.to_owned().into(),
             events: events.clone(),
         };
@@ -128,6 +133,10 @@ impl<K: Copy + Eq + Hash + Send + Debug, E: From<FluidEvent> + Send + 'static> B
         self.backend.set_no_tuning();
     }
 
+    fn set_adaptive_tuning(&mut self, options: AdaptiveOptions) {
+        self.backend.set_adaptive_tuning(options);
+    }
+
     fn request_status(&mut self) {
         let is_tuned = self.backend.is_tuned();
         let soundfont_location = self.soundfont_location.clone();
@@ -6,6 +6,7 @@ use flume::Sender;
 use rand::SeedableRng;
 use rand::rngs::SmallRng;
 use rand::seq::SliceRandom;
+use tune::layout::Controller;
 use tune::math;
 use tune_cli::shared::midi;
 use tune_cli::shared::midi::MidiResult;
@@ -118,91 +119,21 @@ pub struct LumatoneKey {
 
 impl LumatoneKey {
     pub fn iter_all() -> impl Iterator<Item = Self> {
-        (0..5).flat_map(move |board_index| {
-            (0..u8::try_from(KEY_COORDS.len()).unwrap()).map(move |key_index| LumatoneKey {
-                board_index,
-                key_index,
-            })
+        Controller::Lumatone.keys().map(|key| LumatoneKey {
+            board_index: key.section,
+            key_index: key.index,
         })
     }
 
     pub fn isomorphic_coord(&self) -> (i16, i16) {
-        /// For symmetry reasons, middle D is used as the origin of the isomorphic layout.
-        const ORIGIN: (u8, u8) = KEY_COORDS[20];
-
-        let (x, y) = KEY_COORDS[usize::from(self.key_index) % KEY_COORDS.len()];
-        (
-            5 * (i16::from(self.board_index) - 2) + i16::from(x) - i16::from(ORIGIN.0),
-            2 * (i16::from(self.board_index) - 2) + i16::from(y) - i16::from(ORIGIN.1),
-        )
+        /// Boards beyond the 5 physical boards (i.e. MIDI channels > 4) continue the board pattern.
+        const MIDDLE_BOARD: u8 = 2;
+
+        let (_, num_keys) = Controller::Lumatone.dimensions();
+        let (x, y) = Controller::Lumatone
+            .get_coord(MIDDLE_BOARD, self.key_index % num_keys)
+            .unwrap();
+        let board_offset = i16::from(self.board_index) - i16::from(MIDDLE_BOARD);
+        (x + 5 * board_offset, y + 2 * board_offset)
     }
 }
-
-const KEY_COORDS: [(u8, u8); 56] = [
-    (4, 0),
-    (5, 0),
-    // --
-    (4, 1),
-    (5, 1),
-    (6, 1),
-    (7, 1),
-    (8, 1),
-    // --
-    (3, 2),
-    (4, 2),
-    (5, 2),
-    (6, 2),
-    (7, 2),
-    (8, 2),
-    // --
-    (3, 3),
-    (4, 3),
-    (5, 3),
-    (6, 3),
-    (7, 3),
-    (8, 3),
-    // --
-    (2, 4), // C
-    (3, 4), // D
-    (4, 4), // E
-    (5, 4), // F#
-    (6, 4), // G#
-    (7, 4), // A#
-    // --
-    (2, 5), // Db
-    (3, 5), // Eb
-    (4, 5), // F
-    (5, 5), // G
-    (6, 5), // A
-    (7, 5), // B
-    // --
-    (1, 6),
-    (2, 6),
-    (3, 6),
-    (4, 6),
-    (5, 6),
-    (6, 6),
-    // --
-    (1, 7),
-    (2, 7),
-    (3, 7),
-    (4, 7),
-    (5, 7),
-    (6, 7),
-    // --
-    (0, 8),
-    (1, 8),
-    (2, 8),
-    (3, 8),
-    (4, 8),
-    (5, 8),
-    // --
-    (1, 9),
-    (2, 9),
-    (3, 9),
-    (4, 9),
-    (5, 9),
-    // --
-    (3, 10),
-    (4, 10),
-];
@@ -0,0 +1,240 @@
+use std::f64::consts::TAU;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use magnetron::automation::Automatable;
+use magnetron::automation::AutomatableParam;
+use magnetron::automation::AutomatableSlice;
+use magnetron::automation::Automated;
+use magnetron::automation::AutomationFactory;
+use magnetron::buffer::BufferIndex;
+use magnetron::stage::Stage;
+use serde::Deserialize;
+use serde::Serialize;
+use tune::pitch::Pitch;
+use tune::pitch::Ratio;
+use tune::scala::KbmRoot;
+use tune::scala::Scl;
+use tune::tuning::Scale;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdditiveSpec<A> {
+    pub frequency: A,
+    pub partials: Vec<PartialSpec<A>>,
+    /// Temper the partials to the current scale s.t. the overtones land on scale degrees. Has no effect in continuous or adaptive tuning mode.
+    #[serde(default)]
+    pub tempered: bool,
+    #[serde(skip)]
+    pub tuning: ActiveTuning,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartialSpec<A> {
+    /// Frequency ratio wrt. the fundamental
+    pub ratio: A,
+    pub amplitude: A,
+    /// Exponential decay rate (1/s)
+    pub decay: Option<A>,
+}
+
+impl<A: AutomatableParam> Automatable<A> for PartialSpec<A> {
+    type Output = (A::Automated, A::Automated, Option<A::Automated>);
+
+    fn create(&self, factory: &mut AutomationFactory<A>) -> Self::Output {
+        factory.automate((&self.ratio, &self.amplitude, &self.decay))
+    }
+}
+
+/// The fixed tuning that was active when a voice was started.
+#[derive(Clone, Default)]
+pub struct ActiveTuning(Option<Arc<(Scl, KbmRoot)>>);
+
+impl Debug for ActiveTuning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(tuning) => write!(f, "{}", tuning.0.description()),
+            None => write!(f, "No tuning"),
+        }
+    }
+}
+
+impl<A> AdditiveSpec<A> {
+    pub fn set_tuning(&mut self, tuning: Option<&Arc<(Scl, KbmRoot)>>) {
+        self.tuning = ActiveTuning(tuning.cloned());
+    }
+}
+
+impl<A: AutomatableParam> AdditiveSpec<A> {
+    pub fn create(
+        &self,
+        factory: &mut AutomationFactory<A>,
+        out_buffer: BufferIndex,
+        out_level: Option<&A>,
+    ) -> Stage<A> {
+        let tuning = self.tuning.0.clone().filter(|_| self.tempered);
+
+        let mut root_pitch = None;
+        let mut partials = vec![PartialState::new(); self.partials.len()];
+        factory
+            .automate((
+                out_level,
+                &self.frequency,
+                AutomatableSlice::new(&self.partials),
+            ))
+            .into_stage(move |buffers, (out_level, frequency, partial_params)| {
+                let sample_width_secs = buffers.sample_width_secs();
+
+                // The root is determined once per voice s.t. pitch bends do not cause the partials to jump.
+                let root_pitch = *root_pitch.get_or_insert_with(|| {
+                    tuning
+                        .as_deref()
+                        .map(|tuning| nearest_scale_pitch(tuning, Pitch::from_hz(frequency)))
+                });
+
+                for (partial, &(ratio, amplitude, decay)) in partials.iter_mut().zip(partial_params)
+                {
+                    let ratio = match (tuning.as_deref(), root_pitch) {
+                        (Some(tuning), Some(root_pitch)) => match partial.tempered_ratio {
+                            Some((untempered, tempered)) if untempered == ratio => tempered,
+                            _ => {
+                                let tempered = temper_ratio(tuning, root_pitch, ratio);
+                                partial.tempered_ratio = Some((ratio, tempered));
+                                tempered
+                            }
+                        },
+                        _ => ratio,
+                    };
+
+                    partial.d_phase = frequency * ratio * sample_width_secs;
+                    // Partials above the Nyquist frequency are muted to prevent aliasing
+                    partial.amplitude = if partial.d_phase.abs() < 0.5 {
+                        amplitude
+                    } else {
+                        0.0
+                    };
+                    partial.decay_factor = (-decay.unwrap_or_default() * sample_width_secs).exp();
+                }
+
+                buffers.read_0_write_1(out_buffer, out_level, || {
+                    partials.iter_mut().map(PartialState::next_sample).sum()
+                })
+            })
+    }
+}
+
+#[derive(Clone)]
+struct PartialState {
+    phase: f64,
+    d_phase: f64,
+    amplitude: f64,
+    decay_factor: f64,
+    envelope: f64,
+    /// The last untempered ratio and its tempered counterpart. Tempering is only recomputed when the ratio changes.
+    tempered_ratio: Option<(f64, f64)>,
+}
+
+impl PartialState {
+    fn new() -> Self {
+        Self {
+            phase: 0.0,
+            d_phase: 0.0,
+            amplitude: 0.0,
+            decay_factor: 1.0,
+            envelope: 1.0,
+            tempered_ratio: None,
+        }
+    }
+
+    fn next_sample(&mut self) -> f64 {
+        let signal = (self.phase * TAU).sin() * self.amplitude * self.envelope;
+
+        self.envelope *= self.decay_factor;
+        self.phase = (self.phase + self.d_phase).rem_euclid(1.0);
+
+        signal
+    }
+}
+
+fn nearest_scale_pitch(tuning: &(Scl, KbmRoot), pitch: Pitch) -> Pitch {
+    let degree = tuning.find_by_pitch_sorted(pitch).approx_value;
+
+    // Pitches that are very close to a period boundary can be assigned to an adjacent degree due to rounding errors
+    (degree - 1..=degree + 1)
+        .map(|degree| tuning.sorted_pitch_of(degree))
+        .min_by(|a, b| {
+            let deviation = |scale_pitch| {
+                Ratio::between_pitches(pitch, scale_pitch)
+                    .as_octaves()
+                    .abs()
+            };
+            deviation(*a).total_cmp(&deviation(*b))
+        })
+        .unwrap()
+}
+
+/// Replaces `ratio` with the closest interval between `root_pitch` and any other scale degree.
+fn temper_ratio(tuning: &(Scl, KbmRoot), root_pitch: Pitch, ratio: f64) -> f64 {
+    if ratio <= 0.0 {
+        return ratio;
+    }
+
+    let tempered_pitch = nearest_scale_pitch(tuning, root_pitch * Ratio::from_float(ratio));
+    Ratio::between_pitches(root_pitch, tempered_pitch).as_float()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use tune::note::Note;
+
+    use super::*;
+
+    #[test]
+    fn temper_partials_to_scale() {
+        let tuning = (
+            Scl::builder().push_cents(100.0).build().unwrap(),
+            KbmRoot::from(Note::from_midi_number(60)),
+        );
+
+        let root_pitch = nearest_scale_pitch(&tuning, Pitch::from_hz(445.0));
+        assert_approx_eq!(root_pitch.as_hz(), 440.0);
+
+        assert_approx_eq!(temper_ratio(&tuning, root_pitch, 1.0), 1.0);
+        assert_approx_eq!(temper_ratio(&tuning, root_pitch, 2.0), 2.0);
+        assert_approx_eq!(temper_ratio(&tuning, root_pitch, 3.0), 2.996614);
+        assert_approx_eq!(temper_ratio(&tuning, root_pitch, 5.0), 5.039684);
+        assert_approx_eq!(temper_ratio(&tuning, root_pitch, 0.0), 0.0);
+
+        let tuning = (
+            Scl::builder().push_cents(1200.0 / 7.0).build().unwrap(),
+            KbmRoot::from(Note::from_midi_number(60)),
+        );
+        let root_pitch = nearest_scale_pitch(&tuning, Pitch::from_hz(261.626));
+
+        assert_approx_eq!(
+            temper_ratio(&tuning, root_pitch, 3.0),
+            2.0 * 2f64.powf(4.0 / 7.0)
+        );
+        assert_approx_eq!(
+            temper_ratio(&tuning, root_pitch, 5.0),
+            4.0 * 2f64.powf(2.0 / 7.0)
+        );
+    }
+
+    #[test]
+    fn decay_partials() {
+        let mut partial = PartialState {
+            phase: 0.25,
+            d_phase: 0.5,
+            amplitude: 0.5,
+            decay_factor: 0.5,
+            ..PartialState::new()
+        };
+
+        assert_approx_eq!(partial.next_sample(), 0.5);
+        assert_approx_eq!(partial.next_sample(), -0.25);
+        assert_approx_eq!(partial.next_sample(), 0.125);
+    }
+}
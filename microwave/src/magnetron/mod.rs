@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use magnetron::automation::AutomatableParam;
 use magnetron::automation::Automated;
 use magnetron::automation::AutomationFactory;
@@ -5,24 +7,33 @@ use magnetron::buffer::BufferIndex;
 use magnetron::stage::Stage;
 use serde::Deserialize;
 use serde::Serialize;
+use tune::scala::KbmRoot;
+use tune::scala::Scl;
+use tune_cli::CliResult;
 
+use crate::magnetron::additive::AdditiveSpec;
 use crate::magnetron::effects::EffectSpec;
 use crate::magnetron::filter::FilterSpec;
 use crate::magnetron::noise::NoiseSpec;
 use crate::magnetron::oscillator::ModOscillatorSpec;
 use crate::magnetron::oscillator::OscillatorSpec;
+use crate::magnetron::sampler::SamplerSpec;
 use crate::magnetron::waveguide::WaveguideSpec;
+use crate::magnetron::wavetable::WavetableSpec;
 
 mod util;
 
+pub mod additive;
 pub mod effects;
 pub mod envelope;
 pub mod filter;
 pub mod noise;
 pub mod oscillator;
+pub mod sampler;
 pub mod source;
 pub mod waveform;
 pub mod waveguide;
+pub mod wavetable;
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct FragmentSpec<A> {
@@ -39,6 +50,28 @@ pub enum StageType<A> {
     StereoProcessor(StereoProcessorSpec<A>),
 }
 
+impl<A> StageType<A> {
+    /// Loads all files referenced by the stage s.t. the stage can be created synchronously.
+    pub async fn load_files(&mut self) -> CliResult {
+        match self {
+            StageType::Generator(spec) => spec.load_files().await,
+            StageType::Processor(_)
+            | StageType::MergeProcessor(_)
+            | StageType::StereoProcessor(_) => Ok(()),
+        }
+    }
+
+    /// Makes the given fixed tuning available to all stages that depend on the current scale.
+    pub fn set_tuning(&mut self, tuning: Option<&Arc<(Scl, KbmRoot)>>) {
+        match self {
+            StageType::Generator(spec) => spec.set_tuning(tuning),
+            StageType::Processor(_)
+            | StageType::MergeProcessor(_)
+            | StageType::StereoProcessor(_) => {}
+        }
+    }
+}
+
 impl<A: AutomatableParam> StageType<A> {
     pub fn create(&self, factory: &mut AutomationFactory<A>) -> Stage<A> {
         match self {
@@ -58,6 +91,16 @@ pub struct GeneratorSpec<A> {
     pub generator_type: GeneratorType<A>,
 }
 
+impl<A> GeneratorSpec<A> {
+    pub async fn load_files(&mut self) -> CliResult {
+        self.generator_type.load_files().await
+    }
+
+    pub fn set_tuning(&mut self, tuning: Option<&Arc<(Scl, KbmRoot)>>) {
+        self.generator_type.set_tuning(tuning);
+    }
+}
+
 impl<A: AutomatableParam> GeneratorSpec<A> {
     pub fn create(&self, factory: &mut AutomationFactory<A>) -> Stage<A> {
         let out_buffer = BufferIndex::Internal(self.out_buffer);
@@ -149,6 +192,31 @@ pub fn to_out_buffer_index(out_buffer: usize) -> BufferIndex {
 pub enum GeneratorType<A> {
     Oscillator(OscillatorSpec<A>),
     Noise(NoiseSpec),
+    Wavetable(WavetableSpec<A>),
+    Sampler(SamplerSpec<A>),
+    Additive(AdditiveSpec<A>),
+}
+
+impl<A> GeneratorType<A> {
+    async fn load_files(&mut self) -> CliResult {
+        match self {
+            GeneratorType::Oscillator(_) | GeneratorType::Noise(_) | GeneratorType::Additive(_) => {
+                Ok(())
+            }
+            GeneratorType::Wavetable(spec) => spec.load_files().await,
+            GeneratorType::Sampler(spec) => spec.load_files().await,
+        }
+    }
+
+    fn set_tuning(&mut self, tuning: Option<&Arc<(Scl, KbmRoot)>>) {
+        match self {
+            GeneratorType::Additive(spec) => spec.set_tuning(tuning),
+            GeneratorType::Oscillator(_)
+            | GeneratorType::Noise(_)
+            | GeneratorType::Wavetable(_)
+            | GeneratorType::Sampler(_) => {}
+        }
+    }
 }
 
 impl<A: AutomatableParam> GeneratorType<A> {
@@ -161,6 +229,9 @@ impl<A: AutomatableParam> GeneratorType<A> {
         match self {
             GeneratorType::Oscillator(spec) => spec.create(factory, out_buffer, out_level),
             GeneratorType::Noise(spec) => spec.create(factory, out_buffer, out_level),
+            GeneratorType::Wavetable(spec) => spec.create(factory, out_buffer, out_level),
+            GeneratorType::Sampler(spec) => spec.create(factory, out_buffer, out_level),
+            GeneratorType::Additive(spec) => spec.create(factory, out_buffer, out_level),
         }
     }
 }
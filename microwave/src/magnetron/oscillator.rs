@@ -17,35 +17,109 @@ pub enum OscillatorType {
     Triangle,
     Square,
     Sawtooth,
+    BandLimitedTriangle,
+    BandLimitedSquare,
+    BandLimitedSawtooth,
 }
 
 impl OscillatorType {
     pub fn run_oscillator<F: OscillatorRunner>(&self, mut oscillator_runner: F) -> F::Result {
         match self {
             OscillatorType::Sin => {
-                oscillator_runner.apply_oscillator_fn(|phase: f64| (phase * TAU).sin())
+                oscillator_runner.apply_oscillator_fn(|phase: f64, _| (phase * TAU).sin())
             }
-            OscillatorType::Sin3 => oscillator_runner.apply_oscillator_fn(|phase: f64| {
+            OscillatorType::Sin3 => oscillator_runner.apply_oscillator_fn(|phase: f64, _| {
                 let sin = (phase * TAU).sin();
                 sin * sin * sin
             }),
-            OscillatorType::Triangle => oscillator_runner.apply_oscillator_fn(|phase: f64| {
-                (((0.75 + phase).fract() - 0.5).abs() - 0.25) * 4.0
-            }),
-            OscillatorType::Square => oscillator_runner
-                .apply_oscillator_fn(|phase: f64| if phase < 0.5 { 1.0 } else { -1.0 }),
-            OscillatorType::Sawtooth => oscillator_runner
-                .apply_oscillator_fn(|phase: f64| ((0.5 + phase).fract() - 0.5) * 2.0),
+            OscillatorType::Triangle => {
+                oscillator_runner.apply_oscillator_fn(|phase: f64, _| triangle(phase))
+            }
+            OscillatorType::Square => {
+                oscillator_runner.apply_oscillator_fn(|phase: f64, _| square(phase))
+            }
+            OscillatorType::Sawtooth => {
+                oscillator_runner.apply_oscillator_fn(|phase: f64, _| sawtooth(phase))
+            }
+            OscillatorType::BandLimitedTriangle => {
+                oscillator_runner.apply_oscillator_fn(|phase: f64, d_phase: f64| {
+                    let dt = d_phase.abs().min(0.5);
+                    // The slope changes by -8 at phase 0.25 and by +8 at phase 0.75
+                    triangle(phase)
+                        + 8.0
+                            * dt
+                            * (poly_blamp((phase + 0.25).fract(), dt)
+                                - poly_blamp((phase + 0.75).fract(), dt))
+                })
+            }
+            OscillatorType::BandLimitedSquare => {
+                oscillator_runner.apply_oscillator_fn(|phase: f64, d_phase: f64| {
+                    let dt = d_phase.abs().min(0.5);
+                    // The signal jumps by +2 at phase 0.0 and by -2 at phase 0.5
+                    square(phase)
+                        + 2.0 * (poly_blep(phase, dt) - poly_blep((phase + 0.5).fract(), dt))
+                })
+            }
+            OscillatorType::BandLimitedSawtooth => {
+                oscillator_runner.apply_oscillator_fn(|phase: f64, d_phase: f64| {
+                    let dt = d_phase.abs().min(0.5);
+                    // The signal jumps by -2 at phase 0.5
+                    sawtooth(phase) - 2.0 * poly_blep((phase + 0.5).fract(), dt)
+                })
+            }
         }
     }
 }
 
+fn triangle(phase: f64) -> f64 {
+    (((0.75 + phase).fract() - 0.5).abs() - 0.25) * 4.0
+}
+
+fn square(phase: f64) -> f64 {
+    if phase < 0.5 { 1.0 } else { -1.0 }
+}
+
+fn sawtooth(phase: f64) -> f64 {
+    ((0.5 + phase).fract() - 0.5) * 2.0
+}
+
+/// Difference between a band-limited and a naive unit step (PolyBLEP).
+///
+/// `t` is the phase elapsed since the step and `dt` is the phase increment per sample.
+fn poly_blep(t: f64, dt: f64) -> f64 {
+    if t < dt {
+        let x = 1.0 - t / dt;
+        -x * x / 2.0
+    } else if t > 1.0 - dt {
+        let x = 1.0 + (t - 1.0) / dt;
+        x * x / 2.0
+    } else {
+        0.0
+    }
+}
+
+/// Difference between a band-limited and a naive unit slope change per sample (PolyBLAMP).
+///
+/// `t` is the phase elapsed since the slope change and `dt` is the phase increment per sample.
+fn poly_blamp(t: f64, dt: f64) -> f64 {
+    if t < dt {
+        let x = 1.0 - t / dt;
+        x * x * x / 6.0
+    } else if t > 1.0 - dt {
+        let x = 1.0 + (t - 1.0) / dt;
+        x * x * x / 6.0
+    } else {
+        0.0
+    }
+}
+
 pub trait OscillatorRunner {
     type Result;
 
+    /// Creates a runner for `oscillator_fn(phase, d_phase)` where `d_phase` is the phase increment per invocation.
     fn apply_oscillator_fn(
         &mut self,
-        oscillator_fn: impl FnMut(f64) -> f64 + Send + 'static,
+        oscillator_fn: impl FnMut(f64, f64) -> f64 + Send + 'static,
     ) -> Self::Result;
 }
 
@@ -119,7 +193,7 @@ impl<A: AutomatableParam> OscillatorRunner for StageOscillatorRunner<'_, A> {
 
     fn apply_oscillator_fn(
         &mut self,
-        mut oscillator_fn: impl FnMut(f64) -> f64 + Send + 'static,
+        mut oscillator_fn: impl FnMut(f64, f64) -> f64 + Send + 'static,
     ) -> Self::Result {
         let out_buffer = self.out_buffer;
 
@@ -128,7 +202,7 @@ impl<A: AutomatableParam> OscillatorRunner for StageOscillatorRunner<'_, A> {
                 let mut phase = 0.0;
                 self.apply_modulation_fn(move |buffers, out_level, d_phase| {
                     buffers.read_0_write_1(out_buffer, out_level, || {
-                        let signal = oscillator_fn(phase);
+                        let signal = oscillator_fn(phase, d_phase);
                         phase = (phase + d_phase).rem_euclid(1.0);
                         signal
                     })
@@ -136,10 +210,13 @@ impl<A: AutomatableParam> OscillatorRunner for StageOscillatorRunner<'_, A> {
             }
             &Some((mod_buffer, Modulation::ByPhase)) => {
                 let mut phase = 0.0;
+                let mut last_s = 0.0;
                 self.apply_modulation_fn(move |buffers, out_level, d_phase| {
                     buffers.read_1_write_1(mod_buffer, out_buffer, out_level, |s| {
-                        let signal = oscillator_fn((phase + s).rem_euclid(1.0));
+                        let signal =
+                            oscillator_fn((phase + s).rem_euclid(1.0), d_phase + s - last_s);
                         phase = (phase + d_phase).rem_euclid(1.0);
+                        last_s = s;
                         signal
                     })
                 })
@@ -149,8 +226,9 @@ impl<A: AutomatableParam> OscillatorRunner for StageOscillatorRunner<'_, A> {
                 self.apply_modulation_fn(move |buffers, out_level, d_phase| {
                     let sample_width_secs = buffers.sample_width_secs();
                     buffers.read_1_write_1(mod_buffer, out_buffer, out_level, |s| {
-                        let signal = oscillator_fn(phase);
-                        phase = (phase + d_phase + s * sample_width_secs).rem_euclid(1.0);
+                        let d_phase = d_phase + s * sample_width_secs;
+                        let signal = oscillator_fn(phase, d_phase);
+                        phase = (phase + d_phase).rem_euclid(1.0);
                         signal
                     })
                 })
@@ -188,16 +266,19 @@ mod tests {
 
     use super::*;
 
-    struct TestOscillatorRunner;
+    struct TestOscillatorRunner {
+        d_phase: f64,
+    }
 
     impl OscillatorRunner for TestOscillatorRunner {
         type Result = Box<dyn FnMut(f64) -> f64 + Send + 'static>;
 
         fn apply_oscillator_fn(
             &mut self,
-            oscillator_fn: impl FnMut(f64) -> f64 + Send + 'static,
+            mut oscillator_fn: impl FnMut(f64, f64) -> f64 + Send + 'static,
         ) -> Self::Result {
-            Box::new(oscillator_fn)
+            let d_phase = self.d_phase;
+            Box::new(move |phase| oscillator_fn(phase, d_phase))
         }
     }
 
@@ -205,11 +286,14 @@ mod tests {
     fn oscillator_correctness() {
         let eps = 1e-10;
 
-        let mut sin = OscillatorType::Sin.run_oscillator(TestOscillatorRunner);
-        let mut sin3 = OscillatorType::Sin3.run_oscillator(TestOscillatorRunner);
-        let mut triangle = OscillatorType::Triangle.run_oscillator(TestOscillatorRunner);
-        let mut square = OscillatorType::Square.run_oscillator(TestOscillatorRunner);
-        let mut sawtooth = OscillatorType::Sawtooth.run_oscillator(TestOscillatorRunner);
+        let mut sin = OscillatorType::Sin.run_oscillator(TestOscillatorRunner { d_phase: 0.0 });
+        let mut sin3 = OscillatorType::Sin3.run_oscillator(TestOscillatorRunner { d_phase: 0.0 });
+        let mut triangle =
+            OscillatorType::Triangle.run_oscillator(TestOscillatorRunner { d_phase: 0.0 });
+        let mut square =
+            OscillatorType::Square.run_oscillator(TestOscillatorRunner { d_phase: 0.0 });
+        let mut sawtooth =
+            OscillatorType::Sawtooth.run_oscillator(TestOscillatorRunner { d_phase: 0.0 });
 
         assert_approx_eq!(sin(0.0 / 8.0), 0.0);
         assert_approx_eq!(sin(1.0 / 8.0), (1.0f64 / 2.0).sqrt());
@@ -259,4 +343,49 @@ mod tests {
         assert_approx_eq!(sawtooth(6.0 / 8.0), -0.5);
         assert_approx_eq!(sawtooth(7.0 / 8.0), -0.25);
     }
+
+    #[test]
+    fn band_limited_oscillator_correctness() {
+        let eps = 1e-10;
+
+        let runner = || TestOscillatorRunner { d_phase: 0.1 };
+        let mut triangle = OscillatorType::BandLimitedTriangle.run_oscillator(runner());
+        let mut square = OscillatorType::BandLimitedSquare.run_oscillator(runner());
+        let mut sawtooth = OscillatorType::BandLimitedSawtooth.run_oscillator(runner());
+
+        // Corners are rounded
+        assert_approx_eq!(triangle(0.0 / 8.0), 0.0);
+        assert_approx_eq!(triangle(1.0 / 8.0), 0.5);
+        assert_approx_eq!(triangle(2.0 / 8.0), 1.0 - 0.8 / 6.0);
+        assert_approx_eq!(triangle(3.0 / 8.0), 0.5);
+        assert_approx_eq!(triangle(4.0 / 8.0), 0.0);
+        assert_approx_eq!(triangle(5.0 / 8.0), -0.5);
+        assert_approx_eq!(triangle(6.0 / 8.0), -1.0 + 0.8 / 6.0);
+        assert_approx_eq!(triangle(7.0 / 8.0), -0.5);
+
+        // Steps are smoothed out
+        assert_approx_eq!(square(0.0 / 8.0), 0.0);
+        assert_approx_eq!(square(0.5 / 8.0), 1.0 - 0.375 * 0.375);
+        assert_approx_eq!(square(2.0 / 8.0), 1.0);
+        assert_approx_eq!(square(4.0 / 8.0 - eps), 0.0);
+        assert_approx_eq!(square(4.0 / 8.0), 0.0);
+        assert_approx_eq!(square(6.0 / 8.0), -1.0);
+        assert_approx_eq!(square(8.0 / 8.0 - eps), 0.0);
+
+        assert_approx_eq!(sawtooth(0.0 / 8.0), 0.0);
+        assert_approx_eq!(sawtooth(2.0 / 8.0), 0.5);
+        assert_approx_eq!(sawtooth(4.0 / 8.0 - eps), 0.0);
+        assert_approx_eq!(sawtooth(4.0 / 8.0), 0.0);
+        assert_approx_eq!(sawtooth(6.0 / 8.0), -0.5);
+
+        // Without phase increment, the naive waveforms are produced
+        let runner = || TestOscillatorRunner { d_phase: 0.0 };
+        let mut square = OscillatorType::BandLimitedSquare.run_oscillator(runner());
+        let mut sawtooth = OscillatorType::BandLimitedSawtooth.run_oscillator(runner());
+
+        assert_approx_eq!(square(0.0 / 8.0), 1.0);
+        assert_approx_eq!(square(4.0 / 8.0), -1.0);
+        assert_approx_eq!(sawtooth(4.0 / 8.0 - eps), 1.0);
+        assert_approx_eq!(sawtooth(4.0 / 8.0), -1.0);
+    }
 }
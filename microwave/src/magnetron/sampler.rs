@@ -0,0 +1,346 @@
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+
+use hound::WavReader;
+use magnetron::automation::AutomatableParam;
+use magnetron::automation::Automated;
+use magnetron::automation::AutomationFactory;
+use magnetron::buffer::BufferIndex;
+use magnetron::stage::Stage;
+use serde::Deserialize;
+use serde::Serialize;
+use tune_cli::CliError;
+use tune_cli::CliResult;
+use tune_cli::shared::error::ResultExt;
+
+use crate::magnetron::util;
+use crate::portable;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SamplerSpec<A> {
+    /// YAML file describing the zones of the multisample. Sample locations are resolved relative to this file.
+    pub sample_map_location: String,
+    pub frequency: A,
+    /// Velocity ranging from 0.0 to 1.0. If not specified, the velocity ranges of the zones are ignored.
+    pub velocity: Option<A>,
+    #[serde(default)]
+    pub interpolation: SampleInterpolation,
+    #[serde(skip)]
+    pub sample_map: LoadedSampleMap,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub enum SampleInterpolation {
+    Nearest,
+    #[default]
+    Linear,
+    Cubic,
+}
+
+#[derive(Clone, Default)]
+pub struct LoadedSampleMap(Option<Arc<Vec<SampleZone>>>);
+
+impl Debug for LoadedSampleMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(zones) => write!(f, "{} zones", zones.len()),
+            None => write!(f, "Not loaded"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct SampleMapSpec {
+    zones: Vec<SampleZoneSpec>,
+}
+
+/// Key and velocity ranges are given as MIDI values. Both bounds are inclusive, as in SFZ.
+#[derive(Deserialize, Serialize)]
+struct SampleZoneSpec {
+    sample_location: String,
+    /// Key at which the sample plays back at its original speed. Fractional values account for detuned recordings.
+    root_key: f64,
+    lo_key: Option<u8>,
+    hi_key: Option<u8>,
+    lo_vel: Option<u8>,
+    hi_vel: Option<u8>,
+    /// Loop points in samples. The loop is only active if both points are specified.
+    loop_start: Option<usize>,
+    loop_end: Option<usize>,
+}
+
+impl<A> SamplerSpec<A> {
+    pub async fn load_files(&mut self) -> CliResult {
+        let file = portable::read_file(&self.sample_map_location)
+            .await?
+            .ok_or_else(|| format!("Sample map file `{}` not found", self.sample_map_location))?;
+
+        let sample_map: SampleMapSpec = serde_yaml::from_reader(file)
+            .display_err::<CliError>("Could not deserialize sample map")?;
+
+        let base_dir = Path::new(&self.sample_map_location)
+            .parent()
+            .unwrap_or(Path::new(""));
+
+        let mut zones = Vec::new();
+        for zone_spec in sample_map.zones {
+            let sample_location = base_dir.join(&zone_spec.sample_location);
+            let sample_location = sample_location.to_string_lossy();
+
+            let file = portable::read_file(&sample_location)
+                .await?
+                .ok_or_else(|| format!("Sample file `{sample_location}` not found"))?;
+
+            let reader =
+                WavReader::new(file).display_err::<CliError>("Could not read sample file")?;
+            let sample_rate = f64::from(reader.spec().sample_rate);
+            let samples = util::read_first_channel(reader)
+                .display_err::<CliError>("Could not read sample file")?;
+
+            let zone =
+                SampleZone::new(&zone_spec, samples, sample_rate).map_err(|error_message| {
+                    format!("Invalid zone for sample `{sample_location}`: {error_message}")
+                })?;
+
+            zones.push(zone);
+        }
+
+        self.sample_map = LoadedSampleMap(Some(Arc::new(zones)));
+
+        Ok(())
+    }
+}
+
+impl<A: AutomatableParam> SamplerSpec<A> {
+    pub fn create(
+        &self,
+        factory: &mut AutomationFactory<A>,
+        out_buffer: BufferIndex,
+        out_level: Option<&A>,
+    ) -> Stage<A> {
+        let zones = self.sample_map.0.clone().unwrap_or_else(|| {
+            log::warn!("Sample map `{}` not loaded", self.sample_map_location);
+            Arc::default()
+        });
+        let interpolation = self.interpolation;
+
+        let mut selected_zone = None;
+        let mut position = 0.0;
+        factory
+            .automate((out_level, &self.frequency, &self.velocity))
+            .into_stage(move |buffers, (out_level, frequency, velocity)| {
+                // The zone is selected once per voice so that pitch bends do not cause the sample to switch.
+                let zone = *selected_zone.get_or_insert_with(|| {
+                    let index = select_zone(&zones, frequency, velocity);
+                    if index.is_none() {
+                        log::debug!("No sample zone for frequency {frequency:.3} Hz");
+                    }
+                    index
+                });
+
+                let Some(zone) = zone.map(|index| &zones[index]) else {
+                    return buffers.read_0_write_1(out_buffer, out_level, || 0.0);
+                };
+
+                let step =
+                    frequency / zone.root_pitch_hz * zone.sample_rate * buffers.sample_width_secs();
+
+                buffers.read_0_write_1(out_buffer, out_level, || {
+                    let signal = zone.sample(interpolation, position);
+                    position = zone.advance(position, step);
+                    signal
+                })
+            })
+    }
+}
+
+fn select_zone(zones: &[SampleZone], frequency: f64, velocity: Option<f64>) -> Option<usize> {
+    let key = 69.0 + 12.0 * (frequency / 440.0).log2();
+    let velocity = velocity.map(|velocity| (velocity * 127.0).round());
+
+    zones.iter().position(|zone| {
+        zone.key_range.contains(&key.round())
+            && velocity.is_none_or(|velocity| zone.vel_range.contains(&velocity))
+    })
+}
+
+struct SampleZone {
+    samples: Vec<f64>,
+    sample_rate: f64,
+    root_pitch_hz: f64,
+    key_range: RangeInclusive<f64>,
+    vel_range: RangeInclusive<f64>,
+    sample_loop: Option<(usize, usize)>,
+}
+
+impl SampleZone {
+    fn new(spec: &SampleZoneSpec, samples: Vec<f64>, sample_rate: f64) -> Result<Self, String> {
+        let sample_loop = match (spec.loop_start, spec.loop_end) {
+            (Some(loop_start), Some(loop_end)) => {
+                if loop_start >= loop_end || loop_end > samples.len() {
+                    return Err(format!(
+                        "Loop {loop_start}..{loop_end} does not fit into {} samples",
+                        samples.len()
+                    ));
+                }
+                Some((loop_start, loop_end))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            samples,
+            sample_rate,
+            root_pitch_hz: 440.0 * ((spec.root_key - 69.0) / 12.0).exp2(),
+            key_range: f64::from(spec.lo_key.unwrap_or(0))..=f64::from(spec.hi_key.unwrap_or(127)),
+            vel_range: f64::from(spec.lo_vel.unwrap_or(0))..=f64::from(spec.hi_vel.unwrap_or(127)),
+            sample_loop,
+        })
+    }
+
+    fn advance(&self, position: f64, step: f64) -> f64 {
+        let position = position + step;
+        match self.sample_loop {
+            Some((loop_start, loop_end)) if position >= loop_end as f64 => {
+                loop_start as f64 + (position - loop_start as f64) % (loop_end - loop_start) as f64
+            }
+            _ => position,
+        }
+    }
+
+    fn sample(&self, interpolation: SampleInterpolation, position: f64) -> f64 {
+        let index = position.floor();
+        let fract = position - index;
+        let index = index as isize;
+
+        match interpolation {
+            SampleInterpolation::Nearest => self.get(position.round() as isize),
+            SampleInterpolation::Linear => {
+                let (y0, y1) = (self.get(index), self.get(index + 1));
+                y0 + (y1 - y0) * fract
+            }
+            SampleInterpolation::Cubic => {
+                let (ym1, y0, y1, y2) = (
+                    self.get(index - 1),
+                    self.get(index),
+                    self.get(index + 1),
+                    self.get(index + 2),
+                );
+                // 4-point, 3rd-order Hermite interpolation
+                let c1 = 0.5 * (y1 - ym1);
+                let c2 = ym1 - 2.5 * y0 + 2.0 * y1 - 0.5 * y2;
+                let c3 = 0.5 * (y2 - ym1) + 1.5 * (y0 - y1);
+                ((c3 * fract + c2) * fract + c1) * fract + y0
+            }
+        }
+    }
+
+    /// Returns the sample at `index`, continuing into the loop if present. Outside of the sample, silence is returned.
+    fn get(&self, index: isize) -> f64 {
+        let Ok(mut index) = usize::try_from(index) else {
+            return 0.0;
+        };
+        if let Some((loop_start, loop_end)) = self.sample_loop
+            && index >= loop_end
+        {
+            index = loop_start + (index - loop_start) % (loop_end - loop_start);
+        }
+        self.samples.get(index).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    fn zone(spec: &str, samples: Vec<f64>) -> SampleZone {
+        SampleZone::new(&serde_yaml::from_str(spec).unwrap(), samples, 44100.0).unwrap()
+    }
+
+    #[test]
+    fn select_zone_by_key_and_velocity() {
+        let zones = [
+            zone(
+                "{ sample_location: a.wav, root_key: 60, hi_key: 63 }",
+                vec![],
+            ),
+            zone(
+                "{ sample_location: b.wav, root_key: 67, lo_key: 64, hi_vel: 63 }",
+                vec![],
+            ),
+            zone(
+                "{ sample_location: c.wav, root_key: 67, lo_key: 64, lo_vel: 64 }",
+                vec![],
+            ),
+        ];
+
+        assert_approx_eq!(zones[0].root_pitch_hz, 261.626, 1e-3);
+
+        assert_eq!(select_zone(&zones, 261.626, None), Some(0));
+        // 62.7 is rounded to 63
+        assert_eq!(select_zone(&zones, 306.0, Some(1.0)), Some(0));
+        // Upper bounds are inclusive
+        assert_eq!(select_zone(&zones, 311.127, None), Some(0));
+        assert_eq!(select_zone(&zones, 329.628, None), Some(1));
+        assert_eq!(select_zone(&zones, 392.0, None), Some(1));
+        assert_eq!(select_zone(&zones, 392.0, Some(0.5)), Some(2));
+        assert_eq!(select_zone(&zones, 392.0, Some(0.4)), Some(1));
+        assert_eq!(select_zone(&[], 392.0, None), None);
+    }
+
+    #[test]
+    fn interpolate_samples() {
+        let zone = zone(
+            "{ sample_location: a.wav, root_key: 60 }",
+            vec![0.0, 1.0, 0.0, -1.0],
+        );
+
+        assert_approx_eq!(zone.sample(SampleInterpolation::Nearest, 0.4), 0.0);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Nearest, 0.6), 1.0);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Linear, 0.25), 0.25);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Linear, 2.5), -0.5);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Cubic, 1.0), 1.0);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Cubic, 0.5), 0.5625);
+
+        // Silence outside of the sample
+        assert_approx_eq!(zone.sample(SampleInterpolation::Linear, 3.5), -0.5);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Linear, 4.5), 0.0);
+        assert_approx_eq!(zone.advance(3.5, 2.0), 5.5);
+    }
+
+    #[test]
+    fn play_sample_loop() {
+        let zone = zone(
+            "{ sample_location: a.wav, root_key: 60, loop_start: 1, loop_end: 3 }",
+            vec![0.0, 1.0, 2.0, 3.0],
+        );
+
+        assert_approx_eq!(zone.advance(1.5, 1.0), 2.5);
+        assert_approx_eq!(zone.advance(2.5, 1.0), 1.5);
+        assert_approx_eq!(zone.advance(2.5, 4.25), 2.75);
+        // The sample after the loop end is the loop start
+        assert_approx_eq!(zone.sample(SampleInterpolation::Linear, 2.5), 1.5);
+        assert_approx_eq!(zone.sample(SampleInterpolation::Nearest, 3.0), 1.0);
+    }
+
+    #[test]
+    fn reject_invalid_loops() {
+        let spec = serde_yaml::from_str(
+            "{ sample_location: a.wav, root_key: 60, loop_start: 2, loop_end: 5 }",
+        )
+        .unwrap();
+        assert!(SampleZone::new(&spec, vec![0.0; 4], 44100.0).is_err());
+
+        let spec = serde_yaml::from_str(
+            "{ sample_location: a.wav, root_key: 60, loop_start: 2, loop_end: 2 }",
+        )
+        .unwrap();
+        assert!(SampleZone::new(&spec, vec![0.0; 4], 44100.0).is_err());
+    }
+}
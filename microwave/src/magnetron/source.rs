@@ -324,7 +324,7 @@ impl<A: AutomatableParam> OscillatorRunner for LfSourceOscillatorRunner<'_, A> {
 
     fn apply_oscillator_fn(
         &mut self,
-        mut oscillator_fn: impl FnMut(f64) -> f64 + Send + 'static,
+        mut oscillator_fn: impl FnMut(f64, f64) -> f64 + Send + 'static,
     ) -> Self::Result {
         let mut last_phase = 0.0;
         let mut total_phase = 0.0;
@@ -339,8 +339,9 @@ impl<A: AutomatableParam> OscillatorRunner for LfSourceOscillatorRunner<'_, A> {
                     let phase = phase.unwrap_or_default();
                     total_phase = (total_phase + phase - last_phase).rem_euclid(1.0);
                     last_phase = phase;
-                    let signal = oscillator_fn(total_phase);
-                    total_phase += frequency * render_window_secs;
+                    let d_phase = frequency * render_window_secs;
+                    let signal = oscillator_fn(total_phase, d_phase);
+                    total_phase += d_phase;
                     baseline + signal * amplitude
                 },
             )
@@ -1,4 +1,8 @@
 use std::f64::consts::TAU;
+use std::io::Read;
+
+use hound::SampleFormat;
+use hound::WavReader;
 
 pub trait Interpolate {
     fn interpolate(left: Self, right: Self, interpolation: f64) -> Self;
@@ -311,6 +315,28 @@ impl AllPassDelay {
     }
 }
 
+/// Reads the first channel of a WAV file and converts it to `f64` samples in the range [-1.0, 1.0].
+pub fn read_first_channel<R: Read>(mut reader: WavReader<R>) -> Result<Vec<f64>, hound::Error> {
+    let spec = reader.spec();
+    let num_channels = usize::from(spec.channels.max(1));
+
+    let samples = match spec.sample_format {
+        SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|sample| sample.map(f64::from))
+            .collect::<Result<Vec<_>, _>>()?,
+        SampleFormat::Int => {
+            let scale = f64::from(1u32 << (spec.bits_per_sample - 1).min(31)).recip();
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| f64::from(sample) * scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    Ok(samples.into_iter().step_by(num_channels).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use assert_approx_eq::assert_approx_eq;
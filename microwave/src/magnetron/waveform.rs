@@ -1,10 +1,14 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use magnetron::automation::AutomatableParam;
 use magnetron::automation::AutomationFactory;
 use magnetron::stage::Stage;
 use serde::Deserialize;
 use serde::Serialize;
+use tune::scala::KbmRoot;
+use tune::scala::Scl;
+use tune_cli::CliResult;
 
 use crate::magnetron::StageType;
 use crate::magnetron::envelope::EnvelopeSpec;
@@ -24,6 +28,21 @@ pub struct WaveformSpec<A> {
     pub stages: Vec<StageType<A>>,
 }
 
+impl<A> WaveformSpec<A> {
+    pub async fn load_files(&mut self) -> CliResult {
+        for stage in &mut self.stages {
+            stage.load_files().await?;
+        }
+        Ok(())
+    }
+
+    pub fn set_tuning(&mut self, tuning: Option<&Arc<(Scl, KbmRoot)>>) {
+        for stage in &mut self.stages {
+            stage.set_tuning(tuning);
+        }
+    }
+}
+
 impl<A: AutomatableParam> WaveformSpec<A> {
     pub fn create(
         &self,
@@ -0,0 +1,310 @@
+use std::f64::consts::TAU;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use hound::WavReader;
+use magnetron::automation::AutomatableParam;
+use magnetron::automation::Automated;
+use magnetron::automation::AutomationFactory;
+use magnetron::buffer::BufferIndex;
+use magnetron::stage::Stage;
+use serde::Deserialize;
+use serde::Serialize;
+use tune_cli::CliError;
+use tune_cli::CliResult;
+use tune_cli::shared::error::ResultExt;
+
+use crate::magnetron::util;
+use crate::portable;
+
+/// Number of samples per frame after resampling. Must be a power of two.
+const TABLE_SIZE: usize = 2048;
+/// Number of band-limited versions of each frame. Level `n` contains the harmonics `1..=(TABLE_SIZE / 2) >> n`.
+const NUM_LEVELS: usize = TABLE_SIZE.ilog2() as usize;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WavetableSpec<A> {
+    pub wavetable_location: String,
+    /// Number of samples per frame. If not specified, the whole file is treated as a single-cycle waveform.
+    pub frame_size: Option<usize>,
+    pub frequency: A,
+    pub phase: Option<A>,
+    /// Position within the wavetable ranging from 0.0 (first frame) to 1.0 (last frame).
+    pub frame_position: Option<A>,
+    #[serde(skip)]
+    pub wavetable: LoadedWavetable,
+}
+
+#[derive(Clone, Default)]
+pub struct LoadedWavetable(Option<Arc<Wavetable>>);
+
+impl Debug for LoadedWavetable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(wavetable) => write!(f, "{} frames", wavetable.num_frames()),
+            None => write!(f, "Not loaded"),
+        }
+    }
+}
+
+impl<A> WavetableSpec<A> {
+    pub async fn load_files(&mut self) -> CliResult {
+        let file = portable::read_file(&self.wavetable_location)
+            .await?
+            .ok_or_else(|| format!("Wavetable file `{}` not found", self.wavetable_location))?;
+
+        let samples = WavReader::new(file)
+            .and_then(util::read_first_channel)
+            .display_err::<CliError>("Could not read wavetable file")?;
+
+        let wavetable = Wavetable::new(&samples, self.frame_size.unwrap_or(samples.len()))
+            .map_err(|error_message| {
+                format!(
+                    "Invalid wavetable file `{}`: {error_message}",
+                    self.wavetable_location
+                )
+            })?;
+
+        self.wavetable = LoadedWavetable(Some(Arc::new(wavetable)));
+
+        Ok(())
+    }
+}
+
+impl<A: AutomatableParam> WavetableSpec<A> {
+    pub fn create(
+        &self,
+        factory: &mut AutomationFactory<A>,
+        out_buffer: BufferIndex,
+        out_level: Option<&A>,
+    ) -> Stage<A> {
+        let wavetable = self.wavetable.0.clone().unwrap_or_else(|| {
+            log::warn!("Wavetable `{}` not loaded", self.wavetable_location);
+            Arc::new(Wavetable::silent())
+        });
+
+        let mut phase = 0.0;
+        let mut saved_phase = 0.0;
+        factory
+            .automate((
+                out_level,
+                (&self.frequency, &self.phase),
+                &self.frame_position,
+            ))
+            .into_stage(
+                move |buffers, (out_level, (frequency, to_phase), frame_position)| {
+                    let to_phase = to_phase.unwrap_or_default();
+
+                    let d_phase = frequency * buffers.sample_width_secs()
+                        + (to_phase - saved_phase) / buffers.buffer_len() as f64;
+
+                    saved_phase = to_phase;
+
+                    let level = Wavetable::level_for(d_phase);
+                    let frame_position = frame_position.unwrap_or_default();
+
+                    buffers.read_0_write_1(out_buffer, out_level, || {
+                        let signal = wavetable.sample(level, frame_position, phase);
+                        phase = (phase + d_phase).rem_euclid(1.0);
+                        signal
+                    })
+                },
+            )
+    }
+}
+
+/// A sequence of single-cycle frames with precomputed band-limited versions (mipmaps).
+pub struct Wavetable {
+    /// Indexed by level and frame.
+    levels: Vec<Vec<Vec<f64>>>,
+}
+
+impl Wavetable {
+    pub fn new(samples: &[f64], frame_size: usize) -> Result<Self, String> {
+        if frame_size == 0 {
+            return Err("Frame size must be greater than 0".to_owned());
+        }
+        if samples.len() < frame_size || samples.len() % frame_size != 0 {
+            return Err(format!(
+                "File contains {} samples which is not a multiple of the frame size {frame_size}",
+                samples.len()
+            ));
+        }
+
+        let mut levels = vec![Vec::new(); NUM_LEVELS];
+        for frame in samples.chunks_exact(frame_size) {
+            let mut spectrum: Vec<_> = resample(frame, TABLE_SIZE)
+                .into_iter()
+                .map(|sample| (sample, 0.0))
+                .collect();
+            fft(&mut spectrum, false);
+
+            for (level, frames) in levels.iter_mut().enumerate() {
+                let max_harmonic = (TABLE_SIZE / 2) >> level;
+
+                let mut band_limited = spectrum.clone();
+                for (index, bin) in band_limited.iter_mut().enumerate() {
+                    if index.min(TABLE_SIZE - index) > max_harmonic {
+                        *bin = (0.0, 0.0);
+                    }
+                }
+                fft(&mut band_limited, true);
+
+                frames.push(
+                    band_limited
+                        .into_iter()
+                        .map(|(re, _)| re / TABLE_SIZE as f64)
+                        .collect(),
+                );
+            }
+        }
+
+        Ok(Self { levels })
+    }
+
+    fn silent() -> Self {
+        Self {
+            levels: vec![vec![vec![0.0; TABLE_SIZE]]; NUM_LEVELS],
+        }
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Selects the most detailed level whose harmonics stay below the Nyquist frequency.
+    fn level_for(d_phase: f64) -> usize {
+        let max_harmonic = 0.5 / d_phase.abs();
+        (0..NUM_LEVELS)
+            .find(|&level| ((TABLE_SIZE / 2) >> level) as f64 <= max_harmonic)
+            .unwrap_or(NUM_LEVELS - 1)
+    }
+
+    fn sample(&self, level: usize, frame_position: f64, phase: f64) -> f64 {
+        let frames = &self.levels[level];
+
+        let frame_index = frame_position.clamp(0.0, 1.0) * (frames.len() - 1) as f64;
+        let lower_frame = frame_index.floor() as usize;
+        let upper_frame = (lower_frame + 1).min(frames.len() - 1);
+        let frame_fract = frame_index.fract();
+
+        let lower_sample = interpolate(&frames[lower_frame], phase);
+        let upper_sample = interpolate(&frames[upper_frame], phase);
+
+        lower_sample + (upper_sample - lower_sample) * frame_fract
+    }
+}
+
+fn interpolate(table: &[f64], phase: f64) -> f64 {
+    let index = phase * table.len() as f64;
+    let lower_index = (index.floor() as usize).min(table.len() - 1);
+    let upper_index = (lower_index + 1) % table.len();
+    let fract = index - lower_index as f64;
+
+    table[lower_index] + (table[upper_index] - table[lower_index]) * fract
+}
+
+fn resample(frame: &[f64], len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|index| interpolate(frame, index as f64 / len as f64))
+        .collect()
+}
+
+/// In-place radix-2 FFT of complex `(re, im)` values. The length of `data` must be a power of two.
+fn fft(data: &mut [(f64, f64)], inverse: bool) {
+    let len = data.len();
+
+    let mut j = 0;
+    for i in 1..len {
+        let mut bit = len >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut block_len = 2;
+    while block_len <= len {
+        let half_block_len = block_len / 2;
+        for k in 0..half_block_len {
+            let (sin, cos) = (sign * TAU * k as f64 / block_len as f64).sin_cos();
+            for start in (0..len).step_by(block_len) {
+                let (a_re, a_im) = data[start + k];
+                let (b_re, b_im) = data[start + k + half_block_len];
+                let (t_re, t_im) = (b_re * cos - b_im * sin, b_re * sin + b_im * cos);
+                data[start + k] = (a_re + t_re, a_im + t_im);
+                data[start + k + half_block_len] = (a_re - t_re, a_im - t_im);
+            }
+        }
+        block_len *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn resample_and_band_limit_frames() {
+        let sine: Vec<_> = (0..100)
+            .map(|index| (f64::from(index) / 100.0 * TAU).sin())
+            .collect();
+        let wavetable = Wavetable::new(&sine, 100).unwrap();
+
+        assert_eq!(wavetable.num_frames(), 1);
+        for level in [0, 5, NUM_LEVELS - 1] {
+            assert_approx_eq!(wavetable.sample(level, 0.0, 0.0), 0.0, 1e-3);
+            assert_approx_eq!(wavetable.sample(level, 0.0, 0.25), 1.0, 1e-3);
+            assert_approx_eq!(wavetable.sample(level, 0.0, 0.75), -1.0, 1e-3);
+        }
+
+        let sawtooth: Vec<_> = (0..TABLE_SIZE)
+            .map(|index| (index as f64 + 0.5) / TABLE_SIZE as f64 * 2.0 - 1.0)
+            .collect();
+        let wavetable = Wavetable::new(&sawtooth, TABLE_SIZE).unwrap();
+
+        // Only the fundamental with amplitude 2/π is left
+        assert_approx_eq!(wavetable.sample(NUM_LEVELS - 1, 0.0, 0.0), 0.0, 1e-3);
+        assert_approx_eq!(wavetable.sample(NUM_LEVELS - 1, 0.0, 0.25), -2.0 / PI, 1e-3);
+    }
+
+    #[test]
+    fn interpolate_between_frames() {
+        let frames = [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+        let wavetable = Wavetable::new(&frames, 4).unwrap();
+
+        assert_eq!(wavetable.num_frames(), 2);
+        assert_approx_eq!(wavetable.sample(0, 0.0, 0.3), 0.0);
+        assert_approx_eq!(wavetable.sample(0, 0.25, 0.3), 0.25);
+        assert_approx_eq!(wavetable.sample(0, 1.0, 0.3), 1.0);
+        assert_approx_eq!(wavetable.sample(0, 2.0, 0.3), 1.0);
+    }
+
+    #[test]
+    fn select_level_by_phase_increment() {
+        assert_eq!(Wavetable::level_for(0.0), 0);
+        assert_eq!(Wavetable::level_for(0.5 / 1024.0), 0);
+        assert_eq!(Wavetable::level_for(440.0 / 44100.0), 5);
+        assert_eq!(Wavetable::level_for(-440.0 / 44100.0), 5);
+        assert_eq!(Wavetable::level_for(0.5), NUM_LEVELS - 1);
+    }
+
+    #[test]
+    fn reject_invalid_frame_sizes() {
+        assert!(Wavetable::new(&[0.0; 4], 0).is_err());
+        assert!(Wavetable::new(&[0.0; 4], 5).is_err());
+        assert!(Wavetable::new(&[0.0; 5], 2).is_err());
+        assert!(Wavetable::new(&[0.0; 6], 2).is_ok());
+    }
+}
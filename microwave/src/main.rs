@@ -15,6 +15,7 @@ mod pipeline;
 mod portable;
 mod profile;
 mod recorder;
+mod render;
 mod synth;
 mod toggle;
 mod tunable;
@@ -36,6 +37,11 @@ use control::LiveParameterStorage;
 use control::ParameterValue;
 use piano::PianoEngine;
 use profile::MicrowaveProfile;
+use render::RenderOptions;
+use tune::layout::ColorPalette;
+use tune::pitch::Ratio;
+use tune::tuner::AdaptiveOptions;
+use tune::tuner::AdaptiveStrategy;
 use tune_cli::CliError;
 use tune_cli::CliResult;
 use tune_cli::shared;
@@ -69,6 +75,10 @@ enum SubCommand {
         #[arg(long = "analyze")]
         analyze: bool,
     },
+
+    /// Render a note script or MIDI file to a WAV file without using any audio device
+    #[command(name = "render")]
+    Render(RenderOptions),
 }
 
 #[derive(Parser)]
@@ -115,6 +125,27 @@ struct RunOptions {
     /// Odd limit for frequency ratio indicators
     #[arg(long = "lim", default_value = "11")]
     odd_limit: u16,
+
+    #[command(flatten)]
+    adaptive: AdaptiveTuningOptions,
+}
+
+#[derive(Parser)]
+struct AdaptiveTuningOptions {
+    /// Strategy of the adaptive tuning mode.
+    /// [anchor] The oldest sounding note keeps its pitch.
+    /// [drift] Minimize the total deviation from the scale.
+    /// [center] Keep the average deviation from the scale at zero (Hermode-style).
+    #[arg(long = "adapt", default_value = "center")]
+    strategy: AdaptiveStrategy,
+
+    /// Odd limit of the pure ratios in adaptive tuning mode
+    #[arg(long = "adapt-lim", default_value = "5")]
+    odd_limit: u16,
+
+    /// Maximum retuning of a single note in adaptive tuning mode
+    #[arg(long = "adapt-dev", default_value = "30c")]
+    max_deviation: Ratio,
 }
 
 #[derive(Parser)]
@@ -313,6 +344,7 @@ impl MainCommand {
                     bench::run_benchmark()
                 }
             }
+            Some(SubCommand::Render(render_options)) => render_options.run(&self.options).await,
         }
     }
 }
@@ -324,35 +356,12 @@ impl RunOptions {
 
         let profile = MicrowaveProfile::load(&self.profile_location).await?;
 
-        let parsed_scales = profile.parse_scales()?;
-
-        if parsed_scales.is_empty() {
-            return Err("No scales defined in profile".to_owned().into());
-        }
-
-        let tuning_layouts = Toggle::with_initial_index(
-            parsed_scales
-                .into_iter()
-                .map(|(scl, kbm)| {
-                    TuningLayout::new(
-                        scl,
-                        kbm,
-                        self.custom_keyboard.clone(),
-                        &profile.color_palette,
-                    )
-                })
-                .collect(),
-            profile.default_scale.unwrap_or_default(),
-        );
+        let tuning_layouts = self.create_tuning_layouts(&profile)?;
 
         let stream_params =
             audio::get_output_stream_params(self.audio.buffer_size, self.audio.sample_rate);
 
-        let mut initial_storage = LiveParameterStorage::default();
-        initial_storage.set_parameter(LiveParameter::Volume, 100u8.as_f64());
-        initial_storage.set_parameter(LiveParameter::Balance, 0.5);
-        initial_storage.set_parameter(LiveParameter::Pan, 0.5);
-        initial_storage.set_parameter(LiveParameter::Legato, 1.0);
+        let initial_storage = create_initial_storage();
 
         let (pipeline, backends, storage_updates, events) = AudioPipeline::create(
             &mut resources,
@@ -379,6 +388,7 @@ impl RunOptions {
             initial_storage,
             storage_updates,
             lumatone_send.clone(),
+            self.adaptive.to_adaptive_options(),
         );
 
         let midi_source = match self.lumatone_device.is_some() {
@@ -400,6 +410,50 @@ impl RunOptions {
 
         Ok(())
     }
+
+    fn create_tuning_layouts(&self, profile: &MicrowaveProfile) -> CliResult<Toggle<TuningLayout>> {
+        let parsed_scales = profile.parse_scales()?;
+
+        if parsed_scales.is_empty() {
+            return Err("No scales defined in profile".to_owned().into());
+        }
+
+        let color_palette = ColorPalette::from(&profile.color_palette);
+
+        Ok(Toggle::with_initial_index(
+            parsed_scales
+                .into_iter()
+                .map(|(scl, kbm)| {
+                    TuningLayout::new(
+                        scl,
+                        kbm,
+                        self.custom_keyboard.clone(),
+                        &color_palette,
+                    )
+                })
+                .collect(),
+            profile.default_scale.unwrap_or_default(),
+        ))
+    }
+}
+
+fn create_initial_storage() -> LiveParameterStorage {
+    let mut initial_storage = LiveParameterStorage::default();
+    initial_storage.set_parameter(LiveParameter::Volume, 100u8.as_f64());
+    initial_storage.set_parameter(LiveParameter::Balance, 0.5);
+    initial_storage.set_parameter(LiveParameter::Pan, 0.5);
+    initial_storage.set_parameter(LiveParameter::Legato, 1.0);
+    initial_storage
+}
+
+impl AdaptiveTuningOptions {
+    fn to_adaptive_options(&self) -> AdaptiveOptions {
+        AdaptiveOptions {
+            strategy: self.strategy,
+            odd_limit: self.odd_limit,
+            max_deviation: self.max_deviation,
+        }
+    }
 }
 
 impl ControlChangeOptions {
@@ -15,11 +15,14 @@ use tune::midi::ChannelMessageType;
 use tune::pitch::Pitch;
 use tune::scala::KbmRoot;
 use tune::scala::Scl;
+use tune::tuner::AdaptiveOptions;
 use tune::tuner::MidiTunerMessage;
 use tune::tuner::MidiTunerMessageHandler;
+use tune::tuner::PoolingMode;
 use tune::tuner::TunableMidi;
 use tune_cli::CliResult;
 use tune_cli::shared;
+use tune_cli::shared::midi::AllocationArgs;
 use tune_cli::shared::midi::MidiOutArgs;
 use tune_cli::shared::midi::MidiSource;
 use tune_cli::shared::midi::TuningMethod;
@@ -45,6 +48,7 @@ pub struct MidiOutSpec {
     #[serde(flatten)]
     pub out_args: MidiOutArgs,
     pub tuning_method: TuningMethod,
+    pub allocation: Option<AllocationArgs>,
 
     pub banks: BTreeSet<Bank>,
     pub default_bank: Option<Bank>,
@@ -72,6 +76,7 @@ impl MidiOutSpec {
             match midi::connect_to_out_device("microwave", &self.out_device)
                 .map_err(|err| format!("{err:?}"))
                 .and_then(|(device, midi_out)| {
+                    self.out_args.validate_method(self.tuning_method, false)?;
                     self.out_args
                         .get_midi_target(MidiOutHandler {
                             midi_events: midi_send,
@@ -107,7 +112,13 @@ impl MidiOutSpec {
             curr_bank_msb: self.default_bank.map(|b| b.msb),
             curr_bank_lsb: self.default_bank.map(|b| b.lsb),
             curr_program: self.default_program.unwrap_or_default(),
-            backend: TunableBackend::new(self.out_args.create_synth(target, self.tuning_method)),
+            backend: TunableBackend::new(
+                self.out_args.create_synth(target, self.tuning_method),
+                self.allocation
+                    .as_ref()
+                    .map(AllocationArgs::to_allocation_policy)
+                    .unwrap_or_else(|| PoolingMode::Stop.into()),
+            ),
         };
         backend.init();
         backends.push(Box::new(backend));
@@ -161,6 +172,10 @@ impl<K: Copy + Eq + Hash + Debug + Send, E: From<MidiOutEvent> + Send> Backend<K
         self.backend.set_no_tuning();
     }
 
+    fn set_adaptive_tuning(&mut self, options: AdaptiveOptions) {
+        self.backend.set_adaptive_tuning(options);
+    }
+
     fn request_status(&mut self) {
         self.events
             .send(
@@ -299,14 +314,14 @@ pub fn connect_to_in_device(
     Ok(())
 }
 
-fn handle_midi_message(message: &[u8], engine: &PianoEngine, midi_source: Option<&MidiSource>) {
+pub fn handle_midi_message(message: &[u8], engine: &PianoEngine, midi_source: Option<&MidiSource>) {
     if let Some(channel_message) = ChannelMessage::from_raw_message(message) {
         log::debug!("Received MIDI message: {channel_message:?}");
 
         match midi_source {
             Some(midi_source) if midi_source.channels.contains(&channel_message.channel()) => {
                 let offset = midi_source.get_offset(channel_message.channel());
-                engine.handle_midi(channel_message.message_type(), |key| {
+                engine.handle_midi(channel_message, |key| {
                     let piano_key = offset.get_piano_key(key);
                     (
                         SourceId::Piano(channel_message.channel(), key),
@@ -315,7 +330,7 @@ fn handle_midi_message(message: &[u8], engine: &PianoEngine, midi_source: Option
                 });
             }
             None => {
-                engine.handle_midi(channel_message.message_type(), |key| {
+                engine.handle_midi(channel_message, |key| {
                     let lumatone_key = LumatoneKey {
                         board_index: channel_message.channel(),
                         key_index: key,
@@ -721,8 +736,10 @@ mod tests {
                     num_out_channels: 9,
                     device_id: Default::default(),
                     tuning_program: 0,
+                    pitch_bend_range: None,
                 },
                 tuning_method: TuningMethod::FullKeyboard,
+                allocation: None,
                 banks: banks.into(),
                 default_bank,
                 default_program: None,
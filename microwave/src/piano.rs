@@ -6,8 +6,10 @@ use std::sync::MutexGuard;
 use bevy::prelude::*;
 use flume::Sender;
 use tune::key::PianoKey;
+use tune::midi::ChannelMessage;
 use tune::midi::ChannelMessageType;
 use tune::pitch::Pitch;
+use tune::tuner::AdaptiveOptions;
 use tune::tuning::Tuning;
 
 use crate::backend::Backends;
@@ -34,6 +36,7 @@ struct PianoEngineModel {
     tuning_layouts: Toggle<TuningLayout>,
     lumatone_sender: Option<Sender<LumatoneLayout>>,
     tuning_mode: Toggle<TuningMode>,
+    adaptive_options: AdaptiveOptions,
     mapper: LiveParameterMapper,
     storage: LiveParameterStorage,
     pressed_keys: PressedKeys,
@@ -45,6 +48,7 @@ struct PianoEngineModel {
 pub enum TuningMode {
     Fixed,
     Continuous,
+    Adaptive,
 }
 
 pub type PressedKeys = HashMap<(SourceId, usize), Option<Pitch>>;
@@ -57,13 +61,20 @@ impl PianoEngine {
         storage: LiveParameterStorage,
         storage_updates: Sender<LiveParameterStorage>,
         lumatone_sender: Option<Sender<LumatoneLayout>>,
+        adaptive_options: AdaptiveOptions,
     ) -> Self {
         let mut model = PianoEngineModel {
             backends: backends.into(),
             storage_updates,
             tuning_layouts,
             lumatone_sender,
-            tuning_mode: vec![TuningMode::Fixed, TuningMode::Continuous].into(),
+            tuning_mode: vec![
+                TuningMode::Fixed,
+                TuningMode::Continuous,
+                TuningMode::Adaptive,
+            ]
+            .into(),
+            adaptive_options,
             storage,
             mapper,
             pressed_keys: HashMap::new(),
@@ -81,10 +92,10 @@ impl PianoEngine {
 
     pub fn handle_midi(
         &self,
-        message_type: ChannelMessageType,
+        message: ChannelMessage,
         map_midi_key: impl Fn(u8) -> (SourceId, InputLocation),
     ) {
-        self.lock_model().handle_midi(message_type, map_midi_key);
+        self.lock_model().handle_midi(message, map_midi_key);
     }
 
     pub fn handle_input(&self, event: InputEvent) {
@@ -247,10 +258,10 @@ impl PianoEngine {
 impl PianoEngineModel {
     fn handle_midi(
         &mut self,
-        message_type: ChannelMessageType,
+        message: ChannelMessage,
         map_midi_key: impl Fn(u8) -> (SourceId, InputLocation),
     ) {
-        match message_type {
+        match message.message_type() {
             // Forwarded to all backends.
             ChannelMessageType::NoteOff { key, velocity }
             | ChannelMessageType::NoteOn {
@@ -276,7 +287,7 @@ impl PianoEngineModel {
                 for backend in &mut self.backends {
                     backend.control_change(controller, value);
                 }
-                for parameter in self.mapper.resolve_ccn(controller) {
+                for parameter in self.mapper.resolve_control_change(message) {
                     self.set_parameter_without_backends_update(parameter, value.as_f64());
                 }
             }
@@ -350,7 +361,9 @@ impl PianoEngineModel {
                 let degree = tuning.find_by_pitch(pitch).approx_value;
                 match self.tuning_mode.curr_option() {
                     TuningMode::Continuous => Some((degree, pitch)),
-                    TuningMode::Fixed => Some((degree, tuning.pitch_of(degree))),
+                    TuningMode::Fixed | TuningMode::Adaptive => {
+                        Some((degree, tuning.pitch_of(degree)))
+                    }
                 }
             }
         }
@@ -416,6 +429,7 @@ impl PianoEngineModel {
             match self.tuning_mode.curr_option() {
                 TuningMode::Fixed => backend.set_tuning((&scl, kbm_root)),
                 TuningMode::Continuous => backend.set_no_tuning(),
+                TuningMode::Adaptive => backend.set_adaptive_tuning(self.adaptive_options),
             }
         }
         self.backend_mut().request_status();
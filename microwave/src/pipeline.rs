@@ -105,7 +105,8 @@ impl AudioPipeline {
             .map(|spec| (spec.name, spec.spec))
             .collect();
 
-        for stage in profile.stages {
+        for mut stage in profile.stages {
+            stage.load_files().await?;
             stage
                 .create(
                     resources,
@@ -177,6 +178,22 @@ impl AudioPipeline {
 }
 
 impl PipelineStageSpec {
+    async fn load_files(&mut self) -> CliResult {
+        match self {
+            PipelineStageSpec::Magnetron(spec) => spec.load_files().await,
+            PipelineStageSpec::Generator(spec) => spec.load_files().await,
+            PipelineStageSpec::Reset(_)
+            | PipelineStageSpec::AudioIn(_)
+            | PipelineStageSpec::WavRecorder(_)
+            | PipelineStageSpec::Fluid(_)
+            | PipelineStageSpec::MidiOut(_)
+            | PipelineStageSpec::NoAudio
+            | PipelineStageSpec::Processor(_)
+            | PipelineStageSpec::MergeProcessor(_)
+            | PipelineStageSpec::StereoProcessor(_) => Ok(()),
+        }
+    }
+
     async fn create(
         &self,
         resources: &mut Vec<Box<dyn Any>>,
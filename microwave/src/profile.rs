@@ -6,6 +6,7 @@ use clap::Parser;
 use serde::Deserialize;
 use serde::Serialize;
 use shlex::Shlex;
+use tune::layout;
 use tune::note::Note;
 use tune::pitch::Ratio;
 use tune::scala::Kbm;
@@ -31,6 +32,8 @@ use crate::magnetron::ProcessorType;
 use crate::magnetron::StageType;
 use crate::magnetron::StereoProcessorSpec;
 use crate::magnetron::StereoProcessorType;
+use crate::magnetron::additive::AdditiveSpec;
+use crate::magnetron::additive::PartialSpec;
 use crate::magnetron::effects::EffectSpec;
 use crate::magnetron::envelope::EnvelopeSpec;
 use crate::magnetron::filter::FilterSpec;
@@ -99,6 +102,17 @@ pub struct ColorPalette {
     pub enharmonic_colors: Vec<Srgba>,
 }
 
+impl From<&ColorPalette> for layout::ColorPalette<Srgba> {
+    fn from(palette: &ColorPalette) -> Self {
+        Self {
+            natural_color: palette.natural_color,
+            sharp_colors: palette.sharp_colors.clone(),
+            flat_colors: palette.flat_colors.clone(),
+            enharmonic_colors: palette.enharmonic_colors.clone(),
+        }
+    }
+}
+
 impl MicrowaveProfile {
     pub async fn load(file_name: &str) -> CliResult<Self> {
         if let Some(data) = portable::read_file(file_name).await? {
@@ -346,6 +360,7 @@ pub fn get_default_profile() -> MicrowaveProfile {
             out_device: "<midi-device>".to_owned(),
             out_args: Default::default(),
             tuning_method: TuningMethod::Octave1,
+            allocation: None,
             banks: BTreeSet::from([
                 Bank { msb: 0, lsb: 0 },
                 Bank { msb: 0, lsb: 1 },
@@ -1721,6 +1736,85 @@ pub fn get_default_magnetron_spec() -> MagnetronSpec {
                 }),
             ],
         },
+        WaveformSpec {
+            name: "Band-Limited Triangle".to_owned(),
+            envelope: "Organ".to_owned(),
+            stages: vec![StageType::Generator(GeneratorSpec {
+                out_buffer: 7,
+                out_level: None,
+                generator_type: GeneratorType::Oscillator(OscillatorSpec {
+                    oscillator_type: OscillatorType::BandLimitedTriangle,
+                    frequency: LfSource::template("WaveformPitch"),
+                    phase: None,
+                }),
+            })],
+        },
+        WaveformSpec {
+            name: "Band-Limited Square".to_owned(),
+            envelope: "Organ".to_owned(),
+            stages: vec![StageType::Generator(GeneratorSpec {
+                out_buffer: 7,
+                out_level: Some(LfSource::Value(1.0 / 4.0)),
+                generator_type: GeneratorType::Oscillator(OscillatorSpec {
+                    oscillator_type: OscillatorType::BandLimitedSquare,
+                    frequency: LfSource::template("WaveformPitch"),
+                    phase: None,
+                }),
+            })],
+        },
+        WaveformSpec {
+            name: "Band-Limited Sawtooth".to_owned(),
+            envelope: "Organ".to_owned(),
+            stages: vec![StageType::Generator(GeneratorSpec {
+                out_buffer: 7,
+                out_level: Some(LfSource::Value(1.0 / 2.0)),
+                generator_type: GeneratorType::Oscillator(OscillatorSpec {
+                    oscillator_type: OscillatorType::BandLimitedSawtooth,
+                    frequency: LfSource::template("WaveformPitch"),
+                    phase: None,
+                }),
+            })],
+        },
+        WaveformSpec {
+            name: "Bell 3 (Tempered to scale)".to_owned(),
+            envelope: "Bell".to_owned(),
+            stages: vec![StageType::Generator(GeneratorSpec {
+                out_buffer: 7,
+                out_level: Some(LfSource::Value(1.0)),
+                generator_type: GeneratorType::Additive(AdditiveSpec {
+                    frequency: LfSource::template("WaveformPitch"),
+                    partials: vec![
+                        PartialSpec {
+                            ratio: LfSource::Value(1.0),
+                            amplitude: LfSource::Value(16.0 / 31.0),
+                            decay: None,
+                        },
+                        PartialSpec {
+                            ratio: LfSource::Value(3.0),
+                            amplitude: LfSource::Value(8.0 / 31.0),
+                            decay: Some(LfSource::Value(1.0)),
+                        },
+                        PartialSpec {
+                            ratio: LfSource::Value(5.0),
+                            amplitude: LfSource::Value(4.0 / 31.0),
+                            decay: Some(LfSource::Value(2.0)),
+                        },
+                        PartialSpec {
+                            ratio: LfSource::Value(7.0),
+                            amplitude: LfSource::Value(2.0 / 31.0),
+                            decay: Some(LfSource::Value(3.0)),
+                        },
+                        PartialSpec {
+                            ratio: LfSource::Value(9.0),
+                            amplitude: LfSource::Value(1.0 / 31.0),
+                            decay: Some(LfSource::Value(4.0)),
+                        },
+                    ],
+                    tempered: true,
+                    tuning: Default::default(),
+                }),
+            })],
+        },
     ];
 
     MagnetronSpec {
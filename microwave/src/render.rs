@@ -0,0 +1,354 @@
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use clap::Parser;
+use hound::WavSpec;
+use hound::WavWriter;
+use magnetron::buffer::BufferIndex;
+use serde::Deserialize;
+use serde::Serialize;
+use tune::midi::ChannelMessage;
+use tune::midi::ChannelMessageType;
+use tune_cli::CliError;
+use tune_cli::CliResult;
+use tune_cli::shared::error::ResultExt;
+use tune_cli::shared::smf;
+
+use crate::RunOptions;
+use crate::create_initial_storage;
+use crate::midi;
+use crate::piano::PianoEngine;
+use crate::pipeline::AudioPipeline;
+use crate::portable;
+use crate::profile::MicrowaveProfile;
+use crate::toggle::Toggle;
+use crate::tuning_layout::TuningLayout;
+
+const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+#[derive(Parser)]
+pub struct RenderOptions {
+    /// Note script (YAML) or Standard MIDI File (*.mid, *.midi) to render
+    input_file: String,
+
+    /// Location of the rendered WAV file
+    output_file: String,
+
+    /// Index of the scale (as listed in the profile) to render with. Defaults to the profile's default scale
+    #[arg(long = "scale")]
+    scale_index: Option<usize>,
+
+    /// Time to keep rendering after the last event [s]
+    #[arg(long = "tail", default_value = "2")]
+    tail_secs: f64,
+}
+
+impl RenderOptions {
+    /// Renders the audio pipeline faster than real time.
+    ///
+    /// The sample rate (default: 44100 Hz) and buffer size are taken from the audio options.
+    /// Event times are quantized to the buffer size.
+    pub async fn run(self, options: &RunOptions) -> CliResult {
+        let messages = self.load_messages().await?;
+
+        let profile = MicrowaveProfile::load(&options.profile_location).await?;
+
+        let mut tuning_layouts = options.create_tuning_layouts(&profile)?;
+        if let Some(scale_index) = self.scale_index
+            && !tuning_layouts.set_curr_index(scale_index)
+        {
+            return Err(format!(
+                "Scale index {scale_index} is out of range. The profile defines {} scales.",
+                tuning_layouts.num_options()
+            )
+            .into());
+        }
+
+        let rendered_duration = self
+            .render(
+                options,
+                profile,
+                tuning_layouts,
+                messages,
+                portable::write_file(&self.output_file).await?,
+            )
+            .await?;
+
+        log::info!(
+            "Rendered {:.3} s of audio to `{}`",
+            rendered_duration.as_secs_f64(),
+            self.output_file
+        );
+
+        Ok(())
+    }
+
+    /// Renders the given messages to a WAV stream and returns the rendered duration.
+    async fn render(
+        &self,
+        options: &RunOptions,
+        profile: MicrowaveProfile,
+        tuning_layouts: Toggle<TuningLayout>,
+        messages: Vec<(Duration, ChannelMessage)>,
+        writer: impl Write + Seek,
+    ) -> CliResult<Duration> {
+        // Track resources (e.g. audio contexts) that need to be kept alive.
+        let mut resources = Vec::new();
+
+        let buffer_size = options.audio.buffer_size;
+        let sample_rate = options.audio.sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE);
+
+        let initial_storage = create_initial_storage();
+
+        let (mut pipeline, backends, storage_updates, _events) = AudioPipeline::create(
+            &mut resources,
+            buffer_size,
+            sample_rate,
+            profile,
+            initial_storage.clone(),
+        )
+        .await?;
+
+        let engine = PianoEngine::new(
+            tuning_layouts,
+            backends,
+            options.control_change.to_parameter_mapper(),
+            initial_storage,
+            storage_updates,
+            None,
+            options.adaptive.to_adaptive_options(),
+        );
+
+        let midi_source = options.midi_in.get_midi_source()?;
+
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut wav_writer =
+            WavWriter::new(writer, spec).display_err::<CliError>("Could not create WAV file")?;
+
+        let end_time = messages.last().map(|&(time, _)| time).unwrap_or_default()
+            + Duration::from_secs_f64(self.tail_secs.max(0.0));
+        let buffer_duration = Duration::from_secs_f64(f64::from(buffer_size)) / sample_rate;
+
+        let audio_buffers = pipeline.audio_buffers();
+        let mut messages = messages.into_iter().peekable();
+        let mut buffer_end_time = Duration::ZERO;
+
+        while buffer_end_time < end_time {
+            buffer_end_time += buffer_duration;
+
+            while let Some((_, message)) = messages.next_if(|&(time, _)| time < buffer_end_time) {
+                midi::handle_midi_message(&message.to_raw_message(), &engine, Some(&midi_source));
+            }
+
+            let buffers = pipeline.render(usize::try_from(buffer_size).unwrap());
+            for (&l, &r) in buffers
+                .read(BufferIndex::Internal(audio_buffers.0))
+                .iter()
+                .zip(buffers.read(BufferIndex::Internal(audio_buffers.1)))
+            {
+                wav_writer
+                    .write_sample(l as f32)
+                    .display_err::<CliError>("Could not write WAV file")?;
+                wav_writer
+                    .write_sample(r as f32)
+                    .display_err::<CliError>("Could not write WAV file")?;
+            }
+        }
+
+        wav_writer
+            .finalize()
+            .display_err::<CliError>("Could not write WAV file")?;
+
+        Ok(buffer_end_time)
+    }
+
+    async fn load_messages(&self) -> CliResult<Vec<(Duration, ChannelMessage)>> {
+        let Some(mut file) = portable::read_file(&self.input_file).await? else {
+            return Err(format!("Input file `{}` not found", self.input_file).into());
+        };
+
+        let is_smf = Path::new(&self.input_file)
+            .extension()
+            .is_some_and(|extension| extension == "mid" || extension == "midi");
+
+        let mut messages = if is_smf {
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            smf::read_channel_messages(&data)?
+        } else {
+            let script: Vec<NoteScriptEntry> = serde_yaml::from_reader(file)
+                .display_err::<CliError>("Could not deserialize note script")?;
+            script
+                .iter()
+                .flat_map(NoteScriptEntry::to_channel_messages)
+                .collect()
+        };
+
+        // Handle note-offs first so that a repeated note is not released right after being triggered.
+        messages.sort_by_key(|&(time, message)| {
+            let is_note_on = matches!(
+                message.message_type(),
+                ChannelMessageType::NoteOn { velocity, .. } if velocity > 0
+            );
+            (time, is_note_on)
+        });
+
+        Ok(messages)
+    }
+}
+
+/// A single note of a note script.
+///
+/// Times are given in seconds.
+#[derive(Deserialize, Serialize)]
+struct NoteScriptEntry {
+    time: f64,
+    duration: f64,
+    key: u8,
+    #[serde(default = "default_velocity")]
+    velocity: u8,
+    #[serde(default)]
+    channel: u8,
+}
+
+fn default_velocity() -> u8 {
+    100
+}
+
+impl NoteScriptEntry {
+    fn to_channel_messages(&self) -> Vec<(Duration, ChannelMessage)> {
+        let start = Duration::from_secs_f64(self.time.max(0.0));
+        let stop = start + Duration::from_secs_f64(self.duration.max(0.0));
+
+        [
+            (
+                start,
+                ChannelMessageType::NoteOn {
+                    key: self.key,
+                    velocity: self.velocity,
+                },
+            ),
+            (
+                stop,
+                ChannelMessageType::NoteOff {
+                    key: self.key,
+                    velocity: self.velocity,
+                },
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(time, message_type)| {
+            message_type
+                .in_channel(self.channel)
+                .map(|message| (time, message))
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use hound::WavReader;
+
+    use crate::pipeline::PipelineStageSpec;
+    use crate::profile;
+
+    use super::*;
+
+    #[test]
+    fn convert_note_script_to_channel_messages() {
+        let script: Vec<NoteScriptEntry> = serde_yaml::from_str(
+            "
+- time: 0.5
+  duration: 1.0
+  key: 62
+- time: 1.0
+  duration: 0.25
+  key: 64
+  velocity: 80
+  channel: 2",
+        )
+        .unwrap();
+
+        let messages: Vec<_> = script
+            .iter()
+            .flat_map(NoteScriptEntry::to_channel_messages)
+            .map(|(time, message)| (time.as_secs_f64(), message.to_raw_message()))
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                (0.5, [0x90, 62, 100]),
+                (1.5, [0x80, 62, 100]),
+                (1.0, [0x92, 64, 80]),
+                (1.25, [0x82, 64, 80]),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_profile_to_wav() {
+        let options = RunOptions::try_parse_from(["microwave", "--out-buf", "256"]).unwrap();
+        let render_options =
+            RenderOptions::try_parse_from(["render", "in.yml", "out.wav", "--tail", "0.5"])
+                .unwrap();
+
+        let mut profile = profile::get_default_profile();
+        profile.audio_buffers = (0, 1);
+        profile.stages = vec![PipelineStageSpec::Magnetron(
+            profile::get_default_magnetron_spec(),
+        )];
+        let tuning_layouts = options.create_tuning_layouts(&profile).unwrap();
+
+        let script: Vec<NoteScriptEntry> = serde_yaml::from_str(
+            "
+- time: 0.0
+  duration: 0.5
+  key: 62",
+        )
+        .unwrap();
+        let messages = script
+            .iter()
+            .flat_map(NoteScriptEntry::to_channel_messages)
+            .collect();
+
+        let mut data = Cursor::new(Vec::new());
+        let rendered_duration = async_std::task::block_on(render_options.render(
+            &options,
+            profile,
+            tuning_layouts,
+            messages,
+            &mut data,
+        ))
+        .map_err(|err| err.to_string())
+        .unwrap();
+
+        // 0.5 s note + 0.5 s tail, rounded up to full buffers of 256 frames
+        let num_frames = 44100usize.div_ceil(256) * 256;
+        assert!(rendered_duration >= Duration::from_secs(1));
+
+        data.set_position(0);
+        let reader = WavReader::new(data).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 44100);
+        assert_eq!(reader.len() as usize, 2 * num_frames);
+
+        let max_amplitude = reader
+            .into_samples::<f32>()
+            .map(|sample| sample.unwrap().abs())
+            .fold(0.0, f32::max);
+        assert!(max_amplitude > 0.01);
+        assert!(max_amplitude <= 1.0);
+    }
+}
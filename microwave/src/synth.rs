@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::iter;
 use std::mem;
+use std::sync::Arc;
 
 use flume::Receiver;
 use flume::Sender;
@@ -15,6 +16,9 @@ use serde::Serialize;
 use tune::pitch::Pitch;
 use tune::scala::KbmRoot;
 use tune::scala::Scl;
+use tune::tuner::AdaptiveOptions;
+use tune::tuner::AdaptiveTuningModel;
+use tune_cli::CliResult;
 
 use crate::backend::Backend;
 use crate::backend::NoteInput;
@@ -40,7 +44,17 @@ pub struct MagnetronSpec {
 }
 
 impl MagnetronSpec {
-    pub fn create<K: Eq + Hash + Send + 'static, E: From<MagnetronEvent> + Send + 'static>(
+    pub async fn load_files(&mut self) -> CliResult {
+        for waveform in &mut self.waveforms {
+            waveform.load_files().await?;
+        }
+        Ok(())
+    }
+
+    pub fn create<
+        K: Copy + Eq + Hash + Send + 'static,
+        E: From<MagnetronEvent> + Send + 'static,
+    >(
         &self,
         buffer_size: u32,
         sample_rate: u32,
@@ -77,6 +91,8 @@ impl MagnetronSpec {
             ),
             factory: AutomationFactory::new(templates.clone()),
             envelopes: envelopes.clone(),
+            tuning: None,
+            adaptive_model: None,
         };
 
         backends.push(Box::new(backend));
@@ -92,16 +108,29 @@ struct MagnetronBackend<K, E> {
     envelope_names: Toggle<Option<String>>,
     factory: AutomationFactory<WaveformParam>,
     envelopes: HashMap<String, EnvelopeSpec<WaveformParam>>,
+    tuning: Option<Arc<(Scl, KbmRoot)>>,
+    adaptive_model: Option<AdaptiveTuningModel<K>>,
 }
 
-impl<K: Send, E: From<MagnetronEvent> + Send> Backend<K> for MagnetronBackend<K, E> {
+impl<K: Copy + Eq + Send, E: From<MagnetronEvent> + Send> Backend<K> for MagnetronBackend<K, E> {
     fn note_input(&self) -> NoteInput {
         self.note_input
     }
 
-    fn set_tuning(&mut self, _tuning: (&Scl, KbmRoot)) {}
+    fn set_tuning(&mut self, tuning: (&Scl, KbmRoot)) {
+        self.tuning = Some(Arc::new((tuning.0.clone(), tuning.1)));
+        self.adaptive_model = None;
+    }
+
+    fn set_no_tuning(&mut self) {
+        self.tuning = None;
+        self.adaptive_model = None;
+    }
 
-    fn set_no_tuning(&mut self) {}
+    fn set_adaptive_tuning(&mut self, options: AdaptiveOptions) {
+        self.tuning = None;
+        self.adaptive_model = Some(AdaptiveTuningModel::new(options));
+    }
 
     fn request_status(&mut self) {
         self.events
@@ -121,10 +150,18 @@ impl<K: Send, E: From<MagnetronEvent> + Send> Backend<K> for MagnetronBackend<K,
         let selected_envelope = self.selected_envelope().to_owned();
 
         let waveform_spec = self.waveforms.curr_option_mut();
+        waveform_spec.set_tuning(self.tuning.as_ref());
         let default_envelope = mem::replace(&mut waveform_spec.envelope, selected_envelope);
         let waveform = waveform_spec.create(&mut self.factory, &self.envelopes);
         self.waveforms.curr_option_mut().envelope = default_envelope;
 
+        let mut pitch = pitch;
+        if let Some(adaptive_model) = &mut self.adaptive_model {
+            let retuned_keys = adaptive_model.note_on(key_id, pitch);
+            pitch = adaptive_model.adapted_pitch(key_id).unwrap_or(pitch);
+            self.update_pitches(retuned_keys.into_iter().filter(|&(key, _)| key != key_id));
+        }
+
         self.send(Command {
             key_id,
             action: Action::Start {
@@ -137,10 +174,13 @@ impl<K: Send, E: From<MagnetronEvent> + Send> Backend<K> for MagnetronBackend<K,
 
     fn update_pitch(&mut self, key_id: K, _degree: i32, pitch: Pitch, _velocity: u8) {
         // Should we update the velocity as well?
-        self.send(Command {
-            key_id,
-            action: Action::UpdatePitch { pitch },
-        });
+        match &mut self.adaptive_model {
+            Some(adaptive_model) => {
+                let retuned_keys = adaptive_model.note_pitch(key_id, pitch);
+                self.update_pitches(retuned_keys);
+            }
+            None => self.update_pitches([(key_id, pitch)]),
+        }
     }
 
     fn update_pressure(&mut self, key_id: K, pressure: u8) {
@@ -159,6 +199,11 @@ impl<K: Send, E: From<MagnetronEvent> + Send> Backend<K> for MagnetronBackend<K,
                 velocity: velocity.as_f64(),
             },
         });
+
+        if let Some(adaptive_model) = &mut self.adaptive_model {
+            let retuned_keys = adaptive_model.note_off(key_id);
+            self.update_pitches(retuned_keys);
+        }
     }
 
     fn program_change(&mut self, program_change: ProgramChange) {
@@ -197,6 +242,15 @@ impl<K, E> MagnetronBackend<K, E> {
             .unwrap_or_else(|_| log::error!("Main audio thread stopped"))
     }
 
+    fn update_pitches(&self, pitches: impl IntoIterator<Item = (K, Pitch)>) {
+        for (key_id, pitch) in pitches {
+            self.send(Command {
+                key_id,
+                action: Action::UpdatePitch { pitch },
+            });
+        }
+    }
+
     fn selected_envelope(&self) -> &str {
         self.envelope_names
             .curr_option()
@@ -3,14 +3,16 @@ use std::hash::Hash;
 use std::mem;
 use std::ops::RangeInclusive;
 
+use bevy::platform::time::Instant;
 use tune::note::Note;
 use tune::pitch::Pitch;
 use tune::pitch::Pitched;
 use tune::scala::KbmRoot;
 use tune::scala::Scl;
+use tune::tuner::AdaptiveOptions;
+use tune::tuner::AllocationPolicy;
 use tune::tuner::AotTuner;
 use tune::tuner::JitTuner;
-use tune::tuner::PoolingMode;
 use tune::tuner::TunableSynth;
 use tune::tuning::Scale;
 use tune::tuning::Tuning;
@@ -23,15 +25,19 @@ use crate::keypress::PlaceAction;
 /// Meta-backend for tunable synthesizers (MIDI Out and Fluid).
 pub struct TunableBackend<K, S> {
     tuner: Tuner<K, S>,
+    allocation_policy: AllocationPolicy,
+    start_time: Instant,
 }
 
 impl<K, S: TunableSynth> TunableBackend<K, S> {
-    pub fn new(synth: S) -> Self {
+    pub fn new(synth: S, allocation_policy: impl Into<AllocationPolicy>) -> Self {
         Self {
             tuner: Tuner::Aot {
                 aot_tuner: AotTuner::start(synth),
                 keypress_tracker: KeypressTracker::new(),
             },
+            allocation_policy: allocation_policy.into(),
+            start_time: Instant::now(),
         }
     }
 }
@@ -83,7 +89,13 @@ where
 
     pub fn set_no_tuning(&mut self) {
         let synth = self.destroy_tuning();
-        let jit_tuner = JitTuner::start(synth, PoolingMode::Stop);
+        let jit_tuner = JitTuner::start(synth, self.allocation_policy);
+        self.tuner = Tuner::Jit { jit_tuner };
+    }
+
+    pub fn set_adaptive_tuning(&mut self, options: AdaptiveOptions) {
+        let synth = self.destroy_tuning();
+        let jit_tuner = JitTuner::start_adaptive(synth, self.allocation_policy, options);
         self.tuner = Tuner::Jit { jit_tuner };
     }
 
@@ -99,6 +111,7 @@ where
         match &mut self.tuner {
             Tuner::Destroyed => {}
             Tuner::Jit { jit_tuner } => {
+                jit_tuner.set_time(self.start_time.elapsed());
                 jit_tuner.note_on(key_id, pitch, velocity);
             }
             Tuner::Aot {
@@ -163,6 +176,7 @@ where
         match &mut self.tuner {
             Tuner::Destroyed => {}
             Tuner::Jit { jit_tuner } => {
+                jit_tuner.set_time(self.start_time.elapsed());
                 jit_tuner.note_off(key_id, velocity);
             }
             Tuner::Aot {
@@ -242,6 +256,7 @@ mod tests {
     use tune::note::NoteLetter;
     use tune::pitch::Ratio;
     use tune::tuner::GroupBy;
+    use tune::tuner::PoolingMode;
 
     use super::*;
 
@@ -319,7 +334,7 @@ mod tests {
             state: state.clone(),
         };
 
-        let mut backend = TunableBackend::<usize, _>::new(synth);
+        let mut backend = TunableBackend::<usize, _>::new(synth, PoolingMode::Stop);
         let (scl, kbm) = create_non_monotonous_tuning();
 
         backend.set_tuning((&scl, kbm));
@@ -3,15 +3,14 @@ use std::fmt::Display;
 use std::sync::Arc;
 
 use bevy::prelude::*;
+use tune::layout::ColorPalette;
 use tune::layout::IsomorphicLayout;
-use tune::layout::Layer;
 use tune::pergen::Mos;
 use tune::pitch::Ratio;
 use tune::scala::Kbm;
 use tune::scala::Scl;
 
 use crate::CustomKeyboardOptions;
-use crate::profile::ColorPalette;
 use crate::toggle::Toggle;
 
 #[derive(Clone)]
@@ -42,7 +41,7 @@ impl TuningLayout {
         scl: Scl,
         kbm: Kbm,
         options: CustomKeyboardOptions,
-        palette: &ColorPalette,
+        palette: &ColorPalette<Srgba>,
     ) -> TuningLayout {
         let avg_step_size = if scl.period().is_negligible() {
             Ratio::from_octaves(1)
@@ -71,7 +70,7 @@ impl TuningLayout {
                     name: scale_name,
                     mos: mos.coprime(),
                     orig_mos: mos,
-                    colors: generate_colors(&isomorphic_layout, palette),
+                    colors: palette.get_colors(&isomorphic_layout),
                 })
             })
             .collect();
@@ -250,20 +249,3 @@ pub enum OnScreenKeyboards {
     IsomorphicAndReference,
     ScaleAndReference,
 }
-
-fn generate_colors(layout: &IsomorphicLayout, palette: &ColorPalette) -> Vec<Srgba> {
-    layout
-        .get_layers()
-        .into_iter()
-        .map(|layer| {
-            let get_color = |colors: &[Srgba], index| colors[usize::from(index) % colors.len()];
-
-            match layer {
-                Layer::Natural => palette.natural_color,
-                Layer::Sharp(index) => get_color(&palette.sharp_colors, index),
-                Layer::Flat(index) => get_color(&palette.flat_colors, index),
-                Layer::Enharmonic(index) => get_color(&palette.enharmonic_colors, index),
-            }
-        })
-        .collect()
-}
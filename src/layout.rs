@@ -4,7 +4,9 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::sync::LazyLock;
 
+use crate::math;
 use crate::pergen::Accidentals;
 use crate::pergen::AccidentalsFormat;
 use crate::pergen::AccidentalsOrder;
@@ -12,6 +14,7 @@ use crate::pergen::Mos;
 use crate::pergen::NoteFormatter;
 use crate::pergen::PerGen;
 use crate::pitch::Ratio;
+use crate::temperament::Comma;
 use crate::temperament::Val;
 
 /// Find note names and step sizes for a given division of the octave using different genchains.
@@ -31,42 +34,43 @@ impl IsomorphicLayout {
     }
 
     pub fn find_by_step_size(step_size: Ratio) -> Vec<IsomorphicLayout> {
-        let patent_val = Val::patent(step_size, 5);
-
-        let patent_val_errors: Vec<_> = patent_val
-            .errors_in_steps()
-            .map(|error| error.abs())
-            .collect();
-        let evaluate_b_val = patent_val_errors[1] > 1.0 / 3.0; // Ensures b_val error is at most twice as large as patent_val error
-
-        let b_val = evaluate_b_val.then(|| {
-            let mut b_val = patent_val.clone();
-            b_val.pick_alternative(1);
-            b_val
-        });
+        Self::find_by_genchains(step_size, Genchain::defaults())
+    }
 
-        [
-            // Sorted from highest to lowest sharpness within a group
-            Genchain::Mavila9,
-            Genchain::Meantone7,
-            Genchain::Meantone5,
-            Genchain::Porcupine8,
-            Genchain::Tetracot7,
-            Genchain::Hanson7,
-        ]
-        .into_iter()
-        .flat_map(|genchain| {
-            genchain.create_layout(&patent_val, false).or_else(|| {
-                b_val
-                    .as_ref()
-                    .and_then(|b_val| genchain.create_layout(b_val, true))
+    /// Like [`IsomorphicLayout::find_by_step_size`] but with a custom list of [`Genchain`]s to try.
+    ///
+    /// The built-in genchains are evaluated using the 5-limit patent val of `step_size`.
+    /// [`Genchain::Custom`] genchains referring to higher primes are evaluated using the patent val of their own prime limit.
+    pub fn find_by_genchains(
+        step_size: Ratio,
+        genchains: impl IntoIterator<Item = Genchain>,
+    ) -> Vec<IsomorphicLayout> {
+        genchains
+            .into_iter()
+            .flat_map(|genchain| {
+                let patent_val = Val::patent(step_size, genchain.prime_limit());
+
+                let patent_val_errors: Vec<_> = patent_val
+                    .errors_in_steps()
+                    .map(|error| error.abs())
+                    .collect();
+                let evaluate_b_val = patent_val_errors[1] > 1.0 / 3.0; // Ensures b_val error is at most twice as large as patent_val error
+
+                genchain.create_layout(&patent_val, false).or_else(|| {
+                    evaluate_b_val
+                        .then(|| {
+                            let mut b_val = patent_val;
+                            b_val.pick_alternative(1);
+                            b_val
+                        })
+                        .and_then(|b_val| genchain.create_layout(&b_val, true))
+                })
             })
-        })
-        .collect()
+            .collect()
     }
 
-    pub fn genchain(&self) -> Genchain {
-        self.genchain
+    pub fn genchain(&self) -> &Genchain {
+        &self.genchain
     }
 
     pub fn b_val(&self) -> bool {
@@ -85,21 +89,12 @@ impl IsomorphicLayout {
         self.mos
     }
 
-    pub fn get_scale_name(&self) -> &'static str {
-        match (self.genchain, self.mos.sharpness().cmp(&0)) {
-            (_, Ordering::Equal) => "equalized",
-            (Genchain::Mavila9, Ordering::Greater) => "armotonic",
-            (Genchain::Mavila9, Ordering::Less) => "balzano",
-            (Genchain::Meantone7, Ordering::Greater) => "diatonic",
-            (Genchain::Meantone7, Ordering::Less) => "antidiatonic",
-            (Genchain::Meantone5, Ordering::Greater) => "antipentic",
-            (Genchain::Meantone5, Ordering::Less) => "pentic",
-            (Genchain::Porcupine8, Ordering::Greater) => "pine",
-            (Genchain::Porcupine8, Ordering::Less) => "antipine",
-            (Genchain::Tetracot7, Ordering::Greater) => "archeotonic",
-            (Genchain::Tetracot7, Ordering::Less) => "onyx",
-            (Genchain::Hanson7, Ordering::Greater) => "smitonic",
-            (Genchain::Hanson7, Ordering::Less) => "mosh",
+    pub fn get_scale_name(&self) -> &str {
+        let spec = self.genchain.spec();
+        match self.mos.sharpness().cmp(&0) {
+            Ordering::Equal => "equalized",
+            Ordering::Greater => &spec.positive_scale_name,
+            Ordering::Less => &spec.negative_scale_name,
         }
     }
 
@@ -323,6 +318,64 @@ fn layer_index_to_semantic_layer(layer_index: u16, num_layers: u16) -> Layer {
     }
 }
 
+/// Maps the [`Layer`]s of an [`IsomorphicLayout`] to colors of any type `C`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::ColorPalette;
+/// # use tune::layout::IsomorphicLayout;
+/// let palette = ColorPalette {
+///     natural_color: "white",
+///     sharp_colors: vec!["blue"],
+///     flat_colors: vec!["green"],
+///     enharmonic_colors: vec!["red"],
+/// };
+///
+/// assert_eq!(
+///     palette.get_colors(&IsomorphicLayout::find_by_edo(12)[0]),
+///     [
+///         "white", "red", "white", "white", "red", "white", // D D#/Eb E F F#/Gb G
+///         "red", "white", "red", "white", "white", "red", // G#/Ab A A#/Bb B C C#/Db
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ColorPalette<C> {
+    pub natural_color: C,
+    pub sharp_colors: Vec<C>,
+    pub flat_colors: Vec<C>,
+    pub enharmonic_colors: Vec<C>,
+}
+
+impl<C: Clone> ColorPalette<C> {
+    /// Returns the color for the given [`Layer`], cycling through the available colors if the layer index exceeds the number of colors.
+    pub fn get_color(&self, layer: Layer) -> C {
+        let get_color = |colors: &[C], index: u16| {
+            colors
+                .get(usize::from(index) % colors.len().max(1))
+                .unwrap_or(&self.natural_color)
+                .clone()
+        };
+
+        match layer {
+            Layer::Natural => self.natural_color.clone(),
+            Layer::Sharp(index) => get_color(&self.sharp_colors, index),
+            Layer::Flat(index) => get_color(&self.flat_colors, index),
+            Layer::Enharmonic(index) => get_color(&self.enharmonic_colors, index),
+        }
+    }
+
+    /// Returns the colors of all layers of the given [`IsomorphicLayout`] in stepwise order.
+    pub fn get_colors(&self, layout: &IsomorphicLayout) -> Vec<C> {
+        layout
+            .get_layers()
+            .into_iter()
+            .map(|layer| self.get_color(layer))
+            .collect()
+    }
+}
+
 /// A descriptor for a consecutive genchain segment after decomposing a MOS into its color layers.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Layer {
@@ -339,14 +392,53 @@ pub enum Layer {
 /// Genchain used to derive note names, colors and step sizes for a given tuning.
 ///
 /// The name is to be understood as a representative for an entire family of temperaments that share the same genchain.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::Genchain;
+/// # use tune::layout::GenchainSpec;
+/// # use tune::layout::IsomorphicLayout;
+/// # use tune::pitch::Ratio;
+/// // Bohlen-Pierce Lambda scale generated by 9/7 within a tritave
+/// let lambda = Genchain::Custom(
+///     GenchainSpec::new(
+///         "Lambda[9]",
+///         [0, 1],
+///         [0, 2, 0, -1],
+///         1,
+///         ['F', 'H', 'A', 'C', 'E', 'G', 'J', 'B', 'D'],
+///         3,
+///         ["lambda", "antilambda"],
+///     )
+///     .unwrap(),
+/// );
+///
+/// let bp_step = Ratio::from_float(3.0).divided_into_equal_steps(13);
+/// let layout = &IsomorphicLayout::find_by_genchains(bp_step, [lambda.clone()])[0];
+///
+/// assert_eq!(layout.genchain(), &lambda);
+/// assert_eq!(layout.genchain().to_string(), "Lambda[9]");
+/// assert_eq!(layout.get_scale_name(), "lambda");
+/// assert_eq!(layout.pergen().period(), 13);
+/// assert_eq!(layout.pergen().generator(), 3);
+/// assert_eq!(layout.mos().primary_step(), 2);
+/// assert_eq!(layout.mos().secondary_step(), 1);
+///
+/// let note_names: Vec<_> = (0..13).map(|index| layout.get_note_name(index)).collect();
+/// assert_eq!(
+///     note_names,
+///     ["C", "C#/Db", "D", "E", "F", "F#/Gb", "G", "H", "H#/Jb", "J", "A", "A#/Bb", "B"]
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Genchain {
     /// Similar to [`Genchain::Meantone7`] but with 9 natural notes instead of 7.
     ///
     /// This genchain can be used when rather flat versions of 3/2 are involved and [`Genchain::Meantone7`] would result in a MOS with negative sharpness.
     ///
     /// The generated notes are [ &hellip; φb, β, F, C, G, D, A, E, B, φ, β#, &hellip; ].
-
+    ///
     /// Due to the additional notes, the conventional relationships between interval names and just ratios no longer apply.
     /// For instance, a Mavila\[9\] major third will sound similar to a Meantone\[7\] minor third and a Mavila\[9\] minor fourth will sound similar to a Meantone\[7\] major third.
     Mavila9,
@@ -389,16 +481,136 @@ pub enum Genchain {
     ///
     /// The sixth is used to generate the notes [ &hellip; Eb C A F D B G E C# &hellip; ].
     Hanson7,
+
+    /// User-defined genchain, e.g. for non-octave or higher-limit tunings. See [`GenchainSpec`].
+    Custom(GenchainSpec),
+}
+
+/// Data describing a [`Genchain`].
+///
+/// Intervals are given as prime factors, e.g. `[-1, 1]` for 3/2 or `[0, 1]` for 3/1.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GenchainSpec {
+    name: String,
+    period: Vec<i8>,
+    generator: Vec<i8>,
+    num_divisions: u16,
+    natural_notes: Vec<char>,
+    origin: u16,
+    positive_scale_name: String,
+    negative_scale_name: String,
+}
+
+/// Error reported when creating a [`GenchainSpec`] fails.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GenchainSpecError {
+    /// The period or generator is a unison, i.e. all of its prime factors are zero.
+    UnisonInterval,
+
+    /// The period or generator contains a prime factor above 251.
+    PrimeLimitTooHigh,
+
+    /// The number of generator divisions is zero.
+    NoDivisions,
+
+    /// The list of natural notes is empty.
+    NoNaturalNotes,
+
+    /// The origin does not refer to one of the natural notes.
+    OriginOutOfRange,
 }
 
+static BUILTIN_SPECS: LazyLock<[GenchainSpec; 6]> = LazyLock::new(|| {
+    [
+        octave_based(
+            "Mavila[9]",
+            FIFTH,
+            1,
+            &['β', 'F', 'C', 'G', 'D', 'A', 'E', 'B', 'φ'],
+            4,
+            ["armotonic", "balzano"],
+        ),
+        octave_based(
+            "Meantone[7]",
+            FIFTH,
+            1,
+            &['F', 'C', 'G', 'D', 'A', 'E', 'B'],
+            3,
+            ["diatonic", "antidiatonic"],
+        ),
+        octave_based(
+            "Meantone[5]",
+            FIFTH,
+            1,
+            &['C', 'G', 'D', 'A', 'E'],
+            2,
+            ["antipentic", "pentic"],
+        ),
+        octave_based(
+            "Porcupine[8]",
+            &[2, -1],
+            3,
+            &['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'],
+            3,
+            ["pine", "antipine"],
+        ),
+        octave_based(
+            "Tetracot[7]",
+            FIFTH,
+            4,
+            &['A', 'B', 'C', 'D', 'E', 'F', 'G'],
+            3,
+            ["archeotonic", "onyx"],
+        ),
+        octave_based(
+            "Hanson[7]",
+            &[6, -1],
+            6,
+            &['C', 'A', 'F', 'D', 'B', 'G', 'E'],
+            3,
+            ["smitonic", "mosh"],
+        ),
+    ]
+});
+
+const OCTAVE: &[i8] = &[1];
+const FIFTH: &[i8] = &[-1, 1];
+
 impl Genchain {
-    fn create_layout(self, val: &Val, b_val: bool) -> Option<IsomorphicLayout> {
-        let pergen = self.get_pergen(val)?;
-        let spec = self.get_parameters();
+    /// The built-in genchains used by [`IsomorphicLayout::find_by_step_size`].
+    ///
+    /// The genchains are sorted from highest to lowest sharpness within a group.
+    pub fn defaults() -> [Genchain; 6] {
+        [
+            Genchain::Mavila9,
+            Genchain::Meantone7,
+            Genchain::Meantone5,
+            Genchain::Porcupine8,
+            Genchain::Tetracot7,
+            Genchain::Hanson7,
+        ]
+    }
+
+    /// Returns the data describing this genchain.
+    pub fn spec(&self) -> &GenchainSpec {
+        match self {
+            Genchain::Mavila9 => &BUILTIN_SPECS[0],
+            Genchain::Meantone7 => &BUILTIN_SPECS[1],
+            Genchain::Meantone5 => &BUILTIN_SPECS[2],
+            Genchain::Porcupine8 => &BUILTIN_SPECS[3],
+            Genchain::Tetracot7 => &BUILTIN_SPECS[4],
+            Genchain::Hanson7 => &BUILTIN_SPECS[5],
+            Genchain::Custom(spec) => spec,
+        }
+    }
+
+    fn create_layout(&self, val: &Val, b_val: bool) -> Option<IsomorphicLayout> {
+        let spec = self.spec();
+        let pergen = spec.get_pergen(val)?;
 
         let mos = pergen.get_moses().find(|mos| {
             usize::from(mos.num_primary_steps()) + usize::from(mos.num_secondary_steps())
-                == spec.genchain.len()
+                == spec.natural_notes.len()
         })?;
 
         let (sharp_sign, flat_sign, order) = if mos.primary_step() >= mos.secondary_step() {
@@ -408,16 +620,16 @@ impl Genchain {
         };
 
         Some(IsomorphicLayout {
-            genchain: self,
+            genchain: self.clone(),
             b_val,
             pergen,
             mos,
             acc_format: AccidentalsFormat {
                 num_symbols: u16::try_from(mos.num_steps()).ok()?,
-                genchain_origin: spec.genchain_origin,
+                genchain_origin: spec.origin,
             },
             formatter: NoteFormatter {
-                note_names: spec.genchain.into(),
+                note_names: spec.natural_notes.clone().into(),
                 sharp_sign,
                 flat_sign,
                 cycle_sign: '*',
@@ -426,59 +638,180 @@ impl Genchain {
         })
     }
 
-    fn get_pergen(&self, val: &Val) -> Option<PerGen> {
-        let values = val.values();
-        let octave = values[0];
-        let tritave = values[1];
-
-        Some(match self {
-            Genchain::Mavila9 | Genchain::Meantone7 | Genchain::Meantone5 => {
-                let fifth = tritave.checked_sub(octave)?;
-                PerGen::new(octave, fifth)
-            }
-            Genchain::Porcupine8 => {
-                let third_fourth = exact_div(octave.checked_mul(2)?.checked_sub(tritave)?, 3)?;
-                PerGen::new(octave, third_fourth)
-            }
-            Genchain::Tetracot7 => {
-                let quarter_fifth = exact_div(tritave.checked_sub(octave)?, 4)?;
-                PerGen::new(octave, quarter_fifth)
+    fn prime_limit(&self) -> u8 {
+        match self {
+            Genchain::Custom(spec) => spec.prime_limit().max(5),
+            _ => 5,
+        }
+    }
+}
+
+fn octave_based(
+    name: &str,
+    generator: &[i8],
+    num_divisions: u16,
+    natural_notes: &[char],
+    origin: u16,
+    [positive_scale_name, negative_scale_name]: [&str; 2],
+) -> GenchainSpec {
+    GenchainSpec {
+        name: name.to_owned(),
+        period: OCTAVE.to_vec(),
+        generator: generator.to_vec(),
+        num_divisions,
+        natural_notes: natural_notes.to_vec(),
+        origin,
+        positive_scale_name: positive_scale_name.to_owned(),
+        negative_scale_name: negative_scale_name.to_owned(),
+    }
+}
+
+impl GenchainSpec {
+    /// Creates a [`GenchainSpec`] from runtime data, e.g. CLI args or files.
+    ///
+    /// - `period`: The interval of equivalence, e.g. 2/1 for octave-based or 3/1 for tritave-based tunings.
+    /// - `generator`: The interval that, when divided into `num_divisions` equal parts, yields the generator.
+    /// - `natural_notes`: The natural notes in genchain order. The number of natural notes determines the size of the MOS.
+    /// - `origin`: The index of the natural note located at the origin of the layout.
+    /// - `scale_names`: The scale names to use when the MOS has positive or negative sharpness, respectively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::layout::GenchainSpec;
+    /// # use tune::layout::GenchainSpecError;
+    /// let create = |period: &[i8], natural_notes: &[char], origin| {
+    ///     GenchainSpec::new(
+    ///         "Meantone[7]",
+    ///         period,
+    ///         [-1, 1],
+    ///         1,
+    ///         natural_notes,
+    ///         origin,
+    ///         ["diatonic", "antidiatonic"],
+    ///     )
+    /// };
+    ///
+    /// let diatonic = ['F', 'C', 'G', 'D', 'A', 'E', 'B'];
+    ///
+    /// let spec = create(&[1], &diatonic, 3).unwrap();
+    /// assert_eq!(spec.name(), "Meantone[7]");
+    /// assert_eq!(spec.natural_notes()[usize::from(spec.origin())], 'D');
+    ///
+    /// assert_eq!(
+    ///     create(&[0, 0], &diatonic, 3),
+    ///     Err(GenchainSpecError::UnisonInterval)
+    /// );
+    /// assert_eq!(
+    ///     create(&[1], &[], 0),
+    ///     Err(GenchainSpecError::NoNaturalNotes)
+    /// );
+    /// assert_eq!(
+    ///     create(&[1], &diatonic, 7),
+    ///     Err(GenchainSpecError::OriginOutOfRange)
+    /// );
+    /// ```
+    pub fn new(
+        name: impl Into<String>,
+        period: impl Into<Vec<i8>>,
+        generator: impl Into<Vec<i8>>,
+        num_divisions: u16,
+        natural_notes: impl Into<Vec<char>>,
+        origin: u16,
+        [positive_scale_name, negative_scale_name]: [impl Into<String>; 2],
+    ) -> Result<Self, GenchainSpecError> {
+        let period = period.into();
+        let generator = generator.into();
+        let natural_notes = natural_notes.into();
+
+        for interval in [&period, &generator] {
+            if interval.iter().all(|&factor| factor == 0) {
+                return Err(GenchainSpecError::UnisonInterval);
             }
-            Genchain::Hanson7 => {
-                let sixth_twelfth = exact_div(tritave, 6)?;
-                let bright_generator = octave.checked_sub(sixth_twelfth)?;
-                PerGen::new(octave, bright_generator)
+            if interval.len() > math::U8_PRIMES.len() {
+                return Err(GenchainSpecError::PrimeLimitTooHigh);
             }
+        }
+        if num_divisions == 0 {
+            return Err(GenchainSpecError::NoDivisions);
+        }
+        if natural_notes.is_empty() {
+            return Err(GenchainSpecError::NoNaturalNotes);
+        }
+        if usize::from(origin) >= natural_notes.len() {
+            return Err(GenchainSpecError::OriginOutOfRange);
+        }
+
+        Ok(Self {
+            name: name.into(),
+            period,
+            generator,
+            num_divisions,
+            natural_notes,
+            origin,
+            positive_scale_name: positive_scale_name.into(),
+            negative_scale_name: negative_scale_name.into(),
         })
     }
 
-    fn get_parameters(self) -> GenchainParameters {
-        match self {
-            Genchain::Mavila9 => GenchainParameters {
-                genchain: &['β', 'F', 'C', 'G', 'D', 'A', 'E', 'B', 'φ'],
-                genchain_origin: 4,
-            },
-            Genchain::Meantone7 => GenchainParameters {
-                genchain: &['F', 'C', 'G', 'D', 'A', 'E', 'B'],
-                genchain_origin: 3,
-            },
-            Genchain::Meantone5 => GenchainParameters {
-                genchain: &['C', 'G', 'D', 'A', 'E'],
-                genchain_origin: 2,
-            },
-            Genchain::Porcupine8 => GenchainParameters {
-                genchain: &['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'],
-                genchain_origin: 3,
-            },
-            Genchain::Tetracot7 => GenchainParameters {
-                genchain: &['A', 'B', 'C', 'D', 'E', 'F', 'G'],
-                genchain_origin: 3,
-            },
-            Genchain::Hanson7 => GenchainParameters {
-                genchain: &['C', 'A', 'F', 'D', 'B', 'G', 'E'],
-                genchain_origin: 3,
-            },
-        }
+    /// The display name of the genchain, e.g. Meantone\[7\].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The interval of equivalence as prime factors.
+    pub fn period(&self) -> &[i8] {
+        &self.period
+    }
+
+    /// The interval that, when divided into [`GenchainSpec::num_divisions`] equal parts, yields the generator.
+    pub fn generator(&self) -> &[i8] {
+        &self.generator
+    }
+
+    /// The number of generators that make up [`GenchainSpec::generator`].
+    pub fn num_divisions(&self) -> u16 {
+        self.num_divisions
+    }
+
+    /// The natural notes in genchain order.
+    pub fn natural_notes(&self) -> &[char] {
+        &self.natural_notes
+    }
+
+    /// The index of the natural note located at the origin of the layout.
+    pub fn origin(&self) -> u16 {
+        self.origin
+    }
+
+    /// The scale name to use when the MOS has positive sharpness.
+    pub fn positive_scale_name(&self) -> &str {
+        &self.positive_scale_name
+    }
+
+    /// The scale name to use when the MOS has negative sharpness.
+    pub fn negative_scale_name(&self) -> &str {
+        &self.negative_scale_name
+    }
+
+    fn get_pergen(&self, val: &Val) -> Option<PerGen> {
+        let period = u16::try_from(val.map(&self.comma(&self.period))?).ok()?;
+        let generator = u16::try_from(val.map(&self.comma(&self.generator))?).ok()?;
+
+        Some(PerGen::new(
+            period,
+            exact_div(generator, self.num_divisions)?,
+        ))
+    }
+
+    fn prime_limit(&self) -> u8 {
+        let period = self.comma(&self.period);
+        let generator = self.comma(&self.generator);
+        period.prime_limit().max(generator.prime_limit())
+    }
+
+    fn comma(&self, prime_factors: &[i8]) -> Comma {
+        Comma::new(self.name.clone(), prime_factors.to_vec())
     }
 }
 
@@ -488,25 +821,369 @@ fn exact_div(numer: u16, denom: u16) -> Option<u16> {
 
 impl Display for Genchain {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let display_name = match self {
-            Genchain::Mavila9 => "Mavila[9]",
-            Genchain::Meantone7 => "Meantone[7]",
-            Genchain::Meantone5 => "Meantone[5]",
-            Genchain::Porcupine8 => "Porcupine[8]",
-            Genchain::Tetracot7 => "Tetracot[7]",
-            Genchain::Hanson7 => "Hanson[7]",
+        write!(f, "{}", self.spec().name)
+    }
+}
+
+/// Maps the 2D coordinates of physical keys to scale degrees.
+///
+/// The mapping is linear, i.e. every step along the `x` or `y` axis of the key grid adds a fixed number of scale degrees.
+/// By default, the `x` axis corresponds to the primary step and the `y` axis corresponds to the secondary step of a [`Mos`], mirroring the behavior of [`Mos::get_key`].
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::IsomorphicKeyboard;
+/// # use tune::layout::KeyGrid;
+/// # use tune::pergen::Mos;
+/// // 31-EDO Bosanquet layout
+/// let mos = Mos::new(5, 2, 5, 3).unwrap();
+/// let keyboard = IsomorphicKeyboard::from_mos(KeyGrid::Hex, mos);
+///
+/// assert_eq!(keyboard.get_key(0, 0), 0);
+/// assert_eq!(keyboard.get_key(1, 0), 5);
+/// assert_eq!(keyboard.get_key(0, 1), 3);
+/// assert_eq!(keyboard.get_key(-2, -1), mos.get_key(-2, -1));
+///
+/// // Rotate by 60°, s.t. the primary step is located on the y axis
+/// let rotated = keyboard.rotated(1);
+///
+/// assert_eq!(rotated.get_key(1, 0), 2);
+/// assert_eq!(rotated.get_key(0, 1), 5);
+///
+/// // Six rotations lead to the original layout
+/// assert_eq!(keyboard.rotated(6), keyboard);
+/// assert_eq!(keyboard.rotated(-1), keyboard.rotated(5));
+///
+/// // Mirror along the x axis
+/// let mirrored = keyboard.mirrored();
+///
+/// assert_eq!(mirrored.get_key(1, 0), 5);
+/// assert_eq!(mirrored.get_key(0, 1), 2);
+/// assert_eq!(mirrored.mirrored(), keyboard);
+///
+/// // Shift each row by one key to the left
+/// let tilted = keyboard.tilted(-1);
+///
+/// assert_eq!(tilted.get_key(1, 0), 5);
+/// assert_eq!(tilted.get_key(0, 1), 8);
+/// assert_eq!(tilted.tilted(1), keyboard);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct IsomorphicKeyboard {
+    grid: KeyGrid,
+    x_step: i32,
+    y_step: i32,
+}
+
+impl IsomorphicKeyboard {
+    /// Creates a keyboard where the `x` axis corresponds to `primary_step` and the `y` axis corresponds to `secondary_step`.
+    pub fn new(grid: KeyGrid, primary_step: i32, secondary_step: i32) -> Self {
+        Self {
+            grid,
+            x_step: primary_step,
+            y_step: secondary_step,
+        }
+    }
+
+    /// Creates a keyboard based on the primary and secondary step of the given [`Mos`].
+    pub fn from_mos(grid: KeyGrid, mos: Mos) -> Self {
+        Self::new(
+            grid,
+            i32::from(mos.primary_step()),
+            i32::from(mos.secondary_step()),
+        )
+    }
+
+    /// The geometry of the grid the keyboard is laid out on.
+    pub fn grid(&self) -> KeyGrid {
+        self.grid
+    }
+
+    /// The number of scale degrees added per step along the `x` axis.
+    pub fn x_step(&self) -> i32 {
+        self.x_step
+    }
+
+    /// The number of scale degrees added per step along the `y` axis.
+    pub fn y_step(&self) -> i32 {
+        self.y_step
+    }
+
+    /// Rotates the layout by `num_rotations` times the symmetry angle of the grid (60° for [`KeyGrid::Hex`] and 90° for [`KeyGrid::Rect`]).
+    ///
+    /// Positive rotations turn the `x` axis towards the `y` axis.
+    pub fn rotated(self, num_rotations: i32) -> Self {
+        let period = match self.grid {
+            KeyGrid::Hex => 6,
+            KeyGrid::Rect => 4,
+        };
+
+        let (mut x_step, mut y_step) = (self.x_step, self.y_step);
+        for _ in 0..num_rotations.rem_euclid(period) {
+            (x_step, y_step) = match self.grid {
+                KeyGrid::Hex => (x_step - y_step, x_step),
+                KeyGrid::Rect => (-y_step, x_step),
+            };
+        }
+
+        Self {
+            x_step,
+            y_step,
+            ..self
+        }
+    }
+
+    /// Mirrors the layout along the `x` axis.
+    pub fn mirrored(self) -> Self {
+        let y_step = match self.grid {
+            KeyGrid::Hex => self.x_step - self.y_step,
+            KeyGrid::Rect => -self.y_step,
         };
-        write!(f, "{display_name}")
+
+        Self { y_step, ..self }
+    }
+
+    /// Shifts each row of the layout by `num_keys` keys along the `x` axis relative to the previous row.
+    pub fn tilted(self, num_keys: i32) -> Self {
+        Self {
+            y_step: self.y_step - num_keys * self.x_step,
+            ..self
+        }
+    }
+
+    /// Get the scale degree of the key at location `(x, y)`.
+    pub fn get_key(&self, x: i16, y: i16) -> i32 {
+        i32::from(x) * self.x_step + i32::from(y) * self.y_step
     }
 }
 
-struct GenchainParameters {
-    genchain: &'static [char],
-    genchain_origin: u16,
+/// The geometry of a 2D key grid.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum KeyGrid {
+    /// Hexagonal grid using axial coordinates, i.e. the `y` axis is rotated by 60° relative to the `x` axis.
+    Hex,
+    /// Rectangular grid, i.e. the `y` axis is rotated by 90° relative to the `x` axis.
+    Rect,
 }
 
+/// Physical key positions of well-known isomorphic controllers.
+///
+/// The keys of each controller are addressed by a section and an index within that section.
+/// The coordinates are chosen s.t. a key near the center of the controller is located at `(0, 0)`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::Controller;
+/// # use tune::layout::IsomorphicKeyboard;
+/// # use tune::layout::KeyGrid;
+/// assert_eq!(Controller::Lumatone.keys().count(), 280);
+/// assert_eq!(Controller::Axis49.keys().count(), 98);
+/// assert_eq!(Controller::Exquis.keys().count(), 61);
+/// assert_eq!(Controller::LinnStrument.keys().count(), 200);
+/// assert_eq!(Controller::LinnStrument128.keys().count(), 128);
+///
+/// // Middle D of the Lumatone is located on board 2, key 20
+/// assert_eq!(Controller::Lumatone.get_coord(2, 20), Some((0, 0)));
+/// assert_eq!(Controller::Lumatone.get_coord(2, 21), Some((1, 0)));
+/// assert_eq!(Controller::Lumatone.get_coord(3, 20), Some((5, 2)));
+/// assert_eq!(Controller::Lumatone.get_coord(2, 56), None);
+///
+/// // LinnStrument in its default layout: 1 semitone per column, 5 semitones per row
+/// let keyboard = IsomorphicKeyboard::new(Controller::LinnStrument.grid(), 1, 5);
+/// let degrees: Vec<_> = Controller::LinnStrument
+///     .keys()
+///     .filter(|key| key.index == 0)
+///     .map(|key| keyboard.get_key(key.x, key.y))
+///     .collect();
+///
+/// assert_eq!(degrees, [-32, -27, -22, -17, -12, -7, -2, 3]);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Controller {
+    /// Lumatone with 5 boards (sections) of 56 keys each.
+    ///
+    /// The origin is located at middle D (board 2, key 20).
+    /// Each board is shifted by 5 keys along the `x` axis and 2 keys along the `y` axis relative to the previous board.
+    Lumatone,
+    /// C-Thru AXiS-49 with 98 keys in 7 rows of 14 keys, numbered row by row starting at the bottom left.
+    Axis49,
+    /// Intuitive Instruments Exquis with 61 keys in 11 rows of alternately 6 and 5 keys, numbered row by row starting at the bottom left.
+    Exquis,
+    /// Roger Linn Design LinnStrument with 8 rows (sections) of 25 columns each.
+    LinnStrument,
+    /// Roger Linn Design LinnStrument 128 with 8 rows (sections) of 16 columns each.
+    LinnStrument128,
+}
+
+/// A physical key of a [`Controller`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ControllerKey {
+    /// The board or row of the key.
+    pub section: u8,
+    /// The index of the key within its section.
+    pub index: u8,
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Controller {
+    /// The geometry of the key grid of the controller.
+    pub fn grid(self) -> KeyGrid {
+        match self {
+            Controller::Lumatone | Controller::Axis49 | Controller::Exquis => KeyGrid::Hex,
+            Controller::LinnStrument | Controller::LinnStrument128 => KeyGrid::Rect,
+        }
+    }
+
+    /// Returns the number of sections and the number of keys per section.
+    pub fn dimensions(self) -> (u8, u8) {
+        match self {
+            Controller::Lumatone => (5, LUMATONE_KEY_COORDS.len() as u8),
+            Controller::Axis49 => (1, 98),
+            Controller::Exquis => (1, 61),
+            Controller::LinnStrument => (8, 25),
+            Controller::LinnStrument128 => (8, 16),
+        }
+    }
+
+    /// Enumerates all physical keys of the controller.
+    pub fn keys(self) -> impl Iterator<Item = ControllerKey> {
+        let (num_sections, num_keys) = self.dimensions();
+        (0..num_sections).flat_map(move |section| {
+            (0..num_keys).map(move |index| {
+                let (x, y) = self.get_coord(section, index).unwrap();
+                ControllerKey {
+                    section,
+                    index,
+                    x,
+                    y,
+                }
+            })
+        })
+    }
+
+    /// Get the grid coordinates of the key at the given `section` and `index`.
+    ///
+    /// Returns [`None`] if the key does not exist on the controller.
+    pub fn get_coord(self, section: u8, index: u8) -> Option<(i16, i16)> {
+        let (num_sections, num_keys) = self.dimensions();
+        if section >= num_sections || index >= num_keys {
+            return None;
+        }
+        let (section, index) = (i16::from(section), i16::from(index));
+
+        match self {
+            Controller::Lumatone => {
+                const ORIGIN: (u8, u8) = LUMATONE_KEY_COORDS[20];
+
+                let (x, y) = LUMATONE_KEY_COORDS[usize::from(index.unsigned_abs())];
+                Some((
+                    5 * (section - 2) + i16::from(x) - i16::from(ORIGIN.0),
+                    2 * (section - 2) + i16::from(y) - i16::from(ORIGIN.1),
+                ))
+            }
+            Controller::Axis49 | Controller::Exquis => {
+                let (row_lengths, origin_row, origin_column): (&[i16], i16, i16) = match self {
+                    Controller::Axis49 => (&[14; 7], 3, 7),
+                    _ => (&[6, 5, 6, 5, 6, 5, 6, 5, 6, 5, 6], 5, 2),
+                };
+                let mut column = index;
+                let mut row = 0;
+                while column >= row_lengths[row] {
+                    column -= row_lengths[row];
+                    row += 1;
+                }
+                let row = row as i16;
+                // Rows are shifted by half a key which needs to be compensated in axial coordinates
+                Some((
+                    column - row.div_euclid(2) - (origin_column - origin_row.div_euclid(2)),
+                    row - origin_row,
+                ))
+            }
+            Controller::LinnStrument | Controller::LinnStrument128 => Some((
+                index - i16::from(num_keys) / 2,
+                section - i16::from(num_sections) / 2,
+            )),
+        }
+    }
+}
+
+/// Board-local Lumatone key coordinates with rows being shifted by half a key.
+const LUMATONE_KEY_COORDS: [(u8, u8); 56] = [
+    (4, 0),
+    (5, 0),
+    // --
+    (4, 1),
+    (5, 1),
+    (6, 1),
+    (7, 1),
+    (8, 1),
+    // --
+    (3, 2),
+    (4, 2),
+    (5, 2),
+    (6, 2),
+    (7, 2),
+    (8, 2),
+    // --
+    (3, 3),
+    (4, 3),
+    (5, 3),
+    (6, 3),
+    (7, 3),
+    (8, 3),
+    // --
+    (2, 4),
+    (3, 4), // D
+    (4, 4),
+    (5, 4),
+    (6, 4),
+    (7, 4),
+    // --
+    (2, 5),
+    (3, 5),
+    (4, 5),
+    (5, 5),
+    (6, 5),
+    (7, 5),
+    // --
+    (1, 6),
+    (2, 6),
+    (3, 6),
+    (4, 6),
+    (5, 6),
+    (6, 6),
+    // --
+    (1, 7),
+    (2, 7),
+    (3, 7),
+    (4, 7),
+    (5, 7),
+    (6, 7),
+    // --
+    (0, 8),
+    (1, 8),
+    (2, 8),
+    (3, 8),
+    (4, 8),
+    (5, 8),
+    // --
+    (1, 9),
+    (2, 9),
+    (3, 9),
+    (4, 9),
+    (5, 9),
+    // --
+    (3, 10),
+    (4, 10),
+];
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+    use std::collections::HashSet;
     use std::fmt::Write;
 
     use super::*;
@@ -632,6 +1309,112 @@ mod tests {
         )
     }
 
+    #[test]
+    fn controller_key_coords() {
+        for controller in [
+            Controller::Lumatone,
+            Controller::Axis49,
+            Controller::Exquis,
+            Controller::LinnStrument,
+            Controller::LinnStrument128,
+        ] {
+            let (num_sections, num_keys) = controller.dimensions();
+
+            let coords: HashSet<_> = controller.keys().map(|key| (key.x, key.y)).collect();
+            assert_eq!(
+                coords.len(),
+                usize::from(num_sections) * usize::from(num_keys)
+            );
+            assert!(coords.contains(&(0, 0)));
+
+            assert_eq!(controller.get_coord(num_sections, 0), None);
+            assert_eq!(controller.get_coord(0, num_keys), None);
+            assert_eq!(controller.get_coord(u8::MAX, u8::MAX), None);
+        }
+
+        for section in 1..5 {
+            for index in 0..56 {
+                let (x0, y0) = Controller::Lumatone.get_coord(section - 1, index).unwrap();
+                let (x1, y1) = Controller::Lumatone.get_coord(section, index).unwrap();
+                assert_eq!((x1 - x0, y1 - y0), (5, 2));
+            }
+        }
+    }
+
+    #[test]
+    fn isomorphic_keyboard_transformations() {
+        fn neighbors(keyboard: IsomorphicKeyboard) -> BTreeSet<i32> {
+            let offsets: &[(i16, i16)] = match keyboard.grid() {
+                KeyGrid::Hex => &[(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)],
+                KeyGrid::Rect => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            };
+            offsets
+                .iter()
+                .map(|&(x, y)| keyboard.get_key(x, y))
+                .collect()
+        }
+
+        for (grid, period) in [(KeyGrid::Hex, 6), (KeyGrid::Rect, 4)] {
+            let keyboard = IsomorphicKeyboard::new(grid, 5, 3);
+
+            // Transformations preserve the intervals between adjacent keys
+            for num_rotations in 0..period {
+                let rotated = keyboard.rotated(num_rotations);
+                assert_eq!(neighbors(rotated), neighbors(keyboard));
+                assert_eq!(neighbors(rotated.mirrored()), neighbors(keyboard));
+            }
+            assert_eq!(keyboard.rotated(period), keyboard);
+            assert_eq!(keyboard.rotated(-1), keyboard.rotated(period - 1));
+            assert_eq!(keyboard.mirrored().mirrored(), keyboard);
+            assert_eq!(keyboard.tilted(2).tilted(-2), keyboard);
+            assert_eq!(keyboard.tilted(1).get_key(1, 1), keyboard.get_key(0, 1));
+        }
+    }
+
+    #[test]
+    fn custom_genchains_do_not_affect_builtin_genchains() {
+        let septimal = Genchain::Custom(
+            GenchainSpec::new(
+                "Septimal[5]",
+                [1],
+                [-2, 0, 0, 1],
+                1,
+                ['A', 'B', 'C', 'D', 'E'],
+                2,
+                ["positive", "negative"],
+            )
+            .unwrap(),
+        );
+
+        let summarize = |layout: &IsomorphicLayout| {
+            (
+                layout.genchain().clone(),
+                layout.b_val(),
+                layout.pergen().period(),
+                layout.pergen().generator(),
+            )
+        };
+
+        for num_steps_per_octave in 1..100 {
+            let step_size = Ratio::octave().divided_into_equal_steps(num_steps_per_octave);
+
+            let builtin: Vec<_> = IsomorphicLayout::find_by_step_size(step_size)
+                .iter()
+                .map(summarize)
+                .collect();
+            let mixed: Vec<_> = IsomorphicLayout::find_by_genchains(
+                step_size,
+                Genchain::defaults().into_iter().chain([septimal.clone()]),
+            )
+            .iter()
+            .map(summarize)
+            .filter(|(genchain, ..)| genchain != &septimal)
+            .collect();
+
+            assert_eq!(mixed, builtin);
+        }
+    }
+
     fn format_layer(layer: &Layer) -> String {
         match layer {
             Layer::Natural => "nat".to_owned(),
@@ -7,10 +7,12 @@ pub mod layout;
 pub mod math;
 pub mod midi;
 pub mod mts;
+pub mod notation;
 pub mod note;
 pub mod pergen;
 pub mod pitch;
 pub mod scala;
+pub mod svg;
 pub mod temperament;
 pub mod tuner;
 pub mod tuning;
@@ -1,8 +1,16 @@
-//! Basic abstractions for MIDI Channel Voice / Channel Mode messages.
+//! Basic abstractions for MIDI Channel Voice / Channel Mode messages, RPN/NRPN sequences and MIDI byte streams.
 //!
 //! References:
 //! - [MIDI messages](https://www.midi.org/specifications-old/item/table-1-summary-of-midi-message)
 
+mod rpn;
+mod stream;
+mod ump;
+
+pub use rpn::*;
+pub use stream::*;
+pub use ump::*;
+
 /// Status bits for "Note Off event".
 pub const NOTE_OFF: u8 = 0b1000;
 /// Status bits for "Note On event".
@@ -0,0 +1,581 @@
+//! Encoding and decoding of RPN/NRPN sequences and 14-bit Control Change messages.
+//!
+//! References:
+//! - [RPN format](https://www.midi.org/specifications-old/item/table-3-control-change-messages-data-bytes-2)
+
+use super::ChannelMessage;
+use super::ChannelMessageType;
+
+/// Controller number for "Data Entry MSB".
+pub const DATA_ENTRY_MSB: u8 = 0x06;
+/// Controller number for "Data Entry LSB".
+pub const DATA_ENTRY_LSB: u8 = 0x26;
+/// Controller number for "Data Increment".
+pub const DATA_INCREMENT: u8 = 0x60;
+/// Controller number for "Data Decrement".
+pub const DATA_DECREMENT: u8 = 0x61;
+/// Controller number for "Non-Registered Parameter Number LSB".
+pub const NRPN_LSB: u8 = 0x62;
+/// Controller number for "Non-Registered Parameter Number MSB".
+pub const NRPN_MSB: u8 = 0x63;
+/// Controller number for "Registered Parameter Number LSB".
+pub const RPN_LSB: u8 = 0x64;
+/// Controller number for "Registered Parameter Number MSB".
+pub const RPN_MSB: u8 = 0x65;
+
+/// Registered Parameter Number for "Pitch Bend Sensitivity".
+pub const PITCH_BEND_SENSITIVITY: u16 = 0x0000;
+/// Registered Parameter Number for "Channel Fine Tuning".
+pub const CHANNEL_FINE_TUNING: u16 = 0x0001;
+/// Registered Parameter Number for "Channel Coarse Tuning".
+pub const CHANNEL_COARSE_TUNING: u16 = 0x0002;
+/// Registered Parameter Number for "Tuning Program Change".
+pub const TUNING_PROGRAM_CHANGE: u16 = 0x0003;
+/// Registered Parameter Number for "Tuning Bank Select".
+pub const TUNING_BANK_SELECT: u16 = 0x0004;
+/// Registered Parameter Number for "MPE Configuration Message".
+pub const MPE_CONFIGURATION: u16 = 0x0006;
+
+/// Number of controllers whose values can be refined by an LSB controller, i.e. controllers 0..=31 are paired with controllers 32..=63.
+const NUM_14_BIT_CONTROLLERS: u8 = 32;
+
+/// A 14-bit Registered (RPN) or Non-Registered (NRPN) Parameter Number.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParameterNumber {
+    Registered(u16),
+    NonRegistered(u16),
+}
+
+impl ParameterNumber {
+    /// The null RPN which deselects the current parameter s.t. subsequent data entry messages are ignored.
+    pub const NULL: ParameterNumber = ParameterNumber::Registered(0x3fff);
+
+    /// Creates the messages that select the given parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::ParameterNumber;
+    /// let messages = ParameterNumber::NonRegistered(300).select(2).unwrap();
+    ///
+    /// assert_eq!(
+    ///     messages.map(|message| (message.channel(), message.message_type())),
+    ///     [
+    ///         (2, ChannelMessageType::ControlChange { controller: 0x63, value: 2 }),
+    ///         (2, ChannelMessageType::ControlChange { controller: 0x62, value: 44 }),
+    ///     ]
+    /// );
+    ///
+    /// // Deselect the parameter
+    /// let messages = ParameterNumber::NULL.select(2).unwrap();
+    ///
+    /// assert_eq!(
+    ///     messages.map(|message| (message.channel(), message.message_type())),
+    ///     [
+    ///         (2, ChannelMessageType::ControlChange { controller: 0x65, value: 127 }),
+    ///         (2, ChannelMessageType::ControlChange { controller: 0x64, value: 127 }),
+    ///     ]
+    /// );
+    /// ```
+    pub fn select(self, channel: u8) -> Option<[ChannelMessage; 2]> {
+        let (controller_msb, controller_lsb, number) = match self {
+            ParameterNumber::Registered(number) => (RPN_MSB, RPN_LSB, number),
+            ParameterNumber::NonRegistered(number) => (NRPN_MSB, NRPN_LSB, number),
+        };
+        let (number_msb, number_lsb) = split_u14(number)?;
+
+        Some([
+            control_change(channel, controller_msb, number_msb)?,
+            control_change(channel, controller_lsb, number_lsb)?,
+        ])
+    }
+
+    /// Creates the messages that select the given parameter and set its value to `value_msb` (coarse value only).
+    pub fn set_7_bit(self, channel: u8, value_msb: u8) -> Option<[ChannelMessage; 3]> {
+        let [select_msb, select_lsb] = self.select(channel)?;
+
+        Some([
+            select_msb,
+            select_lsb,
+            control_change(channel, DATA_ENTRY_MSB, value_msb)?,
+        ])
+    }
+
+    /// Creates the messages that select the given parameter and set its 14-bit value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::ParameterNumber;
+    /// # use tune::midi::PITCH_BEND_SENSITIVITY;
+    /// let messages = ParameterNumber::Registered(PITCH_BEND_SENSITIVITY)
+    ///     .set_14_bit(0, 12 * 128 + 50)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     messages.map(|message| message.message_type()),
+    ///     [
+    ///         ChannelMessageType::ControlChange { controller: 0x65, value: 0 },
+    ///         ChannelMessageType::ControlChange { controller: 0x64, value: 0 },
+    ///         ChannelMessageType::ControlChange { controller: 0x06, value: 12 },
+    ///         ChannelMessageType::ControlChange { controller: 0x26, value: 50 },
+    ///     ]
+    /// );
+    ///
+    /// // Value out of range
+    /// assert!(ParameterNumber::Registered(PITCH_BEND_SENSITIVITY).set_14_bit(0, 16384).is_none());
+    /// ```
+    pub fn set_14_bit(self, channel: u8, value: u16) -> Option<[ChannelMessage; 4]> {
+        let [select_msb, select_lsb] = self.select(channel)?;
+        let (value_msb, value_lsb) = split_u14(value)?;
+
+        Some([
+            select_msb,
+            select_lsb,
+            control_change(channel, DATA_ENTRY_MSB, value_msb)?,
+            control_change(channel, DATA_ENTRY_LSB, value_lsb)?,
+        ])
+    }
+
+    /// Creates the messages that select the given parameter and increment its value by `amount` steps.
+    pub fn increment(self, channel: u8, amount: u8) -> Option<[ChannelMessage; 3]> {
+        let [select_msb, select_lsb] = self.select(channel)?;
+
+        Some([
+            select_msb,
+            select_lsb,
+            control_change(channel, DATA_INCREMENT, amount)?,
+        ])
+    }
+
+    /// Creates the messages that select the given parameter and decrement its value by `amount` steps.
+    pub fn decrement(self, channel: u8, amount: u8) -> Option<[ChannelMessage; 3]> {
+        let [select_msb, select_lsb] = self.select(channel)?;
+
+        Some([
+            select_msb,
+            select_lsb,
+            control_change(channel, DATA_DECREMENT, amount)?,
+        ])
+    }
+}
+
+/// A complete control event emitted by a [`ControlChangeDecoder`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ControlEvent {
+    pub channel: u8,
+    pub event_type: ControlEventType,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ControlEventType {
+    /// A controller in the range 0..=31 whose MSB has been combined with its LSB controller (32..=63).
+    ///
+    /// The LSB part is reset to 0 whenever a new MSB is received.
+    Controller14Bit { controller: u8, value: u16 },
+    /// Any other controller that is not part of an RPN/NRPN sequence.
+    Controller7Bit { controller: u8, value: u8 },
+    /// The currently selected parameter has been changed.
+    Parameter {
+        parameter: ParameterNumber,
+        change: ParameterChange,
+    },
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParameterChange {
+    /// The parameter has been set to the given 14-bit value.
+    ///
+    /// A Data Entry MSB resets the LSB part to 0 s.t. 7-bit parameters are reported as `value_msb * 128`.
+    Set(u16),
+    /// The parameter has been incremented by the given number of steps.
+    Increment(u8),
+    /// The parameter has been decremented by the given number of steps.
+    Decrement(u8),
+}
+
+/// Decodes the Control Change messages of all 16 channels into 14-bit controller values and RPN/NRPN parameter changes.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::midi::ControlChangeDecoder;
+/// # use tune::midi::ControlEventType;
+/// # use tune::midi::ParameterChange;
+/// # use tune::midi::ParameterNumber;
+/// # use tune::midi::PITCH_BEND_SENSITIVITY;
+/// let mut decoder = ControlChangeDecoder::new();
+///
+/// let events: Vec<_> = ParameterNumber::Registered(PITCH_BEND_SENSITIVITY)
+///     .set_14_bit(5, 24 * 128)
+///     .unwrap()
+///     .iter()
+///     .filter_map(|&message| decoder.decode(message))
+///     .collect();
+///
+/// assert_eq!(events.len(), 2);
+/// assert_eq!(events[1].channel, 5);
+/// assert_eq!(
+///     events[1].event_type,
+///     ControlEventType::Parameter {
+///         parameter: ParameterNumber::Registered(PITCH_BEND_SENSITIVITY),
+///         change: ParameterChange::Set(24 * 128),
+///     }
+/// );
+///
+/// // Modulation wheel, MSB + LSB
+/// let msb = ChannelMessageType::ControlChange { controller: 1, value: 64 };
+/// let lsb = ChannelMessageType::ControlChange { controller: 33, value: 3 };
+/// decoder.decode(msb.in_channel(0).unwrap());
+/// assert_eq!(
+///     decoder.decode(lsb.in_channel(0).unwrap()).unwrap().event_type,
+///     ControlEventType::Controller14Bit { controller: 1, value: 64 * 128 + 3 }
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ControlChangeDecoder {
+    channels: [ChannelState; 16],
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ChannelState {
+    controller_msbs: [u8; NUM_14_BIT_CONTROLLERS as usize],
+    number_msb: Option<u8>,
+    number_lsb: Option<u8>,
+    is_registered: bool,
+}
+
+impl Default for ControlChangeDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControlChangeDecoder {
+    pub fn new() -> Self {
+        Self {
+            channels: [ChannelState {
+                controller_msbs: [0; NUM_14_BIT_CONTROLLERS as usize],
+                number_msb: None,
+                number_lsb: None,
+                is_registered: true,
+            }; 16],
+        }
+    }
+
+    /// Processes a single message and returns a [`ControlEvent`] if the message completes one.
+    ///
+    /// Messages other than Control Change messages as well as parameter selection messages return [`None`].
+    /// Data entry, increment and decrement messages are decoded as ordinary controllers as long as no parameter or the null RPN is selected.
+    pub fn decode(&mut self, message: ChannelMessage) -> Option<ControlEvent> {
+        let ChannelMessageType::ControlChange { controller, value } = message.message_type() else {
+            return None;
+        };
+
+        let channel = message.channel();
+        let state = &mut self.channels[usize::from(channel)];
+
+        let event_type = match controller {
+            RPN_MSB | RPN_LSB | NRPN_MSB | NRPN_LSB => {
+                let is_registered = matches!(controller, RPN_MSB | RPN_LSB);
+                if is_registered != state.is_registered {
+                    state.is_registered = is_registered;
+                    state.number_msb = None;
+                    state.number_lsb = None;
+                }
+                match controller {
+                    RPN_MSB | NRPN_MSB => state.number_msb = Some(value),
+                    _ => state.number_lsb = Some(value),
+                }
+                return None;
+            }
+            _ => {
+                if controller < NUM_14_BIT_CONTROLLERS {
+                    state.controller_msbs[usize::from(controller)] = value;
+                }
+
+                let data_msb = u16::from(state.controller_msbs[usize::from(DATA_ENTRY_MSB)]) << 7;
+                let change = match controller {
+                    DATA_ENTRY_MSB => Some(ParameterChange::Set(data_msb)),
+                    DATA_ENTRY_LSB => Some(ParameterChange::Set(data_msb | u16::from(value))),
+                    DATA_INCREMENT => Some(ParameterChange::Increment(value)),
+                    DATA_DECREMENT => Some(ParameterChange::Decrement(value)),
+                    _ => None,
+                };
+
+                // Without a selected parameter, data entry messages are ordinary controllers
+                change
+                    .and_then(|change| state.parameter_change(change))
+                    .unwrap_or_else(|| state.controller_change(controller, value))
+            }
+        };
+
+        Some(ControlEvent {
+            channel,
+            event_type,
+        })
+    }
+
+    /// Returns the parameter that is currently selected in the given channel.
+    ///
+    /// [`None`] is returned as long as one of the parameter number bytes is missing. The null RPN is returned as [`ParameterNumber::NULL`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ControlChangeDecoder;
+    /// # use tune::midi::ParameterNumber;
+    /// let mut decoder = ControlChangeDecoder::new();
+    /// let [select_msb, select_lsb] = ParameterNumber::NonRegistered(300).select(2).unwrap();
+    ///
+    /// decoder.decode(select_msb);
+    /// assert_eq!(decoder.selected_parameter(2), None);
+    ///
+    /// decoder.decode(select_lsb);
+    /// assert_eq!(decoder.selected_parameter(2), Some(ParameterNumber::NonRegistered(300)));
+    /// assert_eq!(decoder.selected_parameter(3), None);
+    /// ```
+    pub fn selected_parameter(&self, channel: u8) -> Option<ParameterNumber> {
+        self.channels
+            .get(usize::from(channel))
+            .and_then(ChannelState::parameter)
+    }
+}
+
+impl ChannelState {
+    fn parameter(&self) -> Option<ParameterNumber> {
+        let number = u16::from(self.number_msb?) << 7 | u16::from(self.number_lsb?);
+        Some(match self.is_registered {
+            true => ParameterNumber::Registered(number),
+            false => ParameterNumber::NonRegistered(number),
+        })
+    }
+
+    fn parameter_change(&self, change: ParameterChange) -> Option<ControlEventType> {
+        let parameter = self.parameter()?;
+
+        (parameter != ParameterNumber::NULL)
+            .then_some(ControlEventType::Parameter { parameter, change })
+    }
+
+    fn controller_change(&self, controller: u8, value: u8) -> ControlEventType {
+        match controller {
+            0..NUM_14_BIT_CONTROLLERS => ControlEventType::Controller14Bit {
+                controller,
+                value: u16::from(value) << 7,
+            },
+            NUM_14_BIT_CONTROLLERS..64 => {
+                let msb_controller = controller - NUM_14_BIT_CONTROLLERS;
+                let value_msb = self.controller_msbs[usize::from(msb_controller)];
+                ControlEventType::Controller14Bit {
+                    controller: msb_controller,
+                    value: u16::from(value_msb) << 7 | u16::from(value),
+                }
+            }
+            _ => ControlEventType::Controller7Bit { controller, value },
+        }
+    }
+}
+
+fn control_change(channel: u8, controller: u8, value: u8) -> Option<ChannelMessage> {
+    ChannelMessageType::ControlChange { controller, value }.in_channel(channel)
+}
+
+fn split_u14(value: u16) -> Option<(u8, u8)> {
+    (value < 1 << 14).then_some(((value >> 7) as u8, (value & 0x7f) as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_parameter_sequences() {
+        let mut decoder = ControlChangeDecoder::new();
+
+        let mut decode_all = |messages: &[ChannelMessage]| {
+            messages
+                .iter()
+                .filter_map(|&message| decoder.decode(message))
+                .map(|event| (event.channel, event.event_type))
+                .collect::<Vec<_>>()
+        };
+
+        let fine_tuning = ParameterNumber::Registered(CHANNEL_FINE_TUNING);
+        let nrpn = ParameterNumber::NonRegistered(0x1234);
+
+        assert_eq!(
+            decode_all(&fine_tuning.set_14_bit(3, 0x2000).unwrap()),
+            [
+                (
+                    3,
+                    ControlEventType::Parameter {
+                        parameter: fine_tuning,
+                        change: ParameterChange::Set(0x2000)
+                    }
+                ),
+                (
+                    3,
+                    ControlEventType::Parameter {
+                        parameter: fine_tuning,
+                        change: ParameterChange::Set(0x2000)
+                    }
+                ),
+            ]
+        );
+
+        // The parameter remains selected, other channels are not affected
+        assert_eq!(
+            decode_all(&[
+                control_change(3, DATA_INCREMENT, 1).unwrap(),
+                control_change(4, DATA_INCREMENT, 1).unwrap(),
+            ]),
+            [
+                (
+                    3,
+                    ControlEventType::Parameter {
+                        parameter: fine_tuning,
+                        change: ParameterChange::Increment(1)
+                    }
+                ),
+                (
+                    4,
+                    ControlEventType::Controller7Bit {
+                        controller: DATA_INCREMENT,
+                        value: 1
+                    }
+                ),
+            ]
+        );
+
+        // Switching from RPN to NRPN requires both parameter number bytes
+        assert_eq!(
+            decode_all(&[
+                control_change(3, NRPN_LSB, 0x34).unwrap(),
+                control_change(3, DATA_DECREMENT, 2).unwrap(),
+                control_change(3, NRPN_MSB, 0x24).unwrap(),
+                control_change(3, DATA_DECREMENT, 2).unwrap(),
+            ]),
+            [
+                (
+                    3,
+                    ControlEventType::Controller7Bit {
+                        controller: DATA_DECREMENT,
+                        value: 2
+                    }
+                ),
+                (
+                    3,
+                    ControlEventType::Parameter {
+                        parameter: nrpn,
+                        change: ParameterChange::Decrement(2)
+                    }
+                ),
+            ]
+        );
+
+        // The null RPN turns data entry messages into ordinary controllers
+        let mut messages = ParameterNumber::NULL.select(3).unwrap().to_vec();
+        messages.push(control_change(3, DATA_ENTRY_MSB, 5).unwrap());
+        assert_eq!(
+            decode_all(&messages),
+            [(
+                3,
+                ControlEventType::Controller14Bit {
+                    controller: DATA_ENTRY_MSB,
+                    value: 5 << 7
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn decode_data_entry_without_selected_parameter() {
+        let mut decoder = ControlChangeDecoder::new();
+
+        let mut decode = |controller, value| {
+            decoder
+                .decode(control_change(0, controller, value).unwrap())
+                .map(|event| event.event_type)
+        };
+
+        assert_eq!(
+            decode(DATA_ENTRY_MSB, 100),
+            Some(ControlEventType::Controller14Bit {
+                controller: DATA_ENTRY_MSB,
+                value: 100 << 7
+            })
+        );
+        assert_eq!(
+            decode(DATA_ENTRY_LSB, 5),
+            Some(ControlEventType::Controller14Bit {
+                controller: DATA_ENTRY_MSB,
+                value: 100 << 7 | 5
+            })
+        );
+        assert_eq!(
+            decode(DATA_INCREMENT, 1),
+            Some(ControlEventType::Controller7Bit {
+                controller: DATA_INCREMENT,
+                value: 1
+            })
+        );
+
+        // An incomplete selection does not select a parameter either
+        assert_eq!(decode(RPN_MSB, 0), None);
+        assert_eq!(
+            decode(DATA_DECREMENT, 2),
+            Some(ControlEventType::Controller7Bit {
+                controller: DATA_DECREMENT,
+                value: 2
+            })
+        );
+
+        // Once the selection is complete, the data entry MSB is taken over
+        assert_eq!(decode(RPN_LSB, 0), None);
+        assert_eq!(
+            decode(DATA_ENTRY_LSB, 7),
+            Some(ControlEventType::Parameter {
+                parameter: ParameterNumber::Registered(PITCH_BEND_SENSITIVITY),
+                change: ParameterChange::Set(100 << 7 | 7)
+            })
+        );
+    }
+
+    #[test]
+    fn decode_controllers() {
+        let mut decoder = ControlChangeDecoder::new();
+
+        let mut decode = |controller, value| {
+            decoder
+                .decode(control_change(0, controller, value).unwrap())
+                .unwrap()
+                .event_type
+        };
+
+        assert_eq!(
+            decode(7, 100),
+            ControlEventType::Controller14Bit {
+                controller: 7,
+                value: 100 << 7
+            }
+        );
+        assert_eq!(
+            decode(39, 5),
+            ControlEventType::Controller14Bit {
+                controller: 7,
+                value: 100 << 7 | 5
+            }
+        );
+        assert_eq!(
+            decode(64, 127),
+            ControlEventType::Controller7Bit {
+                controller: 64,
+                value: 127
+            }
+        );
+    }
+}
@@ -0,0 +1,426 @@
+use super::CHANNEL_PRESSURE;
+use super::ChannelMessage;
+use super::PROGRAM_CHANGE;
+
+/// Status byte for "System Exclusive" start.
+pub const SYSEX_START: u8 = 0xf0;
+/// Status byte for "MIDI Time Code Quarter Frame".
+pub const TIME_CODE_QUARTER_FRAME: u8 = 0xf1;
+/// Status byte for "Song Position Pointer".
+pub const SONG_POSITION_POINTER: u8 = 0xf2;
+/// Status byte for "Song Select".
+pub const SONG_SELECT: u8 = 0xf3;
+/// Status byte for "Tune Request".
+pub const TUNE_REQUEST: u8 = 0xf6;
+/// Status byte for "End of Exclusive".
+pub const SYSEX_END: u8 = 0xf7;
+/// Status byte for "Timing Clock".
+pub const TIMING_CLOCK: u8 = 0xf8;
+/// Status byte for "Start".
+pub const START: u8 = 0xfa;
+/// Status byte for "Continue".
+pub const CONTINUE: u8 = 0xfb;
+/// Status byte for "Stop".
+pub const STOP: u8 = 0xfc;
+/// Status byte for "Active Sensing".
+pub const ACTIVE_SENSING: u8 = 0xfe;
+/// Status byte for "System Reset".
+pub const SYSTEM_RESET: u8 = 0xff;
+
+/// Any complete MIDI 1.0 message that can occur in a MIDI byte stream.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MidiMessage {
+    /// A Channel Voice or Channel Mode message.
+    Channel(ChannelMessage),
+    /// A System Exclusive message including the leading `0xf0` and the trailing `0xf7` byte.
+    SysEx(Vec<u8>),
+    /// A System Common message.
+    SystemCommon(SystemCommonMessage),
+    /// A System Real-Time message.
+    SystemRealTime(SystemRealTimeMessage),
+}
+
+impl MidiMessage {
+    /// Returns the byte representation of a MIDI message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::MidiMessage;
+    /// # use tune::midi::SystemCommonMessage;
+    /// # use tune::midi::SystemRealTimeMessage;
+    /// let note_on = ChannelMessageType::NoteOn {
+    ///     key: 77,
+    ///     velocity: 88,
+    /// }
+    /// .in_channel(7)
+    /// .unwrap();
+    /// assert_eq!(
+    ///     MidiMessage::Channel(note_on).to_raw_message(),
+    ///     [0b1001_0111, 77, 88]
+    /// );
+    ///
+    /// let program_change = ChannelMessageType::ProgramChange { program: 5 }
+    ///     .in_channel(0)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     MidiMessage::Channel(program_change).to_raw_message(),
+    ///     [0b1100_0000, 5]
+    /// );
+    ///
+    /// assert_eq!(
+    ///     MidiMessage::SystemCommon(SystemCommonMessage::SongPositionPointer(300)).to_raw_message(),
+    ///     [0xf2, 44, 2]
+    /// );
+    /// assert_eq!(
+    ///     MidiMessage::SystemRealTime(SystemRealTimeMessage::TimingClock).to_raw_message(),
+    ///     [0xf8]
+    /// );
+    /// ```
+    pub fn to_raw_message(&self) -> Vec<u8> {
+        match self {
+            MidiMessage::Channel(message) => {
+                let raw_message = message.to_raw_message();
+                let len = 1 + num_data_bytes(raw_message[0]);
+                raw_message[..len].to_vec()
+            }
+            MidiMessage::SysEx(bytes) => bytes.clone(),
+            MidiMessage::SystemCommon(message) => match *message {
+                SystemCommonMessage::TimeCodeQuarterFrame(value) => {
+                    vec![TIME_CODE_QUARTER_FRAME, value]
+                }
+                SystemCommonMessage::SongPositionPointer(position) => vec![
+                    SONG_POSITION_POINTER,
+                    (position % 128) as u8,
+                    (position / 128 % 128) as u8,
+                ],
+                SystemCommonMessage::SongSelect(song) => vec![SONG_SELECT, song],
+                SystemCommonMessage::TuneRequest => vec![TUNE_REQUEST],
+            },
+            MidiMessage::SystemRealTime(message) => vec![message.status_byte()],
+        }
+    }
+}
+
+/// A type-safe representation of System Common messages.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SystemCommonMessage {
+    /// The raw data byte containing the message type and the value nibble.
+    TimeCodeQuarterFrame(u8),
+    /// The position in MIDI beats (sixteenth notes) since the start of the song.
+    SongPositionPointer(u16),
+    SongSelect(u8),
+    TuneRequest,
+}
+
+/// A type-safe representation of System Real-Time messages.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SystemRealTimeMessage {
+    TimingClock,
+    Start,
+    Continue,
+    Stop,
+    ActiveSensing,
+    SystemReset,
+}
+
+impl SystemRealTimeMessage {
+    fn from_status_byte(status_byte: u8) -> Option<Self> {
+        Some(match status_byte {
+            TIMING_CLOCK => SystemRealTimeMessage::TimingClock,
+            START => SystemRealTimeMessage::Start,
+            CONTINUE => SystemRealTimeMessage::Continue,
+            STOP => SystemRealTimeMessage::Stop,
+            ACTIVE_SENSING => SystemRealTimeMessage::ActiveSensing,
+            SYSTEM_RESET => SystemRealTimeMessage::SystemReset,
+            _ => return None,
+        })
+    }
+
+    fn status_byte(self) -> u8 {
+        match self {
+            SystemRealTimeMessage::TimingClock => TIMING_CLOCK,
+            SystemRealTimeMessage::Start => START,
+            SystemRealTimeMessage::Continue => CONTINUE,
+            SystemRealTimeMessage::Stop => STOP,
+            SystemRealTimeMessage::ActiveSensing => ACTIVE_SENSING,
+            SystemRealTimeMessage::SystemReset => SYSTEM_RESET,
+        }
+    }
+}
+
+/// Parses a raw MIDI 1.0 byte stream, e.g. from a serial/DIN connection or a MIDI capture, into [`MidiMessage`]s.
+///
+/// The parser keeps its state between calls s.t. messages can be split across several chunks of data.
+/// It supports running status and real-time bytes interleaved with other messages.
+/// Data bytes without a preceding status byte, undefined status bytes and System Exclusive messages interrupted by a status byte are discarded.
+/// The same applies to System Exclusive messages exceeding [`MidiStreamParser::MAX_SYSEX_LEN`] bytes s.t. an unterminated message cannot exhaust memory.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::midi::MidiMessage;
+/// # use tune::midi::MidiStreamParser;
+/// # use tune::midi::SystemRealTimeMessage;
+/// let mut parser = MidiStreamParser::new();
+///
+/// // Note On with running status and an interleaved Timing Clock
+/// let messages: Vec<_> = parser.parse(&[0x90, 60, 100, 64, 0xf8, 100]).collect();
+///
+/// assert_eq!(
+///     messages,
+///     [
+///         MidiMessage::Channel(
+///             ChannelMessageType::NoteOn {
+///                 key: 60,
+///                 velocity: 100
+///             }
+///             .in_channel(0)
+///             .unwrap()
+///         ),
+///         MidiMessage::SystemRealTime(SystemRealTimeMessage::TimingClock),
+///         MidiMessage::Channel(
+///             ChannelMessageType::NoteOn {
+///                 key: 64,
+///                 velocity: 100
+///             }
+///             .in_channel(0)
+///             .unwrap()
+///         ),
+///     ]
+/// );
+///
+/// // System Exclusive split into two packets
+/// assert_eq!(parser.parse(&[0xf0, 0x7e, 0x7f]).count(), 0);
+/// assert_eq!(
+///     parser.parse(&[0x08, 0xf7]).collect::<Vec<_>>(),
+///     [MidiMessage::SysEx(vec![0xf0, 0x7e, 0x7f, 0x08, 0xf7])]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MidiStreamParser {
+    status_byte: Option<u8>,
+    data_bytes: Vec<u8>,
+}
+
+impl MidiStreamParser {
+    /// The maximum length of a System Exclusive message, including the start and end bytes.
+    pub const MAX_SYSEX_LEN: usize = 64 * 1024;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a chunk of bytes and returns all messages completed by this chunk.
+    pub fn parse<'a>(&'a mut self, bytes: &'a [u8]) -> impl Iterator<Item = MidiMessage> + 'a {
+        bytes.iter().filter_map(|&byte| self.push(byte))
+    }
+
+    /// Feeds a single byte into the parser and returns a message if the byte completes one.
+    pub fn push(&mut self, byte: u8) -> Option<MidiMessage> {
+        if byte < 0x80 {
+            return self.push_data_byte(byte);
+        }
+
+        if byte >= TIMING_CLOCK {
+            // Real-time messages may occur anywhere, even within other messages, and do not affect the running status.
+            return SystemRealTimeMessage::from_status_byte(byte).map(MidiMessage::SystemRealTime);
+        }
+
+        if byte == SYSEX_END {
+            let message = match self.status_byte {
+                Some(SYSEX_START) => {
+                    let mut sysex_bytes = vec![SYSEX_START];
+                    sysex_bytes.append(&mut self.data_bytes);
+                    sysex_bytes.push(SYSEX_END);
+                    Some(MidiMessage::SysEx(sysex_bytes))
+                }
+                _ => None,
+            };
+            self.status_byte = None;
+            self.data_bytes.clear();
+            return message;
+        }
+
+        self.status_byte = Some(byte);
+        self.data_bytes.clear();
+
+        if byte == TUNE_REQUEST {
+            self.status_byte = None;
+            return Some(MidiMessage::SystemCommon(SystemCommonMessage::TuneRequest));
+        }
+
+        None
+    }
+
+    fn push_data_byte(&mut self, byte: u8) -> Option<MidiMessage> {
+        let status_byte = self.status_byte?;
+        self.data_bytes.push(byte);
+
+        if status_byte == SYSEX_START {
+            if self.data_bytes.len() + 2 > Self::MAX_SYSEX_LEN {
+                self.status_byte = None;
+                self.data_bytes = Vec::new();
+            }
+            return None;
+        }
+
+        if self.data_bytes.len() < num_data_bytes(status_byte) {
+            return None;
+        }
+
+        let data_bytes = std::mem::take(&mut self.data_bytes);
+        let message = match status_byte {
+            TIME_CODE_QUARTER_FRAME => SystemCommonMessage::TimeCodeQuarterFrame(data_bytes[0]),
+            SONG_POSITION_POINTER => SystemCommonMessage::SongPositionPointer(
+                u16::from(data_bytes[0]) + u16::from(data_bytes[1]) * 128,
+            ),
+            SONG_SELECT => SystemCommonMessage::SongSelect(data_bytes[0]),
+            0x80..=0xef => {
+                let mut raw_message = vec![status_byte];
+                raw_message.extend(data_bytes);
+                // Running status only applies to Channel messages
+                return ChannelMessage::from_raw_message(&raw_message).map(MidiMessage::Channel);
+            }
+            _ => {
+                self.status_byte = None;
+                return None;
+            }
+        };
+
+        // System Common messages cancel the running status
+        self.status_byte = None;
+        Some(MidiMessage::SystemCommon(message))
+    }
+}
+
+fn num_data_bytes(status_byte: u8) -> usize {
+    match status_byte {
+        TIME_CODE_QUARTER_FRAME | SONG_SELECT => 1,
+        SONG_POSITION_POINTER => 2,
+        0xf0..=0xff => 0,
+        _ => match status_byte >> 4 {
+            PROGRAM_CHANGE | CHANNEL_PRESSURE => 1,
+            _ => 2,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::midi::ChannelMessageType;
+
+    use super::*;
+
+    #[test]
+    fn parse_stream_with_running_status_and_system_messages() {
+        let mut parser = MidiStreamParser::new();
+
+        let stream = [
+            0x00, // Data byte without status
+            0xc3, 5, 6, // Program Change with running status
+            0xf1, 0x25, // Time Code Quarter Frame
+            0x30, // Data byte after System Common message
+            0xb0, 64, 0xfe, 127, // Control Change interrupted by Active Sensing
+            0xf0, 0x01, 0xf8, 0x02, 0xf7, // SysEx interrupted by Timing Clock
+            0xf0, 0x01, 0x90, 60, 100, // SysEx interrupted by Note On
+            0xf2, 44, 2, // Song Position Pointer
+            0xf3, 7, // Song Select
+            0xf4, 1,    // Undefined System Common message
+            0xf6, // Tune Request
+            0xf9, // Undefined real-time message
+            0xe1, 0, 64, // Pitch Bend
+        ];
+
+        assert_eq!(
+            parser.parse(&stream).collect::<Vec<_>>(),
+            [
+                MidiMessage::Channel(
+                    ChannelMessageType::ProgramChange { program: 5 }
+                        .in_channel(3)
+                        .unwrap()
+                ),
+                MidiMessage::Channel(
+                    ChannelMessageType::ProgramChange { program: 6 }
+                        .in_channel(3)
+                        .unwrap()
+                ),
+                MidiMessage::SystemCommon(SystemCommonMessage::TimeCodeQuarterFrame(0x25)),
+                MidiMessage::SystemRealTime(SystemRealTimeMessage::ActiveSensing),
+                MidiMessage::Channel(
+                    ChannelMessageType::ControlChange {
+                        controller: 64,
+                        value: 127
+                    }
+                    .in_channel(0)
+                    .unwrap()
+                ),
+                MidiMessage::SystemRealTime(SystemRealTimeMessage::TimingClock),
+                MidiMessage::SysEx(vec![0xf0, 0x01, 0x02, 0xf7]),
+                MidiMessage::Channel(
+                    ChannelMessageType::NoteOn {
+                        key: 60,
+                        velocity: 100
+                    }
+                    .in_channel(0)
+                    .unwrap()
+                ),
+                MidiMessage::SystemCommon(SystemCommonMessage::SongPositionPointer(300)),
+                MidiMessage::SystemCommon(SystemCommonMessage::SongSelect(7)),
+                MidiMessage::SystemCommon(SystemCommonMessage::TuneRequest),
+                MidiMessage::Channel(
+                    ChannelMessageType::PitchBendChange { value: 0 }
+                        .in_channel(1)
+                        .unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn discard_oversized_sysex() {
+        let mut parser = MidiStreamParser::new();
+
+        let max_payload = vec![0x01; MidiStreamParser::MAX_SYSEX_LEN - 2];
+        let mut stream = vec![0xf0];
+        stream.extend(&max_payload);
+        stream.push(0xf7);
+
+        let messages: Vec<_> = parser.parse(&stream).collect();
+        assert_eq!(messages, [MidiMessage::SysEx(stream.clone())]);
+
+        // One more byte and the message is dropped, including the trailing bytes
+        stream.insert(1, 0x01);
+        stream.extend([0x90, 60, 100]);
+
+        assert_eq!(
+            parser.parse(&stream).collect::<Vec<_>>(),
+            [MidiMessage::Channel(
+                ChannelMessageType::NoteOn {
+                    key: 60,
+                    velocity: 100
+                }
+                .in_channel(0)
+                .unwrap()
+            )]
+        );
+        assert!(parser.data_bytes.capacity() < MidiStreamParser::MAX_SYSEX_LEN);
+    }
+
+    #[test]
+    fn roundtrip_raw_messages() {
+        let stream = [
+            0x80, 60, 0, 0xa1, 60, 20, 0xd2, 30, 0xf0, 0x7e, 0x7f, 0xf7, 0xf1, 0x25, 0xfa,
+        ];
+
+        let mut parser = MidiStreamParser::new();
+        let raw_messages: Vec<_> = parser
+            .parse(&stream)
+            .flat_map(|message| message.to_raw_message())
+            .collect();
+
+        assert_eq!(raw_messages, stream);
+    }
+}
@@ -0,0 +1,775 @@
+//! Universal MIDI Packet (UMP) encoding and decoding of MIDI 1.0 and MIDI 2.0 Channel Voice messages.
+//!
+//! References:
+//! - [Universal MIDI Packet (UMP) Format and MIDI 2.0 Protocol](https://midi.org/universal-midi-packet-ump-and-midi-2-0-protocol-specification)
+
+use crate::midi::CHANNEL_PRESSURE;
+use crate::midi::CONTROL_CHANGE;
+use crate::midi::ChannelMessage;
+use crate::midi::ChannelMessageType;
+use crate::midi::NOTE_OFF;
+use crate::midi::NOTE_ON;
+use crate::midi::PITCH_BEND_CHANGE;
+use crate::midi::POLYPHONIC_KEY_PRESSURE;
+use crate::midi::PROGRAM_CHANGE;
+
+/// UMP message type for "MIDI 1.0 Channel Voice Messages".
+pub const MIDI_1_CHANNEL_VOICE: u8 = 0x2;
+/// UMP message type for "MIDI 2.0 Channel Voice Messages".
+pub const MIDI_2_CHANNEL_VOICE: u8 = 0x4;
+
+/// Status bits for "Registered Per-Note Controller".
+pub const REGISTERED_PER_NOTE_CONTROLLER: u8 = 0b0000;
+/// Status bits for "Assignable Per-Note Controller".
+pub const ASSIGNABLE_PER_NOTE_CONTROLLER: u8 = 0b0001;
+/// Status bits for "Registered Controller" (RPN).
+pub const REGISTERED_CONTROLLER: u8 = 0b0010;
+/// Status bits for "Assignable Controller" (NRPN).
+pub const ASSIGNABLE_CONTROLLER: u8 = 0b0011;
+/// Status bits for "Per-Note Pitch Bend".
+pub const PER_NOTE_PITCH_BEND: u8 = 0b0110;
+/// Status bits for "Per-Note Management".
+pub const PER_NOTE_MANAGEMENT: u8 = 0b1111;
+
+/// Index of the Registered Per-Note Controller "Pitch 7.25".
+pub const PITCH_7_25_CONTROLLER: u8 = 3;
+
+/// A single Universal MIDI Packet containing a Channel Voice message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UmpPacket {
+    /// MIDI 1.0 Channel Voice message wrapped into a 32-bit packet (message type 0x2).
+    Midi1ChannelVoice { group: u8, message: ChannelMessage },
+    /// MIDI 2.0 Channel Voice message with high-resolution data (message type 0x4).
+    Midi2ChannelVoice {
+        group: u8,
+        message: Midi2ChannelMessage,
+    },
+}
+
+impl UmpPacket {
+    /// Parses the first packet of the given sequence of 32-bit words.
+    ///
+    /// [`None`] is returned if the packet is incomplete or not a Channel Voice message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// # use tune::midi::NoteAttribute;
+    /// # use tune::midi::UmpPacket;
+    /// let midi1 = UmpPacket::from_words(&[0x2392_4540]).unwrap();
+    /// assert_eq!(
+    ///     midi1,
+    ///     UmpPacket::Midi1ChannelVoice {
+    ///         group: 3,
+    ///         message: ChannelMessageType::NoteOn {
+    ///             key: 69,
+    ///             velocity: 64
+    ///         }
+    ///         .in_channel(2)
+    ///         .unwrap(),
+    ///     }
+    /// );
+    ///
+    /// let midi2 = UmpPacket::from_words(&[0x4092_4503, 0x8000_8b00]).unwrap();
+    /// assert_eq!(
+    ///     midi2,
+    ///     UmpPacket::Midi2ChannelVoice {
+    ///         group: 0,
+    ///         message: Midi2ChannelMessageType::NoteOn {
+    ///             key: 69,
+    ///             velocity: 0x8000,
+    ///             attribute: NoteAttribute::Pitch7_9(0x8b00),
+    ///         }
+    ///         .in_channel(2)
+    ///         .unwrap(),
+    ///     }
+    /// );
+    ///
+    /// // Incomplete packet
+    /// assert_eq!(UmpPacket::from_words(&[0x4092_4503]), None);
+    ///
+    /// // Utility message
+    /// assert_eq!(UmpPacket::from_words(&[0x0000_0000]), None);
+    /// ```
+    pub fn from_words(words: &[u32]) -> Option<UmpPacket> {
+        let [message_type_and_group, status, index1, index2] = words.first()?.to_be_bytes();
+        let group = message_type_and_group & 0b0000_1111;
+
+        match message_type_and_group >> 4 {
+            MIDI_1_CHANNEL_VOICE => Some(UmpPacket::Midi1ChannelVoice {
+                group,
+                message: ChannelMessage::from_raw_message(&[status, index1, index2])?,
+            }),
+            MIDI_2_CHANNEL_VOICE => Some(UmpPacket::Midi2ChannelVoice {
+                group,
+                message: Midi2ChannelMessage::from_words(status, index1, index2, *words.get(1)?)?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the 32-bit word representation of the packet.
+    ///
+    /// Only the lower 4 bits of the group are encoded s.t. the message type cannot be corrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// # use tune::midi::UmpPacket;
+    /// let midi1 = UmpPacket::Midi1ChannelVoice {
+    ///     group: 3,
+    ///     message: ChannelMessageType::PitchBendChange { value: 0 }
+    ///         .in_channel(2)
+    ///         .unwrap(),
+    /// };
+    /// assert_eq!(midi1.to_words(), [0x23e2_0040]);
+    ///
+    /// let midi2 = UmpPacket::Midi2ChannelVoice {
+    ///     group: 0,
+    ///     message: Midi2ChannelMessageType::RegisteredPerNoteController {
+    ///         key: 69,
+    ///         index: 3,
+    ///         value: 0x8b00_0000,
+    ///     }
+    ///     .in_channel(2)
+    ///     .unwrap(),
+    /// };
+    /// assert_eq!(midi2.to_words(), [0x4002_4503, 0x8b00_0000]);
+    /// ```
+    pub fn to_words(&self) -> Vec<u32> {
+        match self {
+            UmpPacket::Midi1ChannelVoice { group, message } => {
+                let [status, payload1, payload2] = message.to_raw_message();
+                vec![u32::from_be_bytes([
+                    (MIDI_1_CHANNEL_VOICE << 4) | (group & 0b0000_1111),
+                    status,
+                    payload1,
+                    payload2,
+                ])]
+            }
+            UmpPacket::Midi2ChannelVoice { group, message } => {
+                let (status, index1, index2, data) = message.to_words();
+                vec![
+                    u32::from_be_bytes([
+                        (MIDI_2_CHANNEL_VOICE << 4) | (group & 0b0000_1111),
+                        status,
+                        index1,
+                        index2,
+                    ]),
+                    data,
+                ]
+            }
+        }
+    }
+
+    /// Returns the group of the packet.
+    pub fn group(&self) -> u8 {
+        match self {
+            UmpPacket::Midi1ChannelVoice { group, .. }
+            | UmpPacket::Midi2ChannelVoice { group, .. } => *group,
+        }
+    }
+}
+
+/// Returns the number of 32-bit words of the packet starting with `first_word`.
+///
+/// This is useful for skipping packets of unsupported message types in a UMP stream.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi;
+/// assert_eq!(midi::ump_packet_size(0x2392_4540), 1);
+/// assert_eq!(midi::ump_packet_size(0x4092_4503), 2);
+/// assert_eq!(midi::ump_packet_size(0x5000_0000), 4);
+/// ```
+pub fn ump_packet_size(first_word: u32) -> usize {
+    match first_word >> 28 {
+        0x0 | 0x1 | 0x2 | 0x6 | 0x7 => 1,
+        0x3 | 0x4 | 0x8 | 0x9 | 0xa => 2,
+        0xb | 0xc => 3,
+        _ => 4,
+    }
+}
+
+/// A type-safe representation of MIDI 2.0 Channel Voice messages.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Midi2ChannelMessage {
+    channel: u8,
+    message_type: Midi2ChannelMessageType,
+}
+
+impl Midi2ChannelMessage {
+    fn from_words(status_byte: u8, index1: u8, index2: u8, data: u32) -> Option<Self> {
+        let channel = status_byte & 0b0000_1111;
+        let [data_msb, _, data_byte3, data_lsb] = data.to_be_bytes();
+        let data_hi = (data >> 16) as u16;
+        let data_lo = data as u16;
+
+        let message_type = match status_byte >> 4 {
+            REGISTERED_PER_NOTE_CONTROLLER => {
+                Midi2ChannelMessageType::RegisteredPerNoteController {
+                    key: index1,
+                    index: index2,
+                    value: data,
+                }
+            }
+            ASSIGNABLE_PER_NOTE_CONTROLLER => {
+                Midi2ChannelMessageType::AssignablePerNoteController {
+                    key: index1,
+                    index: index2,
+                    value: data,
+                }
+            }
+            REGISTERED_CONTROLLER => Midi2ChannelMessageType::RegisteredController {
+                bank: index1,
+                index: index2,
+                value: data,
+            },
+            ASSIGNABLE_CONTROLLER => Midi2ChannelMessageType::AssignableController {
+                bank: index1,
+                index: index2,
+                value: data,
+            },
+            PER_NOTE_PITCH_BEND => Midi2ChannelMessageType::PerNotePitchBend {
+                key: index1,
+                value: data,
+            },
+            NOTE_OFF => Midi2ChannelMessageType::NoteOff {
+                key: index1,
+                velocity: data_hi,
+                attribute: NoteAttribute::from_type_and_data(index2, data_lo),
+            },
+            NOTE_ON => Midi2ChannelMessageType::NoteOn {
+                key: index1,
+                velocity: data_hi,
+                attribute: NoteAttribute::from_type_and_data(index2, data_lo),
+            },
+            POLYPHONIC_KEY_PRESSURE => Midi2ChannelMessageType::PolyphonicKeyPressure {
+                key: index1,
+                pressure: data,
+            },
+            CONTROL_CHANGE => Midi2ChannelMessageType::ControlChange {
+                controller: index1,
+                value: data,
+            },
+            PROGRAM_CHANGE => Midi2ChannelMessageType::ProgramChange {
+                program: data_msb,
+                bank: (index2 & 0b0000_0001 != 0).then_some((data_byte3, data_lsb)),
+            },
+            CHANNEL_PRESSURE => Midi2ChannelMessageType::ChannelPressure { pressure: data },
+            PITCH_BEND_CHANGE => Midi2ChannelMessageType::PitchBendChange { value: data },
+            PER_NOTE_MANAGEMENT => Midi2ChannelMessageType::PerNoteManagement {
+                key: index1,
+                detach: index2 & 0b0000_0010 != 0,
+                reset: index2 & 0b0000_0001 != 0,
+            },
+            _ => return None,
+        };
+
+        message_type.in_channel(channel)
+    }
+
+    fn to_words(self) -> (u8, u8, u8, u32) {
+        let status = |prefix: u8| (prefix << 4) | self.channel;
+
+        match self.message_type {
+            Midi2ChannelMessageType::RegisteredPerNoteController { key, index, value } => {
+                (status(REGISTERED_PER_NOTE_CONTROLLER), key, index, value)
+            }
+            Midi2ChannelMessageType::AssignablePerNoteController { key, index, value } => {
+                (status(ASSIGNABLE_PER_NOTE_CONTROLLER), key, index, value)
+            }
+            Midi2ChannelMessageType::RegisteredController { bank, index, value } => {
+                (status(REGISTERED_CONTROLLER), bank, index, value)
+            }
+            Midi2ChannelMessageType::AssignableController { bank, index, value } => {
+                (status(ASSIGNABLE_CONTROLLER), bank, index, value)
+            }
+            Midi2ChannelMessageType::PerNotePitchBend { key, value } => {
+                (status(PER_NOTE_PITCH_BEND), key, 0, value)
+            }
+            Midi2ChannelMessageType::NoteOff {
+                key,
+                velocity,
+                attribute,
+            } => {
+                let (attribute_type, attribute_data) = attribute.to_type_and_data();
+                (
+                    status(NOTE_OFF),
+                    key,
+                    attribute_type,
+                    (u32::from(velocity) << 16) | u32::from(attribute_data),
+                )
+            }
+            Midi2ChannelMessageType::NoteOn {
+                key,
+                velocity,
+                attribute,
+            } => {
+                let (attribute_type, attribute_data) = attribute.to_type_and_data();
+                (
+                    status(NOTE_ON),
+                    key,
+                    attribute_type,
+                    (u32::from(velocity) << 16) | u32::from(attribute_data),
+                )
+            }
+            Midi2ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
+                (status(POLYPHONIC_KEY_PRESSURE), key, 0, pressure)
+            }
+            Midi2ChannelMessageType::ControlChange { controller, value } => {
+                (status(CONTROL_CHANGE), controller, 0, value)
+            }
+            Midi2ChannelMessageType::ProgramChange { program, bank } => {
+                let (bank_msb, bank_lsb) = bank.unwrap_or_default();
+                (
+                    status(PROGRAM_CHANGE),
+                    0,
+                    u8::from(bank.is_some()),
+                    u32::from_be_bytes([program, 0, bank_msb, bank_lsb]),
+                )
+            }
+            Midi2ChannelMessageType::ChannelPressure { pressure } => {
+                (status(CHANNEL_PRESSURE), 0, 0, pressure)
+            }
+            Midi2ChannelMessageType::PitchBendChange { value } => {
+                (status(PITCH_BEND_CHANGE), 0, 0, value)
+            }
+            Midi2ChannelMessageType::PerNoteManagement { key, detach, reset } => (
+                status(PER_NOTE_MANAGEMENT),
+                key,
+                (u8::from(detach) << 1) | u8::from(reset),
+                0,
+            ),
+        }
+    }
+
+    /// Returns the channel of a MIDI 2.0 message.
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Returns the channel-agnostic part of a MIDI 2.0 message.
+    pub fn message_type(&self) -> Midi2ChannelMessageType {
+        self.message_type
+    }
+}
+
+/// A parsed representation of the channel-agnostic part of a MIDI 2.0 message.
+///
+/// Pitch bend values are unsigned with a center value of `0x8000_0000`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Midi2ChannelMessageType {
+    NoteOff {
+        key: u8,
+        velocity: u16,
+        attribute: NoteAttribute,
+    },
+    NoteOn {
+        key: u8,
+        velocity: u16,
+        attribute: NoteAttribute,
+    },
+    PolyphonicKeyPressure {
+        key: u8,
+        pressure: u32,
+    },
+    RegisteredPerNoteController {
+        key: u8,
+        index: u8,
+        value: u32,
+    },
+    AssignablePerNoteController {
+        key: u8,
+        index: u8,
+        value: u32,
+    },
+    PerNoteManagement {
+        key: u8,
+        detach: bool,
+        reset: bool,
+    },
+    ControlChange {
+        controller: u8,
+        value: u32,
+    },
+    RegisteredController {
+        bank: u8,
+        index: u8,
+        value: u32,
+    },
+    AssignableController {
+        bank: u8,
+        index: u8,
+        value: u32,
+    },
+    ProgramChange {
+        program: u8,
+        bank: Option<(u8, u8)>,
+    },
+    ChannelPressure {
+        pressure: u32,
+    },
+    PitchBendChange {
+        value: u32,
+    },
+    PerNotePitchBend {
+        key: u8,
+        value: u32,
+    },
+}
+
+impl Midi2ChannelMessageType {
+    /// Creates a new [`Midi2ChannelMessage`] from `self` with the given `channel`.
+    ///
+    /// [`None`] is returned if the channel value is outside the range [0..16).
+    pub fn in_channel(self, channel: u8) -> Option<Midi2ChannelMessage> {
+        match channel < 16 {
+            true => Some(Midi2ChannelMessage {
+                channel,
+                message_type: self,
+            }),
+            false => None,
+        }
+    }
+
+    /// Replaces the attribute of a Note On or Note Off message.
+    ///
+    /// Other message types are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// # use tune::midi::NoteAttribute;
+    /// let note_on = Midi2ChannelMessageType::NoteOn {
+    ///     key: 69,
+    ///     velocity: 0x8000,
+    ///     attribute: NoteAttribute::None,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     note_on.with_attribute(NoteAttribute::Pitch7_9(0x8b00)),
+    ///     Midi2ChannelMessageType::NoteOn {
+    ///         key: 69,
+    ///         velocity: 0x8000,
+    ///         attribute: NoteAttribute::Pitch7_9(0x8b00),
+    ///     }
+    /// );
+    /// ```
+    pub fn with_attribute(self, attribute: NoteAttribute) -> Self {
+        match self {
+            Midi2ChannelMessageType::NoteOff { key, velocity, .. } => {
+                Midi2ChannelMessageType::NoteOff {
+                    key,
+                    velocity,
+                    attribute,
+                }
+            }
+            Midi2ChannelMessageType::NoteOn { key, velocity, .. } => {
+                Midi2ChannelMessageType::NoteOn {
+                    key,
+                    velocity,
+                    attribute,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Translates a MIDI 1.0 message into its MIDI 2.0 equivalent, upscaling all values using the Min-Center-Max algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// # use tune::midi::NoteAttribute;
+    /// assert_eq!(
+    ///     Midi2ChannelMessageType::from_midi1(ChannelMessageType::NoteOn {
+    ///         key: 69,
+    ///         velocity: 127
+    ///     }),
+    ///     Midi2ChannelMessageType::NoteOn {
+    ///         key: 69,
+    ///         velocity: 0xffff,
+    ///         attribute: NoteAttribute::None
+    ///     }
+    /// );
+    ///
+    /// // Center values are preserved
+    /// assert_eq!(
+    ///     Midi2ChannelMessageType::from_midi1(ChannelMessageType::ControlChange {
+    ///         controller: 7,
+    ///         value: 64
+    ///     }),
+    ///     Midi2ChannelMessageType::ControlChange {
+    ///         controller: 7,
+    ///         value: 0x8000_0000
+    ///     }
+    /// );
+    /// assert_eq!(
+    ///     Midi2ChannelMessageType::from_midi1(ChannelMessageType::PitchBendChange { value: 0 }),
+    ///     Midi2ChannelMessageType::PitchBendChange { value: 0x8000_0000 }
+    /// );
+    /// ```
+    pub fn from_midi1(message_type: ChannelMessageType) -> Self {
+        match message_type {
+            ChannelMessageType::NoteOff { key, velocity } => Midi2ChannelMessageType::NoteOff {
+                key,
+                velocity: scale_up(velocity.into(), 7, 16) as u16,
+                attribute: NoteAttribute::None,
+            },
+            ChannelMessageType::NoteOn { key, velocity } => Midi2ChannelMessageType::NoteOn {
+                key,
+                // MIDI 2.0 does not interpret a velocity of 0 as note off.
+                velocity: scale_up(velocity.into(), 7, 16) as u16,
+                attribute: NoteAttribute::None,
+            },
+            ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
+                Midi2ChannelMessageType::PolyphonicKeyPressure {
+                    key,
+                    pressure: scale_up(pressure.into(), 7, 32),
+                }
+            }
+            ChannelMessageType::ControlChange { controller, value } => {
+                Midi2ChannelMessageType::ControlChange {
+                    controller,
+                    value: scale_up(value.into(), 7, 32),
+                }
+            }
+            ChannelMessageType::ProgramChange { program } => {
+                Midi2ChannelMessageType::ProgramChange {
+                    program,
+                    bank: None,
+                }
+            }
+            ChannelMessageType::ChannelPressure { pressure } => {
+                Midi2ChannelMessageType::ChannelPressure {
+                    pressure: scale_up(pressure.into(), 7, 32),
+                }
+            }
+            ChannelMessageType::PitchBendChange { value } => {
+                Midi2ChannelMessageType::PitchBendChange {
+                    value: scale_up((i32::from(value) + 8192).clamp(0, 16383) as u32, 14, 32),
+                }
+            }
+        }
+    }
+}
+
+/// Attribute of a MIDI 2.0 Note On or Note Off message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NoteAttribute {
+    None,
+    ManufacturerSpecific(u16),
+    ProfileSpecific(u16),
+    /// Pitch of the note in 7.9 fixed-point semitones (see [`pitch_7_9`]).
+    Pitch7_9(u16),
+    Other {
+        attribute_type: u8,
+        data: u16,
+    },
+}
+
+impl NoteAttribute {
+    fn from_type_and_data(attribute_type: u8, data: u16) -> Self {
+        match attribute_type {
+            0x00 => NoteAttribute::None,
+            0x01 => NoteAttribute::ManufacturerSpecific(data),
+            0x02 => NoteAttribute::ProfileSpecific(data),
+            0x03 => NoteAttribute::Pitch7_9(data),
+            attribute_type => NoteAttribute::Other {
+                attribute_type,
+                data,
+            },
+        }
+    }
+
+    fn to_type_and_data(self) -> (u8, u16) {
+        match self {
+            NoteAttribute::None => (0x00, 0),
+            NoteAttribute::ManufacturerSpecific(data) => (0x01, data),
+            NoteAttribute::ProfileSpecific(data) => (0x02, data),
+            NoteAttribute::Pitch7_9(data) => (0x03, data),
+            NoteAttribute::Other {
+                attribute_type,
+                data,
+            } => (attribute_type, data),
+        }
+    }
+}
+
+/// Converts a fractional MIDI note number into the 7.9 fixed-point format of the Note On pitch attribute.
+///
+/// The result saturates at the boundaries of the format.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi;
+/// assert_eq!(midi::pitch_7_9(69.0), 0x8a00);
+/// assert_eq!(midi::pitch_7_9(69.5), 0x8b00);
+/// assert_eq!(midi::pitch_7_9(-1.0), 0x0000);
+/// assert_eq!(midi::pitch_7_9(200.0), 0xffff);
+/// ```
+pub fn pitch_7_9(note_number: f64) -> u16 {
+    (note_number * 512.0)
+        .round()
+        .clamp(0.0, f64::from(u16::MAX)) as u16
+}
+
+/// Converts a fractional MIDI note number into the 7.25 fixed-point format of the Registered Per-Note Controller #3.
+///
+/// The result saturates at the boundaries of the format.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi;
+/// assert_eq!(midi::pitch_7_25(69.0), 0x8a00_0000);
+/// assert_eq!(midi::pitch_7_25(69.5), 0x8b00_0000);
+/// ```
+pub fn pitch_7_25(note_number: f64) -> u32 {
+    (note_number * 33554432.0)
+        .round()
+        .clamp(0.0, f64::from(u32::MAX)) as u32
+}
+
+fn scale_up(value: u32, src_bits: u32, dst_bits: u32) -> u32 {
+    let scale_bits = dst_bits - src_bits;
+    let mut bit_shifted_value = value << scale_bits;
+
+    let center_value = 1 << (src_bits - 1);
+    if value <= center_value {
+        return bit_shifted_value;
+    }
+
+    let repeat_bits = src_bits - 1;
+    let repeat_mask = (1 << repeat_bits) - 1;
+    let mut repeat_value = value & repeat_mask;
+    if scale_bits > repeat_bits {
+        repeat_value <<= scale_bits - repeat_bits;
+    } else {
+        repeat_value >>= repeat_bits - scale_bits;
+    }
+
+    while repeat_value != 0 {
+        bit_shifted_value |= repeat_value;
+        repeat_value >>= repeat_bits;
+    }
+
+    bit_shifted_value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_midi2_messages() {
+        let message_types = [
+            Midi2ChannelMessageType::NoteOff {
+                key: 1,
+                velocity: 2,
+                attribute: NoteAttribute::Other {
+                    attribute_type: 0x7f,
+                    data: 3,
+                },
+            },
+            Midi2ChannelMessageType::NoteOn {
+                key: 4,
+                velocity: 5,
+                attribute: NoteAttribute::ManufacturerSpecific(6),
+            },
+            Midi2ChannelMessageType::PolyphonicKeyPressure {
+                key: 7,
+                pressure: 8,
+            },
+            Midi2ChannelMessageType::RegisteredPerNoteController {
+                key: 9,
+                index: 10,
+                value: 11,
+            },
+            Midi2ChannelMessageType::AssignablePerNoteController {
+                key: 12,
+                index: 13,
+                value: 14,
+            },
+            Midi2ChannelMessageType::PerNoteManagement {
+                key: 15,
+                detach: true,
+                reset: false,
+            },
+            Midi2ChannelMessageType::ControlChange {
+                controller: 16,
+                value: 17,
+            },
+            Midi2ChannelMessageType::RegisteredController {
+                bank: 18,
+                index: 19,
+                value: 20,
+            },
+            Midi2ChannelMessageType::AssignableController {
+                bank: 21,
+                index: 22,
+                value: 23,
+            },
+            Midi2ChannelMessageType::ProgramChange {
+                program: 24,
+                bank: Some((25, 26)),
+            },
+            Midi2ChannelMessageType::ProgramChange {
+                program: 27,
+                bank: None,
+            },
+            Midi2ChannelMessageType::ChannelPressure { pressure: 28 },
+            Midi2ChannelMessageType::PitchBendChange { value: 29 },
+            Midi2ChannelMessageType::PerNotePitchBend { key: 30, value: 31 },
+        ];
+
+        for message_type in message_types {
+            let packet = UmpPacket::Midi2ChannelVoice {
+                group: 5,
+                message: message_type.in_channel(11).unwrap(),
+            };
+            let words = packet.to_words();
+
+            assert_eq!(words.len(), ump_packet_size(words[0]));
+            assert_eq!(UmpPacket::from_words(&words), Some(packet));
+        }
+    }
+
+    #[test]
+    fn mask_out_of_range_group() {
+        let packet = UmpPacket::Midi1ChannelVoice {
+            group: 0x15,
+            message: ChannelMessageType::NoteOn {
+                key: 60,
+                velocity: 100,
+            }
+            .in_channel(0)
+            .unwrap(),
+        };
+
+        assert_eq!(packet.to_words(), [0x2590_3c64]);
+    }
+
+    #[test]
+    fn scale_up_min_center_max() {
+        assert_eq!(scale_up(0, 7, 16), 0x0000);
+        assert_eq!(scale_up(64, 7, 16), 0x8000);
+        assert_eq!(scale_up(127, 7, 16), 0xffff);
+        assert_eq!(scale_up(0, 7, 32), 0x0000_0000);
+        assert_eq!(scale_up(64, 7, 32), 0x8000_0000);
+        assert_eq!(scale_up(127, 7, 32), 0xffff_ffff);
+        assert_eq!(scale_up(0, 14, 32), 0x0000_0000);
+        assert_eq!(scale_up(8192, 14, 32), 0x8000_0000);
+        assert_eq!(scale_up(16383, 14, 32), 0xffff_ffff);
+    }
+}
@@ -9,8 +9,9 @@ use std::fmt::Debug;
 use std::iter;
 
 use crate::key::PianoKey;
+use crate::midi;
 use crate::midi::ChannelMessage;
-use crate::midi::ChannelMessageType;
+use crate::midi::ParameterNumber;
 use crate::note::NoteLetter;
 use crate::pitch::Pitch;
 use crate::pitch::Pitched;
@@ -814,111 +815,87 @@ impl From<u8> for Channels {
 }
 
 pub fn channel_fine_tuning(channel: u8, detuning: Ratio) -> Option<[ChannelMessage; 4]> {
-    const CHANNEL_FINE_TUNING_MSB: u8 = 0x00;
-    const CHANNEL_FINE_TUNING_LSB: u8 = 0x01;
-
-    let (value_msb, value_lsb) = ratio_to_u8s(detuning);
-
-    rpn_message_2_byte(
-        channel,
-        CHANNEL_FINE_TUNING_MSB,
-        CHANNEL_FINE_TUNING_LSB,
-        value_msb,
-        value_lsb,
-    )
+    ParameterNumber::Registered(midi::CHANNEL_FINE_TUNING)
+        .set_14_bit(channel, ratio_to_u14(detuning))
 }
 
 pub fn tuning_program_change(channel: u8, tuning_program: u8) -> Option<[ChannelMessage; 3]> {
-    const TUNING_PROGRAM_CHANGE_MSB: u8 = 0x00;
-    const TUNING_PROGRAM_CHANGE_LSB: u8 = 0x03;
-
-    rpn_message_1_byte(
-        channel,
-        TUNING_PROGRAM_CHANGE_MSB,
-        TUNING_PROGRAM_CHANGE_LSB,
-        tuning_program,
-    )
+    ParameterNumber::Registered(midi::TUNING_PROGRAM_CHANGE).set_7_bit(channel, tuning_program)
 }
 
 pub fn tuning_bank_change(channel: u8, tuning_bank: u8) -> Option<[ChannelMessage; 3]> {
-    const TUNING_BANK_CHANGE_MSB: u8 = 0x00;
-    const TUNING_BANK_CHANGE_LSB: u8 = 0x04;
-
-    rpn_message_1_byte(
-        channel,
-        TUNING_BANK_CHANGE_MSB,
-        TUNING_BANK_CHANGE_LSB,
-        tuning_bank,
-    )
+    ParameterNumber::Registered(midi::TUNING_BANK_SELECT).set_7_bit(channel, tuning_bank)
 }
 
-// RPN format reference: https://www.midi.org/specifications-old/item/table-3-control-change-messages-data-bytes-2
+/// Sets the pitch-bend range of the given `channel` via RPN 0 (Pitch Bend Sensitivity).
+///
+/// The range is rounded to the nearest cent and saturates at 127 semitones and 99 cents.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::mts;
+/// # use tune::pitch::Ratio;
+/// let messages = mts::pitch_bend_sensitivity(3, Ratio::from_semitones(48.5)).unwrap();
+///
+/// assert_eq!(
+///     messages.map(|message| (message.channel(), message.message_type())),
+///     [
+///         (3, ChannelMessageType::ControlChange { controller: 0x65, value: 0 }),
+///         (3, ChannelMessageType::ControlChange { controller: 0x64, value: 0 }),
+///         (3, ChannelMessageType::ControlChange { controller: 0x06, value: 48 }),
+///         (3, ChannelMessageType::ControlChange { controller: 0x26, value: 50 }),
+///     ]
+/// );
+/// ```
+pub fn pitch_bend_sensitivity(channel: u8, range: Ratio) -> Option<[ChannelMessage; 4]> {
+    let total_cents = range.as_cents().round().clamp(0.0, 127.0 * 100.0 + 99.0) as u16;
 
-const RPN_MSB: u8 = 0x65;
-const RPN_LSB: u8 = 0x64;
-const DATA_ENTRY_MSB: u8 = 0x06;
-const DATA_ENTRY_LSB: u8 = 0x26;
+    ParameterNumber::Registered(midi::PITCH_BEND_SENSITIVITY)
+        .set_14_bit(channel, total_cents / 100 * 128 + total_cents % 100)
+}
 
-fn rpn_message_1_byte(
-    channel: u8,
-    parameter_number_msb: u8,
-    parameter_number_lsb: u8,
-    value: u8,
+/// Creates an MPE Configuration Message (RPN 6) which defines an MPE zone.
+///
+/// A `manager_channel` of 0 defines a lower zone, a `manager_channel` of 15 defines an upper zone. A value of 0 for `num_member_channels` disables the zone.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::mts;
+/// let messages = mts::mpe_configuration(0, 15).unwrap();
+///
+/// assert_eq!(
+///     messages.map(|message| (message.channel(), message.message_type())),
+///     [
+///         (0, ChannelMessageType::ControlChange { controller: 0x65, value: 0 }),
+///         (0, ChannelMessageType::ControlChange { controller: 0x64, value: 6 }),
+///         (0, ChannelMessageType::ControlChange { controller: 0x06, value: 15 }),
+///     ]
+/// );
+///
+/// // Neither lower nor upper zone
+/// assert!(mts::mpe_configuration(7, 8).is_none());
+///
+/// // Too many member channels
+/// assert!(mts::mpe_configuration(15, 16).is_none());
+/// ```
+pub fn mpe_configuration(
+    manager_channel: u8,
+    num_member_channels: u8,
 ) -> Option<[ChannelMessage; 3]> {
-    Some([
-        ChannelMessageType::ControlChange {
-            controller: RPN_MSB,
-            value: parameter_number_msb,
-        }
-        .in_channel(channel)?,
-        ChannelMessageType::ControlChange {
-            controller: RPN_LSB,
-            value: parameter_number_lsb,
-        }
-        .in_channel(channel)?,
-        ChannelMessageType::ControlChange {
-            controller: DATA_ENTRY_MSB,
-            value,
-        }
-        .in_channel(channel)?,
-    ])
-}
+    if !matches!(manager_channel, 0 | 15) || num_member_channels > 15 {
+        return None;
+    }
 
-fn rpn_message_2_byte(
-    channel: u8,
-    parameter_number_msb: u8,
-    parameter_number_lsb: u8,
-    value_msb: u8,
-    value_lsb: u8,
-) -> Option<[ChannelMessage; 4]> {
-    Some([
-        ChannelMessageType::ControlChange {
-            controller: RPN_MSB,
-            value: parameter_number_msb,
-        }
-        .in_channel(channel)?,
-        ChannelMessageType::ControlChange {
-            controller: RPN_LSB,
-            value: parameter_number_lsb,
-        }
-        .in_channel(channel)?,
-        ChannelMessageType::ControlChange {
-            controller: DATA_ENTRY_MSB,
-            value: value_msb,
-        }
-        .in_channel(channel)?,
-        ChannelMessageType::ControlChange {
-            controller: DATA_ENTRY_LSB,
-            value: value_lsb,
-        }
-        .in_channel(channel)?,
-    ])
+    ParameterNumber::Registered(midi::MPE_CONFIGURATION)
+        .set_7_bit(manager_channel, num_member_channels)
 }
 
-fn ratio_to_u8s(ratio: Ratio) -> (u8, u8) {
-    let as_u16 = (((ratio.as_semitones() + 1.0) * 13f64.exp2()) as u16).min(16383);
-
-    ((as_u16 / 128) as u8, (as_u16 % 128) as u8)
+fn ratio_to_u14(ratio: Ratio) -> u16 {
+    (((ratio.as_semitones() + 1.0) * 13f64.exp2()) as u16).min(16383)
 }
 
 #[cfg(test)]
@@ -0,0 +1,358 @@
+//! Note names for just-intonation pitches in extended Helmholtz-Ellis and Sagittal notation.
+
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::math;
+use crate::note::Note;
+use crate::note::NoteLetter;
+use crate::note::Octave;
+use crate::pitch::Ratio;
+use crate::temperament::Comma;
+
+/// The largest prime number for which a comma accidental is defined.
+pub const HEJI_PRIME_LIMIT: u8 = 47;
+
+/// Number of fifths between C and the Pythagorean note whose pitch is altered by the comma accidental of a given prime.
+///
+/// The table follows the Helmholtz-Ellis convention, e.g. the 13th harmonic of C is notated as a lowered A rather than a raised Ab.
+static PYTHAGOREAN_FIFTHS_BY_PRIME: &[(u8, i32)] = &[
+    (5, 4),
+    (7, -2),
+    (11, -1),
+    (13, 3),
+    (17, 7),
+    (19, -3),
+    (23, 6),
+    (29, -2),
+    (31, 0),
+    (37, 2),
+    (41, 4),
+    (43, -1),
+    (47, 6),
+];
+
+/// Accidental systems available for rendering a [`JiNote`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum JiNotation {
+    /// Extended Helmholtz-Ellis notation using arrows and prime numbers, e.g. `Bb↓7` for 7/4 above C.
+    Heji,
+    /// ASCII Sagittal notation mixed with conventional sharps and flats, e.g. `Bb!)` for 7/4 above C.
+    ///
+    /// Comma accidentals are available for the primes 5, 7, 11, 13 and 19.
+    Sagittal,
+}
+
+/// A just-intonation pitch spelled as a Pythagorean note with additional prime-specific comma accidentals.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct JiNote {
+    /// The natural [`NoteLetter`] of the Pythagorean base note.
+    pub letter: NoteLetter,
+    /// The number of sharps (if positive) or flats (if negative) of the Pythagorean base note.
+    pub num_sharps: i32,
+    /// The octave of the Pythagorean base note as determined by its natural letter.
+    pub octave: Octave,
+    /// The number of upward (if positive) or downward (if negative) comma accidentals for each prime number above 3.
+    pub inflections: Vec<(u8, i32)>,
+}
+
+impl JiNote {
+    /// Names the pitch that lies a just `interval` above the `reference` note.
+    ///
+    /// Black `reference` keys are spelled as Ab, Eb, Bb, F# or C#.
+    ///
+    /// [`None`] is returned if `interval` contains prime factors above [`HEJI_PRIME_LIMIT`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::note::NoteLetter;
+    /// # use tune::notation::JiNote;
+    /// # use tune::temperament::Comma;
+    /// let c4 = NoteLetter::C.in_octave(4);
+    ///
+    /// let major_third = Comma::new("major third", &[-2, 0, 1][..]);
+    /// assert_eq!(JiNote::from_comma(c4, &major_third).unwrap().to_string(), "E↓ 4");
+    ///
+    /// let harmonic_seventh = Comma::new("harmonic seventh", &[-2, 0, 0, 1][..]);
+    /// assert_eq!(JiNote::from_comma(c4, &harmonic_seventh).unwrap().to_string(), "Bb↓7 4");
+    ///
+    /// let perfect_fourth_down = Comma::new("perfect fourth", &[-2, 1][..]);
+    /// assert_eq!(JiNote::from_comma(c4, &perfect_fourth_down).unwrap().to_string(), "G 3");
+    /// ```
+    pub fn from_comma(reference: Note, interval: &Comma) -> Option<Self> {
+        let prime_factors = interval.prime_factors();
+        let power_of = |index: usize| i32::from(prime_factors.get(index).copied().unwrap_or(0));
+
+        let mut num_fifths = power_of(1);
+        let mut num_octaves = power_of(0);
+        let mut inflections = Vec::new();
+
+        for (&power, &prime) in prime_factors.iter().zip(math::U8_PRIMES).skip(2) {
+            if power == 0 {
+                continue;
+            }
+            let accidental = CommaAccidental::for_prime(prime)?;
+            num_fifths += accidental.num_fifths * i32::from(power);
+            num_octaves += accidental.num_octaves * i32::from(power);
+            inflections.push((
+                prime,
+                match accidental.raises {
+                    true => i32::from(power),
+                    false => -i32::from(power),
+                },
+            ));
+        }
+
+        let (ref_letter, ref_octave) = reference.letter_and_octave();
+        let ref_fifths = match ref_letter {
+            NoteLetter::Gsh => -4,
+            NoteLetter::Dsh => -3,
+            NoteLetter::Ash => -2,
+            NoteLetter::F => -1,
+            NoteLetter::C => 0,
+            NoteLetter::G => 1,
+            NoteLetter::D => 2,
+            NoteLetter::A => 3,
+            NoteLetter::E => 4,
+            NoteLetter::B => 5,
+            NoteLetter::Fsh => 6,
+            NoteLetter::Csh => 7,
+        };
+        let ref_diatonic_step =
+            7 * ref_octave.octave_number() + diatonic_step_of_fifths(ref_fifths);
+
+        // A factor of 3 spans a twelfth (11 diatonic steps), a factor of 2 spans an octave (7 diatonic steps).
+        let fifths = ref_fifths + num_fifths;
+        let diatonic_step = ref_diatonic_step + 11 * num_fifths + 7 * num_octaves;
+
+        let (num_sharps, letter_index) = math::i32_dr_u(fifths + 1, 7u32);
+        let letter = [
+            NoteLetter::F,
+            NoteLetter::C,
+            NoteLetter::G,
+            NoteLetter::D,
+            NoteLetter::A,
+            NoteLetter::E,
+            NoteLetter::B,
+        ][usize::try_from(letter_index).unwrap()];
+        let octave = i16::try_from(math::i32_div_u(diatonic_step, 7u32)).ok()?;
+
+        Some(Self {
+            letter,
+            num_sharps,
+            octave: Octave::from_octave_number(octave),
+            inflections,
+        })
+    }
+
+    /// Names the pitch that lies `numer`/`denom` above the `reference` note.
+    ///
+    /// [`None`] is returned if the fraction is degenerate or contains prime factors above [`HEJI_PRIME_LIMIT`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::note::NoteLetter;
+    /// # use tune::notation::JiNote;
+    /// let a4 = NoteLetter::A.in_octave(4);
+    ///
+    /// assert_eq!(JiNote::from_fraction(a4, 3, 2).unwrap().to_string(), "E 5");
+    /// assert_eq!(JiNote::from_fraction(a4, 5, 4).unwrap().to_string(), "C#↓ 5");
+    /// assert_eq!(JiNote::from_fraction(a4, 6, 5).unwrap().to_string(), "C↑ 5");
+    /// assert_eq!(JiNote::from_fraction(a4, 11, 8).unwrap().to_string(), "D↑11 5");
+    /// assert_eq!(JiNote::from_fraction(a4, 1, 13).unwrap().to_string(), "C↑13 1");
+    ///
+    /// // Degenerate fraction
+    /// assert!(JiNote::from_fraction(a4, 0, 1).is_none());
+    ///
+    /// // Prime limit exceeded
+    /// assert!(JiNote::from_fraction(a4, 53, 32).is_none());
+    /// ```
+    pub fn from_fraction(reference: Note, numer: u128, denom: u128) -> Option<Self> {
+        Self::from_comma(reference, &Comma::from_fraction("", numer, denom)?)
+    }
+
+    /// Renders the note name using the given [`JiNotation`].
+    ///
+    /// [`None`] is returned if the notation has no symbol for one of the comma accidentals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::note::NoteLetter;
+    /// # use tune::notation::JiNotation;
+    /// # use tune::notation::JiNote;
+    /// let c4 = NoteLetter::C.in_octave(4);
+    ///
+    /// let septimal_tritone = JiNote::from_fraction(c4, 7, 5).unwrap();
+    /// assert_eq!(septimal_tritone.render(JiNotation::Heji).unwrap(), "Gb↑↓7 4");
+    /// assert_eq!(septimal_tritone.render(JiNotation::Sagittal).unwrap(), "Gb/|!) 4");
+    ///
+    /// let undecimal_tritone = JiNote::from_fraction(c4, 11, 8).unwrap();
+    /// assert_eq!(undecimal_tritone.render(JiNotation::Heji).unwrap(), "F↑11 4");
+    /// assert_eq!(undecimal_tritone.render(JiNotation::Sagittal).unwrap(), "F/|\\ 4");
+    ///
+    /// // No Sagittal symbol for prime 17 available
+    /// let septendecimal_semitone = JiNote::from_fraction(c4, 17, 16).unwrap();
+    /// assert_eq!(septendecimal_semitone.render(JiNotation::Heji).unwrap(), "C#↓17 4");
+    /// assert_eq!(septendecimal_semitone.render(JiNotation::Sagittal), None);
+    /// ```
+    pub fn render(&self, notation: JiNotation) -> Option<String> {
+        let mut rendered = self.letter.to_string();
+
+        let pythagorean_accidental = if self.num_sharps >= 0 { "#" } else { "b" };
+        for _ in 0..self.num_sharps.abs() {
+            rendered.push_str(pythagorean_accidental);
+        }
+
+        for &(prime, num_inflections) in &self.inflections {
+            let upward = num_inflections > 0;
+            let symbol = match notation {
+                JiNotation::Heji => heji_symbol(prime, upward),
+                JiNotation::Sagittal => sagittal_symbol(prime, upward)?.to_owned(),
+            };
+            for _ in 0..num_inflections.abs() {
+                rendered.push_str(&symbol);
+            }
+        }
+
+        Some(format!("{rendered} {}", self.octave.octave_number()))
+    }
+}
+
+/// [`JiNote`]s are rendered in extended Helmholtz-Ellis notation.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::note::NoteLetter;
+/// # use tune::notation::JiNote;
+/// let d4 = NoteLetter::D.in_octave(4);
+/// let ji_note = JiNote::from_fraction(d4, 49, 40).unwrap();
+///
+/// assert_eq!(ji_note.to_string(), "Gb↑↓7↓7 4");
+/// assert_eq!(format!("{ji_note:>12}"), "   Gb↑↓7↓7 4");
+/// ```
+impl Display for JiNote {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.render(JiNotation::Heji) {
+            Some(rendered) => f.pad(&rendered),
+            None => unreachable!("HEJI symbols are defined for all supported primes"),
+        }
+    }
+}
+
+struct CommaAccidental {
+    num_fifths: i32,
+    num_octaves: i32,
+    raises: bool,
+}
+
+impl CommaAccidental {
+    fn for_prime(prime: u8) -> Option<Self> {
+        let &(_, num_fifths) = PYTHAGOREAN_FIFTHS_BY_PRIME
+            .iter()
+            .find(|&&(candidate, _)| candidate == prime)?;
+
+        let prime_in_octaves = Ratio::from_float(prime).as_octaves();
+        let fifths_in_octaves = f64::from(num_fifths) * Ratio::from_float(3).as_octaves();
+        let num_octaves = (prime_in_octaves - fifths_in_octaves).round();
+
+        Some(Self {
+            num_fifths,
+            num_octaves: num_octaves as i32,
+            raises: prime_in_octaves > fifths_in_octaves + num_octaves,
+        })
+    }
+}
+
+fn diatonic_step_of_fifths(num_fifths: i32) -> i32 {
+    math::i32_rem_u(4 * num_fifths, 7u32) as i32
+}
+
+fn heji_symbol(prime: u8, upward: bool) -> String {
+    let arrow = if upward { "↑" } else { "↓" };
+    match prime {
+        5 => arrow.to_owned(),
+        _ => format!("{arrow}{prime}"),
+    }
+}
+
+fn sagittal_symbol(prime: u8, upward: bool) -> Option<&'static str> {
+    Some(match (prime, upward) {
+        (5, true) => "/|",
+        (5, false) => "\\!",
+        (7, true) => "|)",
+        (7, false) => "!)",
+        (11, true) => "/|\\",
+        (11, false) => "\\!/",
+        (13, true) => "(|\\",
+        (13, false) => "(!/",
+        (19, true) => ")|",
+        (19, false) => ")!",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn name_harmonic_series() {
+        let c2 = NoteLetter::C.in_octave(2);
+
+        let harmonics: Vec<_> = (1..=32)
+            .map(|harmonic| {
+                let ji_note = JiNote::from_fraction(c2, harmonic, 1).unwrap();
+                format!(
+                    "{harmonic:>2}: {:<10} {}",
+                    ji_note.render(JiNotation::Heji).unwrap(),
+                    ji_note
+                        .render(JiNotation::Sagittal)
+                        .unwrap_or_else(|| "-".to_owned()),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            harmonics,
+            [
+                " 1: C 2        C 2",
+                " 2: C 3        C 3",
+                " 3: G 3        G 3",
+                " 4: C 4        C 4",
+                " 5: E↓ 4       E\\! 4",
+                " 6: G 4        G 4",
+                " 7: Bb↓7 4     Bb!) 4",
+                " 8: C 5        C 5",
+                " 9: D 5        D 5",
+                "10: E↓ 5       E\\! 5",
+                "11: F↑11 5     F/|\\ 5",
+                "12: G 5        G 5",
+                "13: A↓13 5     A(!/ 5",
+                "14: Bb↓7 5     Bb!) 5",
+                "15: B↓ 5       B\\! 5",
+                "16: C 6        C 6",
+                "17: C#↓17 6    -",
+                "18: D 6        D 6",
+                "19: Eb↑19 6    Eb)| 6",
+                "20: E↓ 6       E\\! 6",
+                "21: F↓7 6      F!) 6",
+                "22: F↑11 6     F/|\\ 6",
+                "23: F#↑23 6    -",
+                "24: G 6        G 6",
+                "25: G#↓↓ 6     G#\\!\\! 6",
+                "26: A↓13 6     A(!/ 6",
+                "27: A 6        A 6",
+                "28: Bb↓7 6     Bb!) 6",
+                "29: Bb↑29 6    -",
+                "30: B↓ 6       B\\! 6",
+                "31: C↓31 7     -",
+                "32: C 7        C 7",
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::str::FromStr;
 
 use crate::key::PianoKey;
 use crate::math;
@@ -282,6 +283,145 @@ impl Display for Note {
     }
 }
 
+/// [`Note`]s can be parsed from MIDI numbers, scientific pitch notation or Helmholtz pitch notation.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::note::HelmholtzOctave;
+/// # use tune::note::Note;
+/// # use tune::note::NoteLetter;
+/// // MIDI number
+/// assert_eq!("69".parse::<Note>().unwrap(), Note::from_midi_number(69));
+/// assert_eq!("-3".parse::<Note>().unwrap(), Note::from_midi_number(-3));
+///
+/// // Scientific pitch notation
+/// assert_eq!("A4".parse::<Note>().unwrap(), NoteLetter::A.in_octave(4));
+/// assert_eq!("C#3".parse::<Note>().unwrap(), NoteLetter::Csh.in_octave(3));
+/// assert_eq!("Eb5".parse::<Note>().unwrap(), NoteLetter::Dsh.in_octave(5));
+/// assert_eq!("C-1".parse::<Note>().unwrap(), Note::from_midi_number(0));
+/// assert_eq!("B#3".parse::<Note>().unwrap(), NoteLetter::C.in_octave(4));
+/// assert_eq!("Fbb4".parse::<Note>().unwrap(), NoteLetter::Dsh.in_octave(4));
+/// assert_eq!("bb4".parse::<Note>().unwrap(), NoteLetter::Ash.in_octave(4));
+///
+/// // Helmholtz pitch notation
+/// assert_eq!("a'".parse::<Note>().unwrap(), NoteLetter::A.in_octave(4));
+/// assert_eq!("c".parse::<Note>().unwrap(), NoteLetter::C.in_octave(HelmholtzOctave::Small));
+/// assert_eq!("F#".parse::<Note>().unwrap(), NoteLetter::Fsh.in_octave(HelmholtzOctave::Great));
+/// assert_eq!("C,,".parse::<Note>().unwrap(), NoteLetter::C.in_octave(HelmholtzOctave::SubContra));
+/// assert_eq!("eb''".parse::<Note>().unwrap(), NoteLetter::Dsh.in_octave(5));
+///
+/// // Errors
+/// assert_eq!(
+///     "H4".parse::<Note>().unwrap_err(),
+///     "Invalid note 'H4': Must be a MIDI number (e.g. 69), a note name with octave number (e.g. A4, C#3, Eb5) \
+///      or a note name with Helmholtz octave marks (e.g. a', c, C,)"
+/// );
+/// assert_eq!(
+///     "A4.5".parse::<Note>().unwrap_err(),
+///     "Invalid octave '4.5': Must be an integer (e.g. 4) or Helmholtz octave marks (e.g. ' or ,)"
+/// );
+/// assert_eq!(
+///     "c,".parse::<Note>().unwrap_err(),
+///     "Invalid octave ',': Lowercase note names can only be raised using ' marks"
+/// );
+/// assert_eq!(
+///     "C,,,".parse::<Note>().unwrap_err(),
+///     "Invalid octave ',,,': Out of Helmholtz octave range (C,, to b'''''')"
+/// );
+/// ```
+impl FromStr for Note {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(midi_number) = s.parse::<i32>() {
+            return Ok(Note::from_midi_number(midi_number));
+        }
+
+        let mut chars = s.chars();
+        let (letter, uppercase) = match chars.next() {
+            Some(c @ 'A'..='G') => (c, true),
+            Some(c @ 'a'..='g') => (c.to_ascii_uppercase(), false),
+            _ => {
+                return Err(format!(
+                    "Invalid note '{s}': Must be a MIDI number (e.g. 69), \
+                     a note name with octave number (e.g. A4, C#3, Eb5) \
+                     or a note name with Helmholtz octave marks (e.g. a', c, C,)"
+                ));
+            }
+        };
+        let note_letter = match letter {
+            'C' => NoteLetter::C,
+            'D' => NoteLetter::D,
+            'E' => NoteLetter::E,
+            'F' => NoteLetter::F,
+            'G' => NoteLetter::G,
+            'A' => NoteLetter::A,
+            'B' => NoteLetter::B,
+            other => unreachable!("value was {}", other),
+        };
+
+        let rest = chars.as_str();
+        let (accidentals, octave) =
+            rest.split_at(rest.find(|c| c != '#' && c != 'b').unwrap_or(rest.len()));
+        let num_semitones = accidentals
+            .chars()
+            .map(|c| if c == '#' { 1 } else { -1 })
+            .sum();
+
+        let octave = parse_octave(octave, uppercase)
+            .map_err(|e| format!("Invalid octave '{octave}': {e}"))?;
+
+        Ok(note_letter.in_octave(octave).plus_semitones(num_semitones))
+    }
+}
+
+fn parse_octave(s: &str, uppercase: bool) -> Result<Octave, String> {
+    if let Ok(octave_number) = s.parse::<i16>() {
+        return Ok(Octave::from_octave_number(octave_number));
+    }
+
+    let num_raises = s.chars().filter(|&c| c == '\'').count();
+    let num_lowers = s.chars().filter(|&c| c == ',').count();
+    if num_raises + num_lowers != s.len() {
+        return Err(
+            "Must be an integer (e.g. 4) or Helmholtz octave marks (e.g. ' or ,)".to_owned(),
+        );
+    }
+
+    let index = match (uppercase, num_raises, num_lowers) {
+        (true, 0, num_lowers) => 2usize.checked_sub(num_lowers),
+        (false, num_raises, 0) => Some(3 + num_raises),
+        (true, _, _) => {
+            return Err("Uppercase note names can only be lowered using , marks".to_owned());
+        }
+        (false, _, _) => {
+            return Err("Lowercase note names can only be raised using ' marks".to_owned());
+        }
+    };
+
+    index
+        .and_then(|index| {
+            [
+                HelmholtzOctave::SubContra,
+                HelmholtzOctave::Contra,
+                HelmholtzOctave::Great,
+                HelmholtzOctave::Small,
+                HelmholtzOctave::OneLined,
+                HelmholtzOctave::TwoLined,
+                HelmholtzOctave::ThreeLined,
+                HelmholtzOctave::FourLined,
+                HelmholtzOctave::FiveLined,
+                HelmholtzOctave::SixLined,
+            ]
+            .get(index)
+            .copied()
+        })
+        .map(Octave::from)
+        .ok_or_else(|| "Out of Helmholtz octave range (C,, to b'''''')".to_owned())
+}
+
 /// The speaking name of a note within its octave.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum NoteLetter {
@@ -4,6 +4,29 @@ pub fn split_balanced(s: &str, split_character: char) -> Vec<&str> {
         .collect::<Vec<_>>()
 }
 
+/// Splits at the last occurrence of any of the `split_characters`, e.g. s.t. chains of operators are evaluated from left to right.
+pub fn rsplit_once_balanced<'a>(
+    s: &'a str,
+    split_characters: &[char],
+) -> Option<(&'a str, char, &'a str)> {
+    let mut num_parens = 0;
+    for (index, c) in s.char_indices().rev() {
+        match c {
+            ')' => num_parens += 1,
+            '(' => num_parens -= 1,
+            other if num_parens == 0 && split_characters.contains(&other) => {
+                return Some((
+                    s[..index].trim(),
+                    other,
+                    s[index + other.len_utf8()..].trim(),
+                ));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 fn balanced(character_to_match: char) -> impl FnMut(char) -> bool {
     let mut num_parens = 0;
     move |c| match c {
@@ -529,7 +529,21 @@ impl Display for Ratio {
 /// assert_approx_eq!("3/2".parse::<Ratio>().unwrap().as_float(), 1.5);
 /// assert_approx_eq!("7/12:2".parse::<Ratio>().unwrap().as_semitones(), 7.0);
 /// assert_approx_eq!("702c".parse::<Ratio>().unwrap().as_cents(), 702.0);
-/// assert_eq!("foo".parse::<Ratio>().unwrap_err(), "Invalid expression \'foo\': Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2) or cents value (e.g. 702c)");
+/// assert_approx_eq!("(3/2)^4/2".parse::<Ratio>().unwrap().as_float(), 2.53125);
+/// assert_approx_eq!("3/2*5/4".parse::<Ratio>().unwrap().as_float(), 1.875);
+/// assert_approx_eq!("7\\12".parse::<Ratio>().unwrap().as_semitones(), 7.0);
+/// assert_approx_eq!("13\\13<3>".parse::<Ratio>().unwrap().as_float(), 3.0);
+/// assert_approx_eq!("660Hz/440Hz".parse::<Ratio>().unwrap().as_float(), 1.5);
+/// assert_eq!(
+///     "foo".parse::<Ratio>().unwrap_err(),
+///     "Invalid expression 'foo': Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+///      cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+///      or pitch ratio (e.g. 660Hz/440Hz)"
+/// );
+/// assert_eq!(
+///     "660Hz/2".parse::<Ratio>().unwrap_err(),
+///     "Invalid expression '660Hz/2': Pitches (e.g. 440Hz) can only be divided by other pitches"
+/// );
 impl FromStr for Ratio {
     type Err = String;
 
@@ -573,12 +587,16 @@ impl FromStr for RatioExpression {
 }
 
 /// Type used to distinguish which particular outer expression was given as string input before parsing.
+///
+/// New variants may be added when the expression syntax is extended.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub enum RatioExpressionVariant {
     Float { float_value: f64 },
     Fraction { numer: f64, denom: f64 },
     IntervalFraction { exponent: f64, interval: f64 },
     Cents { cents_value: f64 },
+    Product { factor1: f64, factor2: f64 },
 }
 
 impl RatioExpressionVariant {
@@ -597,6 +615,7 @@ impl RatioExpressionVariant {
             Self::Fraction { numer, denom } => numer / denom,
             Self::IntervalFraction { exponent, interval } => interval.powf(exponent),
             Self::Cents { cents_value } => Ratio::from_cents(cents_value).as_float(),
+            Self::Product { factor1, factor2 } => factor1 * factor2,
         };
         if as_float.is_finite() {
             Ok(as_float)
@@ -608,15 +627,33 @@ impl RatioExpressionVariant {
 
 fn parse_ratio(s: &str) -> Result<RatioExpressionVariant, String> {
     let s = s.trim();
-    if let [exponent, interval] = parse::split_balanced(s, ':').as_slice() {
+    if let Some((steps, divisions, period)) = split_edo_step(s) {
+        let period = match period {
+            Some(period) => parse_ratio_as_float(period, "period")?,
+            None => 2.0,
+        };
+        Ok(RatioExpressionVariant::IntervalFraction {
+            exponent: parse_ratio_as_float(steps, "number of steps")?
+                / parse_ratio_as_float(divisions, "number of divisions")?,
+            interval: period,
+        })
+    } else if let [exponent, interval] = parse::split_balanced(s, ':').as_slice() {
         Ok(RatioExpressionVariant::IntervalFraction {
             exponent: parse_ratio_as_float(exponent, "exponent")?,
             interval: parse_ratio_as_float(interval, "interval")?,
         })
-    } else if let [numer, denom] = parse::split_balanced(s, '/').as_slice() {
-        Ok(RatioExpressionVariant::Fraction {
-            numer: parse_ratio_as_float(numer, "numerator")?,
-            denom: parse_ratio_as_float(denom, "denominator")?,
+    } else if let Some((left, operator, right)) = parse::rsplit_once_balanced(s, &['*', '/']) {
+        match operator {
+            '*' => Ok(RatioExpressionVariant::Product {
+                factor1: parse_ratio_as_float(left, "factor")?,
+                factor2: parse_ratio_as_float(right, "factor")?,
+            }),
+            _ => parse_fraction(left, right),
+        }
+    } else if let [base, exponent] = parse::split_balanced(s, '^').as_slice() {
+        Ok(RatioExpressionVariant::IntervalFraction {
+            exponent: parse_ratio_as_float(exponent, "exponent")?,
+            interval: parse_ratio_as_float(base, "base")?,
         })
     } else if let [cents_value, ""] = parse::split_balanced(s, 'c').as_slice() {
         Ok(RatioExpressionVariant::Cents {
@@ -624,23 +661,77 @@ fn parse_ratio(s: &str) -> Result<RatioExpressionVariant, String> {
         })
     } else if s.starts_with('(') && s.ends_with(')') {
         parse_ratio(&s[1..s.len() - 1])
+    } else if is_pitch(s) {
+        Err("Pitches (e.g. 440Hz) are only allowed in pitch ratios (e.g. 660Hz/440Hz)".to_string())
     } else {
         Ok(RatioExpressionVariant::Float {
             float_value: s.parse().map_err(|_| {
-                "Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
-                 interval fraction (e.g. 7/12:2) or cents value (e.g. 702c)"
+                "Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+                 cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+                 or pitch ratio (e.g. 660Hz/440Hz)"
                     .to_string()
             })?,
         })
     }
 }
 
+/// Splits `steps\divisions` or `steps\divisions<period>` into its parts if `steps` and `divisions` are single operands.
+///
+/// Angle brackets are only meaningful in EDO steps. This is why the period is split off before any other operator is considered.
+fn split_edo_step(s: &str) -> Option<(&str, &str, Option<&str>)> {
+    let (edo_step, period) = match s.strip_suffix('>') {
+        Some(s) => {
+            let (edo_step, period) = s.rsplit_once('<')?;
+            (edo_step, Some(period))
+        }
+        None => (s, None),
+    };
+
+    let [steps, divisions] = parse::split_balanced(edo_step, '\\')[..] else {
+        return None;
+    };
+
+    let is_operand = |s| {
+        [':', '*', '/', '^']
+            .into_iter()
+            .all(|operator| parse::split_balanced(s, operator).len() == 1)
+    };
+
+    (is_operand(steps) && is_operand(divisions)).then_some((steps, divisions, period))
+}
+
+fn parse_fraction(numer: &str, denom: &str) -> Result<RatioExpressionVariant, String> {
+    match (is_pitch(numer), is_pitch(denom)) {
+        (false, false) => Ok(RatioExpressionVariant::Fraction {
+            numer: parse_ratio_as_float(numer, "numerator")?,
+            denom: parse_ratio_as_float(denom, "denominator")?,
+        }),
+        (true, true) => Ok(RatioExpressionVariant::Fraction {
+            numer: parse_pitch_as_hz(numer, "numerator")?,
+            denom: parse_pitch_as_hz(denom, "denominator")?,
+        }),
+        (true, false) | (false, true) => {
+            Err("Pitches (e.g. 440Hz) can only be divided by other pitches".to_string())
+        }
+    }
+}
+
 fn parse_ratio_as_float(s: &str, name: &str) -> Result<f64, String> {
     parse_ratio(s)
         .and_then(RatioExpressionVariant::as_float)
         .map_err(|e| format!("Invalid {name} '{s}': {e}"))
 }
 
+fn is_pitch(s: &str) -> bool {
+    s.ends_with("Hz") || s.ends_with("hz")
+}
+
+fn parse_pitch_as_hz(s: &str, name: &str) -> Result<f64, String> {
+    s.parse::<Pitch>()
+        .map(Pitch::as_hz)
+        .map_err(|e| format!("Invalid {name} '{s}': {e}"))
+}
+
 /// An odd-limit nearest-fraction approximation fo a given [`Ratio`].
 #[derive(Copy, Clone, Debug)]
 pub struct NearestFraction {
@@ -756,6 +847,27 @@ mod test {
             ("702c/3", 0.5000),    // 2^(702/1200)/3 - 702 cents divided by 3
             ("3/702c", 2.0000),    // 3/2^(702/1200) - 3 divided by 702 cents
             ("(1404/2)c", 1.5000), // 2^(702/1200) - 1402/2 cents
+            ("3/2*4/3", 2.0000),
+            ("3*5/4", 3.7500),
+            ("9/8/9*8", 1.0000),   // Evaluated from left to right
+            ("(3/2)^4/2", 2.5313), // (3/2)^4/2 - pythagorean major ninth
+            ("2^(7/12)", 1.4983),
+            ("(3/2)^-1", 0.6667),
+            ("2^0.5*2^0.5", 2.0000),
+            ("7\\12", 1.4983), // 2^(7/12) - 12-edo perfect fifth
+            ("-12\\12", 0.5000),
+            ("13\\13<3>", 3.0000),   // 3^(13/13) - Bohlen-Pierce tritave
+            ("1\\2<9/4>", 1.5000),   // (9/4)^(1/2)
+            ("1\\2<(9/4)>", 1.5000), // (9/4)^(1/2)
+            ("7\\12/7\\12", 1.0000),
+            ("2*1\\2<(9/4)>", 3.0000),
+            ("(1\\2<9/4>)^2", 2.2500),
+            ("1\\2<(9/4)>^2", 2.2500),
+            ("1\\2<3>*1\\2<3>", 3.0000),
+            ("660Hz/440Hz", 1.5000),
+            ("(440 hz / 660hz)", 0.6667),
+            ("(660Hz/440Hz)^2", 2.2500),
+            ("3*440Hz/660Hz", 2.0000),
         ];
 
         for (input, expected) in test_cases.iter() {
@@ -786,12 +898,51 @@ mod test {
             (
                 "(1/x)c",
                 "Invalid expression '(1/x)c': Invalid cents value '(1/x)': Invalid denominator 'x': \
-                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2) or cents value (e.g. 702c)",
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+                 cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+                 or pitch ratio (e.g. 660Hz/440Hz)",
             ),
             (
                 "   (1   /x )c ",
                 "Invalid expression '(1   /x )c': Invalid cents value '(1   /x )': Invalid denominator 'x': \
-                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2) or cents value (e.g. 702c)",
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+                 cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+                 or pitch ratio (e.g. 660Hz/440Hz)",
+            ),
+            (
+                "(3/2)^x",
+                "Invalid expression '(3/2)^x': Invalid exponent 'x': \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+                 cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+                 or pitch ratio (e.g. 660Hz/440Hz)",
+            ),
+            ("7\\0", "Invalid expression '7\\0': Evaluates to inf"),
+            (
+                "7\\12<3",
+                "Invalid expression '7\\12<3': Invalid number of divisions '12<3': \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+                 cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+                 or pitch ratio (e.g. 660Hz/440Hz)",
+            ),
+            (
+                "440Hz",
+                "Invalid expression '440Hz': Pitches (e.g. 440Hz) are only allowed in pitch ratios (e.g. 660Hz/440Hz)",
+            ),
+            (
+                "3/2*440Hz",
+                "Invalid expression '3/2*440Hz': Invalid factor '440Hz': \
+                 Pitches (e.g. 440Hz) are only allowed in pitch ratios (e.g. 660Hz/440Hz)",
+            ),
+            (
+                "440Hz/3",
+                "Invalid expression '440Hz/3': Pitches (e.g. 440Hz) can only be divided by other pitches",
+            ),
+            (
+                "440Hz/xHz",
+                "Invalid expression '440Hz/xHz': Invalid denominator 'xHz': Invalid frequency: 'x': \
+                 Invalid expression 'x': Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7/12:2), \
+                 cents value (e.g. 702c), power (e.g. (3/2)^4), EDO step (e.g. 7\\12 or 13\\13<3>) \
+                 or pitch ratio (e.g. 660Hz/440Hz)",
             ),
         ];
 
@@ -823,6 +974,22 @@ mod test {
             "(0/3)c".parse::<RatioExpression>().unwrap().variant(),
             RatioExpressionVariant::Cents { .. }
         ));
+        assert!(matches!(
+            "(3/2)^4".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::IntervalFraction { .. }
+        ));
+        assert!(matches!(
+            "7\\12".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::IntervalFraction { .. }
+        ));
+        assert!(matches!(
+            "660Hz/440Hz".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Fraction { .. }
+        ));
+        assert!(matches!(
+            "(3/2)*(5/4)".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Product { .. }
+        ));
     }
 
     #[test]
@@ -1070,43 +1070,84 @@ impl<N: PitchedNote> From<N> for KbmRoot {
     }
 }
 
+/// [`KbmRoot`]s can be parsed from a reference note with an optional pitch or pitch offset.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::key::PianoKey;
+/// # use tune::scala::KbmRoot;
+/// let kbm_root = "69".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(69));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 440.0);
+///
+/// let kbm_root = "C4@261.6Hz".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(60));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 261.6);
+///
+/// let kbm_root = "A4+13.7c".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(69));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 443.495723);
+///
+/// let kbm_root = "C-1-100c".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(0));
+/// assert_approx_eq!(kbm_root.ref_pitch.as_hz(), 7.716927);
+///
+/// let kbm_root = "d'".parse::<KbmRoot>().unwrap();
+/// assert_eq!(kbm_root.ref_key, PianoKey::from_midi_number(62));
+///
+/// assert_eq!(
+///     "X4@440Hz".parse::<KbmRoot>().unwrap_err(),
+///     "Invalid note 'X4': Must be a MIDI number (e.g. 69), a note name with octave number (e.g. A4, C#3, Eb5) \
+///      or a note name with Helmholtz octave marks (e.g. a', c, C,)"
+/// );
+/// assert_eq!(
+///     "A4@440".parse::<KbmRoot>().unwrap_err(),
+///     "Invalid pitch '440': Must end with Hz or hz"
+/// );
+/// assert_eq!(
+///     "A4+x".parse::<KbmRoot>().unwrap_err(),
+///     "Invalid delta 'x': Invalid expression 'x': Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
+///      interval fraction (e.g. 7/12:2), cents value (e.g. 702c), power (e.g. (3/2)^4), \
+///      EDO step (e.g. 7\\12 or 13\\13<3>) or pitch ratio (e.g. 660Hz/440Hz)"
+/// );
+/// assert_eq!(
+///     "A4 4".parse::<KbmRoot>().unwrap_err(),
+///     "Must be an expression of type 69, A4, c', A4@440Hz, A4+13.7c or A4-13.7c"
+/// );
+/// ```
 impl FromStr for KbmRoot {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
         if let [note, pitch] = parse::split_balanced(s, '@').as_slice() {
-            let midi_number = note
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid note '{note}': Must be an integer"))?;
+            let note = note.parse::<Note>()?;
             let pitch: Pitch = pitch
                 .parse()
                 .map_err(|e| format!("Invalid pitch '{pitch}': {e}"))?;
-            Ok(Note::from_midi_number(midi_number).at_pitch(pitch).into())
+            Ok(note.at_pitch(pitch).into())
         } else if let [note, delta] = parse::split_balanced(s, '+').as_slice() {
-            let midi_number = note
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid note '{note}': Must be an integer"))?;
+            let note = note.parse::<Note>()?;
             let delta = delta
                 .parse()
                 .map_err(|e| format!("Invalid delta '{delta}': {e}"))?;
-            Ok(Note::from_midi_number(midi_number)
-                .alter_pitch_by(delta)
-                .into())
-        } else if let [note, delta] = parse::split_balanced(s, '-').as_slice() {
-            let midi_number = note
-                .parse::<i32>()
-                .map_err(|_| format!("Invalid note '{note}': Must be an integer"))?;
+            Ok(note.alter_pitch_by(delta).into())
+        } else if let Ok(note) = s.parse::<Note>() {
+            Ok(note.into())
+        } else if let Some((note, _, delta)) = parse::rsplit_once_balanced(s, &['-']) {
+            // The last occurrence is used since the note might contain a negative octave number, e.g. C-1
+            let note = note.parse::<Note>()?;
             let delta = delta
                 .parse::<Ratio>()
                 .map_err(|e| format!("Invalid delta '{delta}': {e}"))?;
-            Ok(Note::from_midi_number(midi_number)
-                .alter_pitch_by(delta.inv())
-                .into())
+            Ok(note.alter_pitch_by(delta.inv()).into())
         } else {
-            let note_number = s
-                .parse::<i32>()
-                .map_err(|_| "Must be an expression of type 69, 69@440Hz or 69+100c".to_string())?;
-            Ok(Note::from_midi_number(note_number).into())
+            Err(
+                "Must be an expression of type 69, A4, c', A4@440Hz, A4+13.7c or A4-13.7c"
+                    .to_owned(),
+            )
         }
     }
 }
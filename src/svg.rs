@@ -0,0 +1,221 @@
+//! Render keyboard layouts and scale charts as SVG documents.
+
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+use crate::layout::IsomorphicKeyboard;
+use crate::layout::KeyGrid;
+
+const HEX_RADIUS: f64 = 24.0;
+const RECT_SIZE: f64 = 42.0;
+const STRIP_KEY_WIDTH: f64 = 42.0;
+const STRIP_KEY_HEIGHT: f64 = 120.0;
+const FONT_SIZE: f64 = 9.0;
+const LINE_HEIGHT: f64 = 11.0;
+const MARGIN: f64 = 4.0;
+
+/// A single key of a rendered chart.
+#[derive(Clone, Debug)]
+pub struct SvgKey {
+    /// The fill color of the key, e.g. `#ffffff`.
+    pub color: String,
+    /// Text lines displayed in the center of the key.
+    pub labels: Vec<String>,
+}
+
+/// Renders the keys of an [`IsomorphicKeyboard`] within a rectangular area of `num_columns` × `num_rows` keys centered around the origin.
+///
+/// The callback receives the scale degree of each key and returns its appearance.
+/// For [`KeyGrid::Hex`] keyboards, every other row is shifted by half a key s.t. the rendered area remains rectangular.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::IsomorphicKeyboard;
+/// # use tune::layout::KeyGrid;
+/// # use tune::svg;
+/// # use tune::svg::SvgKey;
+/// let keyboard = IsomorphicKeyboard::new(KeyGrid::Rect, 1, 5);
+///
+/// let svg = svg::render_keyboard(&keyboard, 2, 1, |degree| SvgKey {
+///     color: "#ffffff".to_owned(),
+///     labels: vec![degree.to_string()],
+/// });
+///
+/// assert_eq!(
+///     svg,
+///     r##"<svg xmlns="http://www.w3.org/2000/svg" width="92.0" height="50.0" viewBox="-67.0 -25.0 92.0 50.0">
+/// <g font-family="sans-serif" font-size="9.0" text-anchor="middle" dominant-baseline="middle">
+/// <polygon points="-63.0,-21.0 -21.0,-21.0 -21.0,21.0 -63.0,21.0" fill="#ffffff" stroke="#000000"/>
+/// <text x="-42.0" y="0.0">-1</text>
+/// <polygon points="-21.0,-21.0 21.0,-21.0 21.0,21.0 -21.0,21.0" fill="#ffffff" stroke="#000000"/>
+/// <text x="0.0" y="0.0">0</text>
+/// </g>
+/// </svg>
+/// "##
+/// );
+/// ```
+pub fn render_keyboard(
+    keyboard: &IsomorphicKeyboard,
+    num_columns: u16,
+    num_rows: u16,
+    mut get_key: impl FnMut(i32) -> SvgKey,
+) -> String {
+    let columns = centered_range(num_columns);
+    let rows = centered_range(num_rows);
+
+    let mut shapes = Vec::new();
+    for row in rows.rev() {
+        for column in columns.clone() {
+            let (x, y, corners) = match keyboard.grid() {
+                KeyGrid::Hex => {
+                    let x = column - row.div_euclid(2);
+                    let center = (
+                        3f64.sqrt() * HEX_RADIUS * (f64::from(x) + f64::from(row) / 2.0),
+                        -1.5 * HEX_RADIUS * f64::from(row),
+                    );
+                    (x, row, hex_corners(center))
+                }
+                KeyGrid::Rect => {
+                    let center = (RECT_SIZE * f64::from(column), -RECT_SIZE * f64::from(row));
+                    (column, row, rect_corners(center, RECT_SIZE, RECT_SIZE))
+                }
+            };
+            let degree = keyboard.get_key(x, y);
+            shapes.push((corners, get_key(degree)));
+        }
+    }
+
+    render_shapes(shapes)
+}
+
+/// Renders keys side by side like a piano-style strip.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::svg;
+/// # use tune::svg::SvgKey;
+/// let svg = svg::render_strip([SvgKey {
+///     color: "#ffffff".to_owned(),
+///     labels: vec!["C".to_owned(), "0.0c".to_owned()],
+/// }]);
+///
+/// assert_eq!(
+///     svg,
+///     r##"<svg xmlns="http://www.w3.org/2000/svg" width="50.0" height="128.0" viewBox="-25.0 -64.0 50.0 128.0">
+/// <g font-family="sans-serif" font-size="9.0" text-anchor="middle" dominant-baseline="middle">
+/// <polygon points="-21.0,-60.0 21.0,-60.0 21.0,60.0 -21.0,60.0" fill="#ffffff" stroke="#000000"/>
+/// <text x="0.0" y="-5.5">C</text>
+/// <text x="0.0" y="5.5">0.0c</text>
+/// </g>
+/// </svg>
+/// "##
+/// );
+/// ```
+pub fn render_strip(keys: impl IntoIterator<Item = SvgKey>) -> String {
+    let shapes = keys
+        .into_iter()
+        .enumerate()
+        .map(|(index, key)| {
+            let center = (STRIP_KEY_WIDTH * index as f64, 0.0);
+            (rect_corners(center, STRIP_KEY_WIDTH, STRIP_KEY_HEIGHT), key)
+        })
+        .collect();
+
+    render_shapes(shapes)
+}
+
+fn centered_range(num_items: u16) -> std::ops::Range<i16> {
+    let num_items = i16::try_from(num_items).unwrap_or(i16::MAX);
+    let start = -(num_items / 2);
+    start..start + num_items
+}
+
+fn hex_corners((x, y): (f64, f64)) -> Vec<(f64, f64)> {
+    (0..6)
+        .map(|index| {
+            let angle = PI / 6.0 + PI / 3.0 * f64::from(index);
+            (x + HEX_RADIUS * angle.cos(), y + HEX_RADIUS * angle.sin())
+        })
+        .collect()
+}
+
+fn rect_corners((x, y): (f64, f64), width: f64, height: f64) -> Vec<(f64, f64)> {
+    let (half_width, half_height) = (width / 2.0, height / 2.0);
+    vec![
+        (x - half_width, y - half_height),
+        (x + half_width, y - half_height),
+        (x + half_width, y + half_height),
+        (x - half_width, y + half_height),
+    ]
+}
+
+fn render_shapes(shapes: Vec<(Vec<(f64, f64)>, SvgKey)>) -> String {
+    let corners = || shapes.iter().flat_map(|(corners, _)| corners);
+    let min_x = corners().map(|&(x, _)| x).fold(0.0, f64::min) - MARGIN;
+    let max_x = corners().map(|&(x, _)| x).fold(0.0, f64::max) + MARGIN;
+    let min_y = corners().map(|&(_, y)| y).fold(0.0, f64::min) - MARGIN;
+    let max_y = corners().map(|&(_, y)| y).fold(0.0, f64::max) + MARGIN;
+    let (width, height) = (max_x - min_x, max_y - min_y);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.1}" height="{height:.1}" viewBox="{min_x:.1} {min_y:.1} {width:.1} {height:.1}">"#
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<g font-family="sans-serif" font-size="{FONT_SIZE:.1}" text-anchor="middle" dominant-baseline="middle">"#
+    )
+    .unwrap();
+
+    for (corners, key) in &shapes {
+        let points = corners
+            .iter()
+            .map(|(x, y)| format!("{:.1},{:.1}", normalize(*x), normalize(*y)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            svg,
+            r##"<polygon points="{points}" fill="{}" stroke="#000000"/>"##,
+            escape(&key.color)
+        )
+        .unwrap();
+
+        let num_corners = corners.len() as f64;
+        let center_x = corners.iter().map(|&(x, _)| x).sum::<f64>() / num_corners;
+        let center_y = corners.iter().map(|&(_, y)| y).sum::<f64>() / num_corners;
+        let first_line_y = center_y - LINE_HEIGHT * (key.labels.len() as f64 - 1.0) / 2.0;
+
+        for (index, label) in key.labels.iter().enumerate() {
+            writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                normalize(center_x),
+                normalize(first_line_y + LINE_HEIGHT * index as f64),
+                escape(label)
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(svg, "</g>").unwrap();
+    writeln!(svg, "</svg>").unwrap();
+
+    svg
+}
+
+/// Avoids rendering rounding artifacts like -0.0.
+fn normalize(value: f64) -> f64 {
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded == 0.0 { 0.0 } else { rounded }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -357,6 +357,66 @@ impl Comma {
         }
     }
 
+    /// Creates a comma with the given `description` from the prime factor decomposition of `numer`/`denom`.
+    ///
+    /// [`None`] is returned if `numer` or `denom` is zero or contains a prime factor above 251.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::Comma;
+    /// let syntonic_comma = Comma::from_fraction("syntonic comma", 81, 80).unwrap();
+    /// assert_eq!(syntonic_comma.prime_factors(), [-4, 4, -1]);
+    ///
+    /// // Common factors cancel out
+    /// let perfect_fifth = Comma::from_fraction("perfect fifth", 6, 4).unwrap();
+    /// assert_eq!(perfect_fifth.prime_factors(), [-1, 1]);
+    ///
+    /// let unison = Comma::from_fraction("unison", 7, 7).unwrap();
+    /// assert_eq!(unison.prime_limit(), 1);
+    ///
+    /// assert!(Comma::from_fraction("zero", 0, 1).is_none());
+    /// assert!(Comma::from_fraction("257th harmonic", 257, 256).is_none());
+    /// ```
+    pub fn from_fraction(
+        description: impl Into<Cow<'static, str>>,
+        mut numer: u128,
+        mut denom: u128,
+    ) -> Option<Self> {
+        if numer == 0 || denom == 0 {
+            return None;
+        }
+
+        let mut prime_factors = Vec::new();
+        for &prime in math::U8_PRIMES {
+            if numer == 1 && denom == 1 {
+                break;
+            }
+
+            let prime = u128::from(prime);
+            let mut power = 0i8;
+            while numer.is_multiple_of(prime) {
+                numer /= prime;
+                power = power.checked_add(1)?;
+            }
+            while denom.is_multiple_of(prime) {
+                denom /= prime;
+                power = power.checked_sub(1)?;
+            }
+            prime_factors.push(power);
+        }
+
+        if numer != 1 || denom != 1 {
+            return None;
+        }
+
+        while prime_factors.last() == Some(&0) {
+            prime_factors.pop();
+        }
+
+        Some(Self::new(description, prime_factors))
+    }
+
     /// Returns the name/description of the [`Comma`].
     pub fn description(&self) -> &str {
         &self.description
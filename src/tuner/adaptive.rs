@@ -0,0 +1,388 @@
+use std::str::FromStr;
+
+use crate::pitch::Pitch;
+use crate::pitch::Ratio;
+
+const MAX_ITERATIONS: usize = 100;
+const CONVERGENCE_THRESHOLD_CENTS: f64 = 1e-6;
+
+/// Configures the adaptive just intonation mode of the [`JitTuner`](super::JitTuner).
+#[derive(Copy, Clone, Debug)]
+pub struct AdaptiveOptions {
+    /// Defines how a sounding chord is positioned after its intervals have been made pure.
+    pub strategy: AdaptiveStrategy,
+
+    /// The largest odd factor of the pure ratios that intervals are retuned toward.
+    pub odd_limit: u16,
+
+    /// The maximum amount by which a single note is retuned.
+    ///
+    /// Intervals deviating from their nearest pure ratio by more than this value are considered dissonant and are not retuned.
+    pub max_deviation: Ratio,
+}
+
+/// Defines how a sounding chord is positioned relative to the nominal pitches of the scale.
+///
+/// All strategies make the intervals between sounding notes pure. They only differ in the common offset that is applied to the chord.
+/// Notes that do not form a consonance with each other are positioned independently.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::tuner::AdaptiveStrategy;
+/// assert_eq!("anchor".parse(), Ok(AdaptiveStrategy::Anchor));
+/// assert_eq!("drift".parse(), Ok(AdaptiveStrategy::Drift));
+/// assert_eq!("center".parse(), Ok(AdaptiveStrategy::Center));
+/// assert_eq!(
+///     "foo".parse::<AdaptiveStrategy>(),
+///     Err("Invalid strategy 'foo'. Should be `anchor`, `drift` or `center`".to_owned())
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AdaptiveStrategy {
+    /// The oldest sounding note keeps its pitch and all other notes are tuned relative to it.
+    Anchor,
+
+    /// The chord is shifted s.t. the total absolute deviation from the nominal pitches is minimized.
+    Drift,
+
+    /// The chord is shifted s.t. the average deviation from the nominal pitches is zero (similar to Hermode Tuning).
+    Center,
+}
+
+impl FromStr for AdaptiveStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match &*s.to_lowercase() {
+            "anchor" => AdaptiveStrategy::Anchor,
+            "drift" => AdaptiveStrategy::Drift,
+            "center" => AdaptiveStrategy::Center,
+            _ => {
+                return Err(format!(
+                    "Invalid strategy '{s}'. Should be `anchor`, `drift` or `center`"
+                ));
+            }
+        })
+    }
+}
+
+/// Retunes the currently sounding notes toward pure ratios within a given odd limit.
+///
+/// The model tracks the nominal pitch of each key and yields the adapted pitches of all keys affected by a change.
+pub struct AdaptiveTuningModel<K> {
+    options: AdaptiveOptions,
+    notes: Vec<AdaptiveNote<K>>, // Ordered by age
+}
+
+struct AdaptiveNote<K> {
+    key: K,
+    nominal_pitch: Pitch,
+    deviation: Ratio,
+}
+
+impl<K> AdaptiveNote<K> {
+    fn adapted_pitch(&self) -> Pitch {
+        self.nominal_pitch * self.deviation
+    }
+}
+
+impl<K> AdaptiveTuningModel<K> {
+    pub fn new(options: AdaptiveOptions) -> Self {
+        Self {
+            options,
+            notes: Vec::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq> AdaptiveTuningModel<K> {
+    /// Registers `key` with the given nominal `pitch`.
+    ///
+    /// Returns the adapted pitches of all keys whose pitch has changed, including `key`.
+    pub fn note_on(&mut self, key: K, pitch: Pitch) -> Vec<(K, Pitch)> {
+        self.notes.retain(|note| note.key != key);
+        self.notes.push(AdaptiveNote {
+            key,
+            nominal_pitch: pitch,
+            deviation: Ratio::default(),
+        });
+        self.adapt_including(key)
+    }
+
+    /// Deregisters `key`.
+    ///
+    /// Returns the adapted pitches of all remaining keys whose pitch has changed.
+    pub fn note_off(&mut self, key: K) -> Vec<(K, Pitch)> {
+        let num_notes = self.notes.len();
+        self.notes.retain(|note| note.key != key);
+        if self.notes.len() == num_notes {
+            return Vec::new();
+        }
+        self.adapt()
+    }
+
+    /// Updates the nominal pitch of `key`, e.g. during a glide.
+    ///
+    /// Returns the adapted pitches of all keys whose pitch has changed, including `key`.
+    pub fn note_pitch(&mut self, key: K, pitch: Pitch) -> Vec<(K, Pitch)> {
+        match self.notes.iter_mut().find(|note| note.key == key) {
+            Some(note) => note.nominal_pitch = pitch,
+            None => return Vec::new(),
+        }
+        self.adapt_including(key)
+    }
+
+    /// Returns the adapted pitch of `key` if `key` is registered.
+    pub fn adapted_pitch(&self, key: K) -> Option<Pitch> {
+        self.notes
+            .iter()
+            .find(|note| note.key == key)
+            .map(AdaptiveNote::adapted_pitch)
+    }
+
+    fn adapt_including(&mut self, key: K) -> Vec<(K, Pitch)> {
+        let mut retuned_keys = self.adapt();
+        if !retuned_keys
+            .iter()
+            .any(|&(retuned_key, _)| retuned_key == key)
+        {
+            retuned_keys.extend(self.adapted_pitch(key).map(|pitch| (key, pitch)));
+        }
+        retuned_keys
+    }
+
+    fn adapt(&mut self) -> Vec<(K, Pitch)> {
+        let deviations = self.solve();
+
+        let mut retuned_keys = Vec::new();
+        for (note, deviation) in self.notes.iter_mut().zip(deviations) {
+            if !deviation.deviation_from(note.deviation).is_negligible() {
+                note.deviation = deviation;
+                retuned_keys.push((note.key, note.adapted_pitch()));
+            }
+        }
+        retuned_keys
+    }
+
+    fn solve(&self) -> Vec<Ratio> {
+        let max_deviation_cents = self.options.max_deviation.as_cents().abs();
+
+        // Each consonance (i, j, c) requires the offset of note j minus the offset of note i to be c
+        let mut consonances = Vec::new();
+        for (i, lower) in self.notes.iter().enumerate() {
+            for (j, upper) in self.notes.iter().enumerate().skip(i + 1) {
+                let interval = Ratio::between_pitches(lower.nominal_pitch, upper.nominal_pitch);
+                let correction = -interval
+                    .nearest_fraction(self.options.odd_limit)
+                    .deviation
+                    .as_cents();
+                if correction.abs() <= max_deviation_cents {
+                    consonances.push((i, j, correction));
+                }
+            }
+        }
+
+        let previous_offsets: Vec<_> = self
+            .notes
+            .iter()
+            .map(|note| note.deviation.as_cents())
+            .collect();
+
+        // Gauss-Seidel iteration yields the least-squares solution for inconsistent consonances
+        let mut offsets = previous_offsets.clone();
+        for _ in 0..MAX_ITERATIONS {
+            let mut max_change: f64 = 0.0;
+            for k in 0..offsets.len() {
+                let (sum, count) = consonances
+                    .iter()
+                    .filter_map(|&(i, j, correction)| {
+                        if k == j {
+                            Some(offsets[i] + correction)
+                        } else if k == i {
+                            Some(offsets[j] - correction)
+                        } else {
+                            None
+                        }
+                    })
+                    .fold((0.0, 0), |(sum, count), offset| (sum + offset, count + 1));
+                if count > 0 {
+                    let new_offset = sum / f64::from(count);
+                    max_change = max_change.max((new_offset - offsets[k]).abs());
+                    offsets[k] = new_offset;
+                }
+            }
+            if max_change < CONVERGENCE_THRESHOLD_CENTS {
+                break;
+            }
+        }
+
+        for component in find_components(offsets.len(), &consonances) {
+            let shift = match self.options.strategy {
+                AdaptiveStrategy::Anchor => offsets[component[0]] - previous_offsets[component[0]],
+                AdaptiveStrategy::Drift => {
+                    let mut component_offsets: Vec<_> =
+                        component.iter().map(|&k| offsets[k]).collect();
+                    component_offsets.sort_by(f64::total_cmp);
+                    component_offsets[component_offsets.len() / 2]
+                }
+                AdaptiveStrategy::Center => {
+                    component.iter().map(|&k| offsets[k]).sum::<f64>() / component.len() as f64
+                }
+            };
+            for &k in &component {
+                offsets[k] -= shift;
+            }
+        }
+
+        offsets
+            .into_iter()
+            .map(|offset| {
+                Ratio::from_cents(offset.clamp(-max_deviation_cents, max_deviation_cents))
+            })
+            .collect()
+    }
+}
+
+/// Groups the note indices into sets of notes that are connected by consonances.
+///
+/// Each group is ordered by age.
+fn find_components(num_notes: usize, consonances: &[(usize, usize, f64)]) -> Vec<Vec<usize>> {
+    let mut component_ids: Vec<_> = (0..num_notes).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &(i, j, _) in consonances {
+            let min_id = component_ids[i].min(component_ids[j]);
+            if component_ids[i] != min_id || component_ids[j] != min_id {
+                component_ids[i] = min_id;
+                component_ids[j] = min_id;
+                changed = true;
+            }
+        }
+    }
+
+    let mut components = vec![Vec::new(); num_notes];
+    for (k, &component_id) in component_ids.iter().enumerate() {
+        components[component_id].push(k);
+    }
+    components.retain(|component| !component.is_empty());
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::note::NoteLetter;
+    use crate::pitch::Pitched;
+
+    use super::*;
+
+    fn create_model(strategy: AdaptiveStrategy) -> AdaptiveTuningModel<&'static str> {
+        AdaptiveTuningModel::new(AdaptiveOptions {
+            strategy,
+            odd_limit: 5,
+            max_deviation: Ratio::from_cents(30.0),
+        })
+    }
+
+    fn cents_from_nominal(
+        model: &AdaptiveTuningModel<&'static str>,
+        key: &'static str,
+        nominal: NoteLetter,
+    ) -> f64 {
+        Ratio::between_pitches(nominal.in_octave(4), model.adapted_pitch(key).unwrap()).as_cents()
+    }
+
+    #[test]
+    fn anchor_keeps_oldest_note() {
+        let mut model = create_model(AdaptiveStrategy::Anchor);
+
+        let retuned_keys = model.note_on("C", NoteLetter::C.in_octave(4).pitch());
+        assert_eq!(retuned_keys.len(), 1);
+        assert_approx_eq!(cents_from_nominal(&model, "C", NoteLetter::C), 0.0);
+
+        let retuned_keys = model.note_on("E", NoteLetter::E.in_octave(4).pitch());
+        assert_eq!(retuned_keys.len(), 1);
+        assert_eq!(retuned_keys[0].0, "E");
+        assert_approx_eq!(cents_from_nominal(&model, "C", NoteLetter::C), 0.0);
+        assert_approx_eq!(cents_from_nominal(&model, "E", NoteLetter::E), -13.686286);
+
+        model.note_on("G", NoteLetter::G.in_octave(4).pitch());
+        assert_approx_eq!(cents_from_nominal(&model, "C", NoteLetter::C), 0.0);
+        assert_approx_eq!(cents_from_nominal(&model, "E", NoteLetter::E), -13.686286);
+        assert_approx_eq!(cents_from_nominal(&model, "G", NoteLetter::G), 1.955001);
+
+        // E becomes the new anchor and keeps its pitch
+        assert!(model.note_off("C").is_empty());
+        assert!(model.note_off("G").is_empty());
+        assert_approx_eq!(cents_from_nominal(&model, "E", NoteLetter::E), -13.686286);
+    }
+
+    #[test]
+    fn center_zeroes_average_deviation() {
+        let mut model = create_model(AdaptiveStrategy::Center);
+
+        model.note_on("C", NoteLetter::C.in_octave(4).pitch());
+        model.note_on("E", NoteLetter::E.in_octave(4).pitch());
+        let retuned_keys = model.note_on("G", NoteLetter::G.in_octave(4).pitch());
+        assert_eq!(retuned_keys.len(), 3);
+
+        let c = cents_from_nominal(&model, "C", NoteLetter::C);
+        let e = cents_from_nominal(&model, "E", NoteLetter::E);
+        let g = cents_from_nominal(&model, "G", NoteLetter::G);
+        assert_approx_eq!(c + e + g, 0.0);
+        assert_approx_eq!(400.0 + e - c, 386.313714);
+        assert_approx_eq!(700.0 + g - c, 701.955001);
+
+        // The remaining note returns to its nominal pitch
+        model.note_off("C");
+        model.note_off("E");
+        assert_approx_eq!(cents_from_nominal(&model, "G", NoteLetter::G), 0.0);
+    }
+
+    #[test]
+    fn drift_minimizes_total_deviation() {
+        let mut model = create_model(AdaptiveStrategy::Drift);
+
+        model.note_on("C", NoteLetter::C.in_octave(4).pitch());
+        model.note_on("E", NoteLetter::E.in_octave(4).pitch());
+        model.note_on("G", NoteLetter::G.in_octave(4).pitch());
+
+        // The median note (C) stays at its nominal pitch
+        assert_approx_eq!(cents_from_nominal(&model, "C", NoteLetter::C), 0.0);
+        assert_approx_eq!(cents_from_nominal(&model, "E", NoteLetter::E), -13.686286);
+        assert_approx_eq!(cents_from_nominal(&model, "G", NoteLetter::G), 1.955001);
+    }
+
+    #[test]
+    fn dissonances_and_glides() {
+        let mut model = create_model(AdaptiveStrategy::Anchor);
+
+        model.note_on("C", NoteLetter::C.in_octave(4).pitch());
+
+        // The tritone is more than 30 cents away from any 5-limit ratio
+        model.note_on("F#", NoteLetter::F.in_octave(4).plus_semitones(1).pitch());
+        assert_approx_eq!(
+            Ratio::between_pitches(
+                NoteLetter::F.in_octave(4).plus_semitones(1),
+                model.adapted_pitch("F#").unwrap()
+            )
+            .as_cents(),
+            0.0
+        );
+
+        // Gliding F# to G yields a pure fifth
+        let retuned_keys = model.note_pitch("F#", NoteLetter::G.in_octave(4).pitch());
+        assert_eq!(retuned_keys.len(), 1);
+        assert_eq!(retuned_keys[0].0, "F#");
+        assert_approx_eq!(cents_from_nominal(&model, "F#", NoteLetter::G), 1.955001);
+
+        assert!(
+            model
+                .note_pitch("D", NoteLetter::D.in_octave(4).pitch())
+                .is_empty()
+        );
+    }
+}
@@ -23,6 +23,7 @@ pub struct AotTuner<K, S> {
     model: AotTuningModel<K>,
     synth: S,
     tuned: bool,
+    active_keys: HashMap<K, (usize, Note)>,
 }
 
 impl<K, S: TunableSynth> AotTuner<K, S> {
@@ -32,12 +33,15 @@ impl<K, S: TunableSynth> AotTuner<K, S> {
             model: AotTuningModel::empty(synth.num_channels()),
             synth,
             tuned: false,
+            active_keys: HashMap::new(),
         }
     }
 }
 
 impl<K: Copy + Eq + Hash, S: TunableSynth> AotTuner<K, S> {
     /// Apply the ahead-of-time `tuning` for the given `keys`.
+    ///
+    /// The tuning can be changed while notes are sounding. Those notes keep their channel and [`Note`] s.t. they can still be stopped or modified.
     pub fn set_tuning(
         &mut self,
         tuning: impl KeyboardMapping<K>,
@@ -46,6 +50,33 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> AotTuner<K, S> {
         let (model, channel_detunings) =
             AotTuningModel::apply_tuning(self.synth.group_by(), tuning, keys);
 
+        self.apply_model(model, channel_detunings)
+    }
+
+    /// Apply the ahead-of-time `tuning` for the given `keys` using as few channels as the `optimization` constraints allow.
+    ///
+    /// See [`AotTuningModel::apply_optimized_tuning`] for details.
+    pub fn set_optimized_tuning(
+        &mut self,
+        tuning: impl KeyboardMapping<K>,
+        keys: impl IntoIterator<Item = K>,
+        optimization: &AotOptimization,
+    ) -> Result<usize, S::Result> {
+        let (model, channel_detunings) = AotTuningModel::apply_optimized_tuning(
+            self.synth.group_by(),
+            tuning,
+            keys,
+            optimization,
+        );
+
+        self.apply_model(model, channel_detunings)
+    }
+
+    fn apply_model(
+        &mut self,
+        model: AotTuningModel<K>,
+        channel_detunings: Vec<ChannelDetuning<Group>>,
+    ) -> Result<usize, S::Result> {
         let num_detunings = channel_detunings.len();
         if num_detunings > self.synth.num_channels() {
             self.model = AotTuningModel::empty(self.synth.num_channels());
@@ -77,6 +108,7 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> AotTuner<K, S> {
     /// Starts a note with a pitch given by the currently loaded tuning.
     pub fn note_on(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
         if let Some((channel, started_note)) = self.model.get_channel_and_note_for_key(key) {
+            self.active_keys.insert(key, (channel, started_note));
             return self.synth.note_on(channel, started_note, attr);
         }
         S::Result::ok()
@@ -84,7 +116,11 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> AotTuner<K, S> {
 
     /// Stops the note of the given `key`.
     pub fn note_off(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
-        if let Some((channel, stopped_note)) = self.model.get_channel_and_note_for_key(key) {
+        if let Some((channel, stopped_note)) = self
+            .active_keys
+            .remove(&key)
+            .or_else(|| self.model.get_channel_and_note_for_key(key))
+        {
             return self.synth.note_off(channel, stopped_note, attr);
         }
         S::Result::ok()
@@ -92,12 +128,27 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> AotTuner<K, S> {
 
     /// Sets a polyphonic attribute for the note with the given `key`.
     pub fn note_attr(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
-        if let Some((channel, affected_note)) = self.model.get_channel_and_note_for_key(key) {
+        if let Some((channel, affected_note)) = self.find_channel_and_note(key) {
             return self.synth.note_attr(channel, affected_note, attr);
         }
         S::Result::ok()
     }
 
+    /// Sets a channel-global attribute on the channel of the note with the given `key` only.
+    pub fn note_channel_attr(&mut self, key: K, attr: S::GlobalAttr) -> S::Result {
+        if let Some((channel, _)) = self.find_channel_and_note(key) {
+            return self.synth.channel_attr(channel, attr);
+        }
+        S::Result::ok()
+    }
+
+    fn find_channel_and_note(&self, key: K) -> Option<(usize, Note)> {
+        self.active_keys
+            .get(&key)
+            .copied()
+            .or_else(|| self.model.get_channel_and_note_for_key(key))
+    }
+
     /// Sets a channel-global attribute.
     pub fn global_attr(&mut self, attr: S::GlobalAttr) -> S::Result {
         self.synth.global_attr(attr)
@@ -265,6 +316,67 @@ impl<K: Copy + Eq + Hash> AotTuningModel<K> {
         Self::apply_tuning_internal(|note| group_by.group(note), tuning, keys)
     }
 
+    /// Distributes the provided [`KeyboardMapping`] across as few channels as the given `optimization` constraints allow.
+    ///
+    /// In contrast to the fixed strategies above, this method treats the distribution as a graph-colouring problem:
+    /// Each distinct pitch is a vertex which can be played on any [`Note`] within [`AotOptimization::max_detuning`] and two vertices conflict when they require different detunings of the same [`Group`].
+    /// A DSatur heuristic provides an initial solution which is subsequently improved by a bounded exact search.
+    ///
+    /// The optimization pays off when keys are allowed to move to a neighboring note or when a small [`AotOptimization::tolerance`] is acceptable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::Note;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// # use tune::tuner::AotOptimization;
+    /// # use tune::tuner::AotTuningModel;
+    /// # use tune::tuner::GroupBy;
+    /// // Three pitches within 40c: All of them are closest to the same note letter
+    /// let scl = Scl::builder()
+    ///     .push_cents(20.0)
+    ///     .push_cents(40.0)
+    ///     .push_cents(1200.0)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let tuning = (scl, KbmRoot::from(Note::from_midi_number(62)).to_kbm());
+    /// // 3 keys per octave. Keep the pitches within the MIDI range
+    /// let keys = || (50..75).map(PianoKey::from_midi_number);
+    ///
+    /// let (model, _) = AotTuningModel::apply_octave_based_tuning(&tuning, keys());
+    /// assert_eq!(model.num_channels(), 3);
+    ///
+    /// // Allow keys to be moved to a neighboring note letter
+    /// let mut optimization = AotOptimization {
+    ///     max_detuning: Ratio::from_cents(90.0),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let (model, channel_tunings) =
+    ///     AotTuningModel::apply_optimized_tuning(GroupBy::NoteLetter, &tuning, keys(), &optimization);
+    /// assert_eq!(model.num_channels(), 2);
+    /// assert_eq!(model.num_channels(), channel_tunings.len());
+    ///
+    /// // Accept an error of 10c
+    /// optimization.tolerance = Ratio::from_cents(10.0);
+    ///
+    /// let (model, _) =
+    ///     AotTuningModel::apply_optimized_tuning(GroupBy::NoteLetter, &tuning, keys(), &optimization);
+    /// assert_eq!(model.num_channels(), 1);
+    /// ```
+    pub fn apply_optimized_tuning(
+        group_by: GroupBy,
+        tuning: impl KeyboardMapping<K>,
+        keys: impl IntoIterator<Item = K>,
+        optimization: &AotOptimization,
+    ) -> (Self, Vec<ChannelDetuning<Group>>) {
+        ChannelAllocator::new(|note| group_by.group(note), tuning, keys, optimization).allocate()
+    }
+
     fn apply_tuning_internal<N: Copy + Eq + Hash>(
         group: impl Fn(Note) -> N,
         tuning: impl KeyboardMapping<K>,
@@ -332,6 +444,323 @@ impl<K: Copy + Eq + Hash> AotTuningModel<K> {
     }
 }
 
+/// Constraints for [`AotTuningModel::apply_optimized_tuning`].
+#[derive(Copy, Clone, Debug)]
+pub struct AotOptimization {
+    /// Maximum amount by which the sounding pitch of a key may deviate from its target pitch.
+    ///
+    /// Keys whose detunings differ by at most twice this amount can share a tuning slot.
+    pub tolerance: Ratio,
+
+    /// Maximum amount by which a single note may be detuned.
+    ///
+    /// Values above 50c allow a key to be played on a neighboring note.
+    /// Make sure that the tuning method of your synthesizer supports the given range.
+    pub max_detuning: Ratio,
+}
+
+impl Default for AotOptimization {
+    fn default() -> Self {
+        Self {
+            tolerance: Ratio::default(),
+            max_detuning: Ratio::from_cents(50.0),
+        }
+    }
+}
+
+/// Upper limit of placements during the exact search. Keeps the runtime predictable for large keyboards.
+const SEARCH_BUDGET: usize = 10_000;
+
+/// Assigns the distinct pitches of a tuning to channel slots s.t. the number of channels is minimized.
+struct ChannelAllocator<K, N> {
+    vertices: Vec<Vertex<K, N>>,
+    tolerance: f64,
+    channels: Vec<HashMap<N, (f64, f64)>>,
+    placements: Vec<Option<(usize, usize)>>,
+    budget: usize,
+}
+
+/// A set of keys which can be played on the same detuned slots.
+struct Vertex<K, N> {
+    keys: Vec<(K, Vec<Note>)>,
+    slots: Vec<(N, f64)>,
+}
+
+struct Placement<N> {
+    vertex: usize,
+    channel: usize,
+    group: N,
+    previous_range: Option<(f64, f64)>,
+}
+
+impl<K: Copy + Eq + Hash, N: Copy + Eq + Hash> ChannelAllocator<K, N> {
+    fn new(
+        group: impl Fn(Note) -> N,
+        tuning: impl KeyboardMapping<K>,
+        keys: impl IntoIterator<Item = K>,
+        optimization: &AotOptimization,
+    ) -> Self {
+        let max_detuning = optimization.max_detuning.abs().as_cents();
+
+        let mut to_distribute: Vec<_> = keys
+            .into_iter()
+            .flat_map(|key| {
+                tuning
+                    .maybe_pitch_of(key)
+                    .map(|pitch| (key, pitch.find_in_tuning(())))
+            })
+            .collect();
+
+        to_distribute.sort_by(|a, b| a.1.deviation.total_cmp(&b.1.deviation));
+
+        let mut vertices = Vec::<Vertex<K, N>>::new();
+        let mut vertex_indices = HashMap::new();
+        for (key, approx) in to_distribute {
+            let candidates: Vec<_> = [0, -1, 1]
+                .into_iter()
+                .map(|offset| {
+                    (
+                        approx.approx_value.plus_semitones(offset),
+                        approx.deviation.as_cents() - 100.0 * f64::from(offset),
+                    )
+                })
+                .enumerate()
+                .filter(|&(index, (note, detuning))| {
+                    index == 0
+                        || detuning.abs() <= max_detuning && note.checked_midi_number().is_some()
+                })
+                .map(|(_, candidate)| candidate)
+                .collect();
+
+            let signature: Vec<_> = candidates
+                .iter()
+                .map(|&(note, detuning)| (group(note), (detuning * 1e4).round() as i64))
+                .collect();
+
+            let notes = candidates.iter().map(|&(note, _)| note).collect();
+            let vertex_index = *vertex_indices.entry(signature).or_insert_with(|| {
+                vertices.push(Vertex {
+                    keys: Vec::new(),
+                    slots: candidates
+                        .iter()
+                        .map(|&(note, detuning)| (group(note), detuning))
+                        .collect(),
+                });
+                vertices.len() - 1
+            });
+            vertices[vertex_index].keys.push((key, notes));
+        }
+
+        Self {
+            placements: vec![None; vertices.len()],
+            vertices,
+            tolerance: optimization.tolerance.abs().as_cents(),
+            channels: Vec::new(),
+            budget: 0,
+        }
+    }
+
+    fn allocate(mut self) -> (AotTuningModel<K>, Vec<ChannelDetuning<N>>) {
+        self.color_greedily(true);
+        let mut best_solution = (self.channels.clone(), self.placements.clone());
+
+        self.reset();
+        self.color_greedily(false);
+        if self.channels.len() < best_solution.0.len() {
+            best_solution = (self.channels.clone(), self.placements.clone());
+        }
+
+        self.budget = SEARCH_BUDGET;
+        let lower_bound = self.clique_size();
+        while best_solution.0.len() > lower_bound {
+            self.reset();
+            if !self.search(best_solution.0.len() - 1) {
+                break;
+            }
+            best_solution = (self.channels.clone(), self.placements.clone());
+        }
+        let (channels, placements) = best_solution;
+
+        let mut key_map = HashMap::new();
+        for (vertex, placement) in self.vertices.iter().zip(placements) {
+            let (channel, slot) = placement.unwrap();
+            for (key, notes) in &vertex.keys {
+                key_map.insert(*key, (channel, notes[slot]));
+            }
+        }
+
+        let channel_tunings: Vec<_> = channels
+            .into_iter()
+            .map(|ranges| ChannelDetuning {
+                tuning_map: ranges
+                    .into_iter()
+                    .map(|(group, (lower, upper))| {
+                        (group, Ratio::from_cents((lower + upper) / 2.0))
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        (
+            AotTuningModel {
+                key_map,
+                num_channels: channel_tunings.len(),
+            },
+            channel_tunings,
+        )
+    }
+
+    /// DSatur: Always place the vertex with the fewest remaining options, opening a new channel if necessary.
+    ///
+    /// Restricting the vertices to their `nearest_slot` guarantees a result which is at least as good as the one of the fixed strategies.
+    fn color_greedily(&mut self, nearest_slot: bool) {
+        while let Some((vertex, options)) = self.most_constrained_vertex(usize::MAX) {
+            let (channel, slot) = options
+                .into_iter()
+                .find(|&(_, slot)| !nearest_slot || slot == 0)
+                .unwrap();
+            self.place(vertex, channel, slot);
+        }
+    }
+
+    /// Exact backtracking search for a solution with at most `max_channels` channels.
+    fn search(&mut self, max_channels: usize) -> bool {
+        let Some((vertex, options)) = self.most_constrained_vertex(max_channels) else {
+            return true;
+        };
+        for (channel, slot) in options {
+            if self.budget == 0 {
+                return false;
+            }
+            self.budget -= 1;
+            let placement = self.place(vertex, channel, slot);
+            if self.search(max_channels) {
+                return true;
+            }
+            self.unplace(placement);
+        }
+        false
+    }
+
+    fn most_constrained_vertex(&self, max_channels: usize) -> Option<(usize, Vec<(usize, usize)>)> {
+        let mut result: Option<(usize, Vec<_>)> = None;
+        for (vertex, placement) in self.placements.iter().enumerate() {
+            if placement.is_some() {
+                continue;
+            }
+            let options = self.options(vertex, max_channels);
+            if result
+                .as_ref()
+                .is_none_or(|(_, best_options)| options.len() < best_options.len())
+            {
+                let is_stuck = options.is_empty();
+                result = Some((vertex, options));
+                if is_stuck {
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    /// Lists the feasible placements of `vertex`. Shared slots come first s.t. free slots are preserved for later vertices.
+    fn options(&self, vertex: usize, max_channels: usize) -> Vec<(usize, usize)> {
+        let slots = &self.vertices[vertex].slots;
+        let mut shared = Vec::new();
+        let mut free = Vec::new();
+        for (channel, ranges) in self.channels.iter().enumerate() {
+            for (slot, &(group, detuning)) in slots.iter().enumerate() {
+                match ranges.get(&group) {
+                    Some(&range) if self.fits(range, detuning) => shared.push((channel, slot)),
+                    Some(_) => {}
+                    None => free.push((channel, slot)),
+                }
+            }
+        }
+        if self.channels.len() < max_channels {
+            free.extend((0..slots.len()).map(|slot| (self.channels.len(), slot)));
+        }
+        shared.extend(free);
+        shared
+    }
+
+    fn fits(&self, (lower, upper): (f64, f64), detuning: f64) -> bool {
+        let spread = upper.max(detuning) - lower.min(detuning);
+        spread <= 2.0 * self.tolerance || Ratio::from_cents(spread).is_negligible()
+    }
+
+    fn place(&mut self, vertex: usize, channel: usize, slot: usize) -> Placement<N> {
+        if channel == self.channels.len() {
+            self.channels.push(HashMap::new());
+        }
+        let (group, detuning) = self.vertices[vertex].slots[slot];
+        let ranges = &mut self.channels[channel];
+        let previous_range = ranges.get(&group).copied();
+        let new_range = previous_range.map_or((detuning, detuning), |(lower, upper)| {
+            (lower.min(detuning), upper.max(detuning))
+        });
+        ranges.insert(group, new_range);
+        self.placements[vertex] = Some((channel, slot));
+
+        Placement {
+            vertex,
+            channel,
+            group,
+            previous_range,
+        }
+    }
+
+    fn unplace(&mut self, placement: Placement<N>) {
+        let ranges = &mut self.channels[placement.channel];
+        match placement.previous_range {
+            Some(range) => {
+                ranges.insert(placement.group, range);
+            }
+            None => {
+                ranges.remove(&placement.group);
+            }
+        }
+        if ranges.is_empty() && placement.channel + 1 == self.channels.len() {
+            self.channels.pop();
+        }
+        self.placements[placement.vertex] = None;
+    }
+
+    fn reset(&mut self) {
+        self.channels.clear();
+        self.placements.fill(None);
+    }
+
+    /// Size of a greedily found set of mutually conflicting vertices. This is a lower bound for the number of channels.
+    fn clique_size(&self) -> usize {
+        let conflicts = |a: &Vertex<K, N>, b: &Vertex<K, N>| {
+            a.slots.iter().all(|&(group_a, detuning_a)| {
+                b.slots.iter().all(|&(group_b, detuning_b)| {
+                    group_a == group_b && !self.fits((detuning_a, detuning_a), detuning_b)
+                })
+            })
+        };
+
+        let mut by_degree: Vec<_> = self
+            .vertices
+            .iter()
+            .map(|a| {
+                let degree = self.vertices.iter().filter(|&b| conflicts(a, b)).count();
+                (a, degree)
+            })
+            .collect();
+        by_degree.sort_by_key(|&(_, degree)| std::cmp::Reverse(degree));
+
+        let mut clique = Vec::<&Vertex<K, N>>::new();
+        for (vertex, _) in by_degree {
+            if clique.iter().all(|member| conflicts(member, vertex)) {
+                clique.push(vertex);
+            }
+        }
+        clique.len()
+    }
+}
+
 /// Defines the amount by which a group of notes is supposed to be detuned.
 #[derive(Clone, Debug)]
 pub struct ChannelDetuning<G> {
@@ -415,6 +844,7 @@ mod tests {
 
     use super::*;
     use crate::key::PianoKey;
+    use crate::note::PitchedNote;
     use crate::scala::Kbm;
     use crate::scala::KbmRoot;
     use crate::scala::Scl;
@@ -681,6 +1111,165 @@ mod tests {
         assert_eq!(tunings.len(), 13); // The number of channels is high since no note letter can be reused
     }
 
+    #[test]
+    fn apply_optimized_tuning_matches_fixed_strategies() {
+        for num_steps in [12, 16, 19, 22, 31, 41] {
+            let scl = Scl::builder()
+                .push_ratio(Ratio::octave().divided_into_equal_steps(num_steps))
+                .build()
+                .unwrap();
+
+            let tuning = (scl, KbmRoot::from(Note::from_midi_number(62)).to_kbm());
+
+            for group_by in [GroupBy::Note, GroupBy::NoteLetter, GroupBy::Channel] {
+                let (fixed_model, _) = AotTuningModel::apply_tuning(
+                    group_by,
+                    &tuning,
+                    (0..128).map(PianoKey::from_midi_number),
+                );
+                let (model, tunings) = AotTuningModel::apply_optimized_tuning(
+                    group_by,
+                    &tuning,
+                    (0..128).map(PianoKey::from_midi_number),
+                    &AotOptimization::default(),
+                );
+
+                assert_eq!(model.num_channels(), fixed_model.num_channels());
+                assert_max_pitch_error(group_by, &tuning, &model, &tunings, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn apply_optimized_tuning_with_tolerance() {
+        let scl = Scl::builder()
+            .push_ratio(Ratio::octave().divided_into_equal_steps(19))
+            .build()
+            .unwrap();
+
+        let tuning = (scl, KbmRoot::from(Note::from_midi_number(62)).to_kbm());
+
+        let expected_num_channels = [(0.0, 19), (2.0, 19), (5.0, 10), (10.0, 5), (25.0, 2)];
+        for (tolerance, expected_num_channels) in expected_num_channels {
+            let (model, tunings) = AotTuningModel::apply_optimized_tuning(
+                GroupBy::Channel,
+                &tuning,
+                (0..128).map(PianoKey::from_midi_number),
+                &AotOptimization {
+                    tolerance: Ratio::from_cents(tolerance),
+                    ..Default::default()
+                },
+            );
+
+            assert_eq!(model.num_channels(), expected_num_channels);
+            assert_max_pitch_error(GroupBy::Channel, &tuning, &model, &tunings, tolerance);
+        }
+    }
+
+    #[test]
+    fn apply_optimized_tuning_with_non_octave_scale() {
+        let scl = Scl::builder()
+            .push_ratio(Ratio::from_float(3.0).divided_into_equal_steps(13))
+            .build()
+            .unwrap();
+
+        let tuning = (scl, KbmRoot::from(Note::from_midi_number(62)).to_kbm());
+
+        let optimization = AotOptimization {
+            tolerance: Ratio::from_cents(5.0),
+            max_detuning: Ratio::from_cents(50.0),
+        };
+
+        let (model, tunings) = AotTuningModel::apply_optimized_tuning(
+            GroupBy::NoteLetter,
+            &tuning,
+            (0..128).map(PianoKey::from_midi_number),
+            &optimization,
+        );
+
+        assert_eq!(model.num_channels(), 4); // Instead of 13
+        assert_max_pitch_error(GroupBy::NoteLetter, &tuning, &model, &tunings, 5.0);
+    }
+
+    #[test]
+    fn apply_optimized_tuning_keeps_neighboring_notes_in_midi_range() {
+        let scl = Scl::builder()
+            .push_ratio(Ratio::octave().divided_into_equal_steps(12))
+            .build()
+            .unwrap();
+
+        // All keys are 40c sharp
+        let kbm_root =
+            KbmRoot::from(Note::from_midi_number(62).alter_pitch_by(Ratio::from_cents(40.0)));
+        let tuning = (scl, kbm_root.to_kbm());
+        let keys = || [0, 1, 126, 127].map(PianoKey::from_midi_number);
+
+        let optimization = AotOptimization {
+            tolerance: Ratio::default(),
+            max_detuning: Ratio::from_cents(150.0),
+        };
+
+        let allocator = ChannelAllocator::new(
+            |note| GroupBy::Note.group(note),
+            &tuning,
+            keys(),
+            &optimization,
+        );
+        let mut candidates: Vec<_> = allocator
+            .vertices
+            .iter()
+            .flat_map(|vertex| &vertex.keys)
+            .map(|(key, notes)| {
+                (
+                    key.midi_number(),
+                    notes
+                        .iter()
+                        .map(|note| note.midi_number())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        candidates.sort();
+
+        assert_eq!(
+            candidates,
+            [
+                (0, vec![0, 1]),
+                (1, vec![1, 0, 2]),
+                (126, vec![126, 125, 127]),
+                (127, vec![127, 126]),
+            ]
+        );
+
+        let (model, _) =
+            AotTuningModel::apply_optimized_tuning(GroupBy::Note, &tuning, keys(), &optimization);
+        for key in keys() {
+            let (_, note) = model.get_channel_and_note_for_key(key).unwrap();
+            assert!(note.checked_midi_number().is_some());
+        }
+    }
+
+    fn assert_max_pitch_error(
+        group_by: GroupBy,
+        tuning: &impl KeyboardMapping<PianoKey>,
+        model: &AotTuningModel<PianoKey>,
+        tunings: &[ChannelDetuning<Group>],
+        max_error_in_cents: f64,
+    ) {
+        for midi_number in 0..128 {
+            let key = PianoKey::from_midi_number(midi_number);
+            let (channel, note) = model.get_channel_and_note_for_key(key).unwrap();
+            let detuning = tunings[channel].tuning_map[&group_by.group(note)];
+
+            let error = Ratio::between_pitches(
+                tuning.maybe_pitch_of(key).unwrap(),
+                note.pitch() * detuning,
+            )
+            .as_cents();
+            assert!(error.abs() <= max_error_in_cents + 1e-6, "{error}");
+        }
+    }
+
     fn extract_channels_and_notes(tuner: &AotTuningModel<PianoKey>) -> (Vec<usize>, Vec<i32>) {
         (0..128)
             .map(|midi_number| {
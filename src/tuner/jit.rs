@@ -2,11 +2,15 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::hash::Hash;
+use std::time::Duration;
 
 use crate::note::Note;
 use crate::pitch::Pitch;
 use crate::pitch::Pitched;
 use crate::pitch::Ratio;
+use crate::tuner::AdaptiveOptions;
+use crate::tuner::AdaptiveTuningModel;
+use crate::tuner::DEFAULT_VELOCITY;
 use crate::tuner::Group;
 use crate::tuner::GroupBy;
 use crate::tuner::IsErr;
@@ -15,62 +19,133 @@ use crate::tuning::Approximation;
 
 pub struct JitTuner<K, S> {
     model: JitTuningModel<K>,
+    adaptive_model: Option<AdaptiveTuningModel<K>>,
     synth: S,
 }
 
 impl<K, S: TunableSynth> JitTuner<K, S> {
-    /// Starts a new [`JitTuner`] with the given `synth` and `pooling_mode`.
-    pub fn start(synth: S, pooling_mode: PoolingMode) -> Self {
+    /// Starts a new [`JitTuner`] with the given `synth` and channel allocation `policy`.
+    ///
+    /// A plain [`PoolingMode`] can be used as a `policy` with default values for all other settings.
+    pub fn start(synth: S, policy: impl Into<AllocationPolicy>) -> Self {
         Self {
-            model: JitTuningModel::new(synth.num_channels(), synth.group_by(), pooling_mode),
+            model: JitTuningModel::new(synth.num_channels(), synth.group_by(), policy),
+            adaptive_model: None,
             synth,
         }
     }
+
+    /// Starts a new [`JitTuner`] in adaptive just intonation mode.
+    ///
+    /// In this mode, the pitches of all sounding notes are continuously retuned toward pure ratios as described by the given `adaptive_options`.
+    pub fn start_adaptive(
+        synth: S,
+        policy: impl Into<AllocationPolicy>,
+        adaptive_options: AdaptiveOptions,
+    ) -> Self {
+        Self {
+            adaptive_model: Some(AdaptiveTuningModel::new(adaptive_options)),
+            ..Self::start(synth, policy)
+        }
+    }
 }
 
 impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
     /// Starts a note with the given `pitch`.
     ///
     /// `key` is used as identifier for currently sounding notes.
+    /// In adaptive mode, `pitch` is the nominal pitch of the note and other sounding notes might be retuned.
     pub fn note_on(&mut self, key: K, pitch: Pitch, attr: S::NoteAttr) -> S::Result {
-        match self.model.register_key(key, pitch) {
-            RegisterKeyResult::Accepted {
-                channel,
-                stopped_note,
-                started_note,
-                detuning,
-            } => {
-                if let Some(stopped_note) = stopped_note {
-                    let result = self.synth.note_off(channel, stopped_note, attr.clone());
-                    if result.is_err() {
-                        return result;
-                    }
-                }
-                let result = self
-                    .synth
-                    .notes_detune(channel, &[(started_note, detuning)]);
-                if result.is_err() {
-                    return result;
-                }
-                self.synth.note_on(channel, started_note, attr)
+        let velocity = self.synth.note_velocity(&attr);
+        let Some(RegisteredKey {
+            channel,
+            stopped,
+            started_note,
+            mut detuning,
+        }) = self.model.try_register_key(key, pitch, velocity)
+        else {
+            return S::Result::ok();
+        };
+
+        if let Some((_, stopped_note)) = stopped {
+            let result = self.synth.note_off(channel, stopped_note, attr.clone());
+            if result.is_err() {
+                return result;
+            }
+        }
+
+        // The adaptive model is only updated once the key has been accepted s.t. blocked keys cannot affect the sounding notes
+        if let Some(adaptive_model) = &mut self.adaptive_model {
+            let mut retuned_keys = Vec::new();
+            if let Some((stopped_key, _)) = stopped {
+                retuned_keys.extend(adaptive_model.note_off(stopped_key));
+            }
+            retuned_keys.extend(adaptive_model.note_on(key, pitch));
+            if let Some(adapted_pitch) = adaptive_model.adapted_pitch(key) {
+                detuning = Ratio::between_pitches(started_note.pitch(), adapted_pitch);
+            }
+            retuned_keys.retain(|&(retuned_key, _)| retuned_key != key);
+            let result = self.retune_keys(retuned_keys);
+            if result.is_err() {
+                return result;
             }
-            RegisterKeyResult::Rejected => S::Result::ok(),
         }
+
+        let result = self
+            .synth
+            .notes_detune(channel, &[(started_note, detuning)]);
+        if result.is_err() {
+            return result;
+        }
+        self.synth.note_on(channel, started_note, attr)
     }
 
     /// Stops the note of the given `key`.
     pub fn note_off(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
-        match self.model.deregister_key(key) {
+        let result = match self.model.deregister_key(key) {
             AccessKeyResult::Found {
                 channel,
                 found_note,
             } => self.synth.note_off(channel, found_note, attr),
             AccessKeyResult::NotFound => S::Result::ok(),
+        };
+        if result.is_err() {
+            return result;
+        }
+
+        match &mut self.adaptive_model {
+            Some(adaptive_model) => {
+                let retuned_keys = adaptive_model.note_off(key);
+                self.retune_keys(retuned_keys)
+            }
+            None => result,
         }
     }
 
     /// Updates the note of `key` with the given `pitch`.
+    ///
+    /// In adaptive mode, `pitch` is the new nominal pitch of the note and other sounding notes might be retuned.
     pub fn note_pitch(&mut self, key: K, pitch: Pitch) -> S::Result {
+        match &mut self.adaptive_model {
+            Some(adaptive_model) => {
+                let retuned_keys = adaptive_model.note_pitch(key, pitch);
+                self.retune_keys(retuned_keys)
+            }
+            None => self.detune_key(key, pitch),
+        }
+    }
+
+    fn retune_keys(&mut self, retuned_keys: Vec<(K, Pitch)>) -> S::Result {
+        for (key, pitch) in retuned_keys {
+            let result = self.detune_key(key, pitch);
+            if result.is_err() {
+                return result;
+            }
+        }
+        S::Result::ok()
+    }
+
+    fn detune_key(&mut self, key: K, pitch: Pitch) -> S::Result {
         match self.model.access_key(key) {
             AccessKeyResult::Found {
                 channel,
@@ -94,6 +169,23 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
         }
     }
 
+    /// Sets a channel-global attribute on the channel of the note with the given `key` only.
+    ///
+    /// This is useful for per-note expression when the [`TunableSynth`] allocates one channel per note, e.g. in MPE mode.
+    pub fn note_channel_attr(&mut self, key: K, attr: S::GlobalAttr) -> S::Result {
+        match self.model.access_key(key) {
+            AccessKeyResult::Found { channel, .. } => self.synth.channel_attr(channel, attr),
+            AccessKeyResult::NotFound => S::Result::ok(),
+        }
+    }
+
+    /// Updates the current time which is measured from an arbitrary but fixed point in time.
+    ///
+    /// The time is used to determine whether the [`AllocationPolicy::release_time`] of a released channel has passed.
+    pub fn set_time(&mut self, time: Duration) {
+        self.model.set_time(time);
+    }
+
     /// Sets a channel-global attribute.
     pub fn global_attr(&mut self, attr: S::GlobalAttr) -> S::Result {
         self.synth.global_attr(attr)
@@ -117,25 +209,65 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
 pub struct JitTuningModel<K> {
     num_channels: usize,
     group_by: GroupBy,
-    pooling_mode: PoolingMode,
+    policy: AllocationPolicy,
     pools: HashMap<Group, JitPool<K, usize, Note>>,
     groups: HashMap<K, Group>,
+    now: Duration,
 }
 
 impl<K> JitTuningModel<K> {
-    pub fn new(num_channels: usize, group_by: GroupBy, pooling_mode: PoolingMode) -> Self {
+    pub fn new(
+        num_channels: usize,
+        group_by: GroupBy,
+        policy: impl Into<AllocationPolicy>,
+    ) -> Self {
         Self {
             num_channels,
             group_by,
-            pooling_mode,
+            policy: policy.into(),
             pools: HashMap::new(),
             groups: HashMap::new(),
+            now: Duration::ZERO,
+        }
+    }
+
+    pub fn set_time(&mut self, time: Duration) {
+        self.now = time;
+        for pool in self.pools.values_mut() {
+            pool.now = time;
         }
     }
 }
 
 impl<K: Copy + Eq + Hash> JitTuningModel<K> {
     pub fn register_key(&mut self, key: K, pitch: Pitch) -> RegisterKeyResult {
+        self.register_key_with_velocity(key, pitch, DEFAULT_VELOCITY)
+    }
+
+    /// Like [`JitTuningModel::register_key`] but takes the `velocity` into account when [`StealingPolicy::Quietest`] is used.
+    pub fn register_key_with_velocity(
+        &mut self,
+        key: K,
+        pitch: Pitch,
+        velocity: u8,
+    ) -> RegisterKeyResult {
+        match self.try_register_key(key, pitch, velocity) {
+            Some(RegisteredKey {
+                channel,
+                stopped,
+                started_note,
+                detuning,
+            }) => RegisterKeyResult::Accepted {
+                channel,
+                stopped_note: stopped.map(|(_, note)| note),
+                started_note,
+                detuning,
+            },
+            None => RegisterKeyResult::Rejected,
+        }
+    }
+
+    fn try_register_key(&mut self, key: K, pitch: Pitch, velocity: u8) -> Option<RegisteredKey<K>> {
         let Approximation {
             approx_value,
             deviation,
@@ -143,26 +275,23 @@ impl<K: Copy + Eq + Hash> JitTuningModel<K> {
 
         let group = self.group_by.group(approx_value);
 
-        let pool = self
-            .pools
-            .entry(group)
-            .or_insert_with(|| JitPool::new(self.pooling_mode, 0..self.num_channels));
+        let pool = self.pools.entry(group).or_insert_with(|| {
+            let mut pool = JitPool::new(self.policy, 0..self.num_channels);
+            pool.now = self.now;
+            pool
+        });
 
-        match pool.key_pressed(key, approx_value) {
-            Some((channel, stopped)) => {
-                self.groups.insert(key, group);
-                if let Some(stopped) = stopped {
-                    self.groups.remove(&stopped.0);
-                }
-                RegisterKeyResult::Accepted {
-                    stopped_note: stopped.map(|(_, note)| note),
-                    started_note: approx_value,
-                    channel,
-                    detuning: deviation,
-                }
-            }
-            None => RegisterKeyResult::Rejected,
+        let (channel, stopped) = pool.key_pressed(key, approx_value, velocity)?;
+        self.groups.insert(key, group);
+        if let Some((stopped_key, _)) = stopped {
+            self.groups.remove(&stopped_key);
         }
+        Some(RegisteredKey {
+            channel,
+            stopped,
+            started_note: approx_value,
+            detuning: deviation,
+        })
     }
 
     pub fn deregister_key(&mut self, key: K) -> AccessKeyResult {
@@ -218,6 +347,14 @@ pub enum RegisterKeyResult {
     Rejected,
 }
 
+/// Like [`RegisterKeyResult::Accepted`] but also reports the key of the stopped note.
+struct RegisteredKey<K> {
+    channel: usize,
+    stopped: Option<(K, Note)>,
+    started_note: Note,
+    detuning: Ratio,
+}
+
 /// Reports the channel and [`Note`] of a registered key.
 ///
 /// If the key is not registered [`AccessKeyResult::NotFound`] is returned.
@@ -227,11 +364,14 @@ pub enum AccessKeyResult {
 }
 
 struct JitPool<K, C, N> {
-    mode: PoolingMode,
-    free: VecDeque<C>,
+    policy: AllocationPolicy,
+    channels: Vec<C>,
+    free: VecDeque<(C, Option<Duration>)>, // Release time of the last note on the channel
+    next_channel: usize,
     tuned: BTreeMap<u64, K>, // Insertion order is conserved
-    active: HashMap<K, (u64, C, N)>,
+    active: HashMap<K, (u64, C, N, u8)>,
     curr_usage_id: u64,
+    now: Duration,
 }
 
 /// Defines what to do when the channel pool is full and a new key cannot be registered.
@@ -242,32 +382,123 @@ pub enum PoolingMode {
     Ignore,
 }
 
-impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
-    fn new(mode: PoolingMode, channels: impl IntoIterator<Item = C>) -> Self {
+/// Configures how the [`JitTuner`] allocates channels to keys.
+#[derive(Clone, Copy, Debug)]
+pub struct AllocationPolicy {
+    /// Defines what to do when no channel is available for a new key.
+    pub pooling_mode: PoolingMode,
+
+    /// Defines which sounding key is stopped or ignored when no channel is available.
+    pub stealing: StealingPolicy,
+
+    /// Defines which sounding keys are never stolen.
+    pub protection: NoteProtection,
+
+    /// Defines the order in which free channels are assigned to new keys.
+    pub channel_order: ChannelOrder,
+
+    /// Time during which a released channel is only reused if no other free channel is available.
+    ///
+    /// This prevents the release tail of a stopped note from being affected by the tuning of a new note.
+    pub release_time: Duration,
+}
+
+/// Creates an [`AllocationPolicy`] with the given [`PoolingMode`] and the most basic settings.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use tune::tuner::AllocationPolicy;
+/// # use tune::tuner::ChannelOrder;
+/// # use tune::tuner::NoteProtection;
+/// # use tune::tuner::PoolingMode;
+/// # use tune::tuner::StealingPolicy;
+/// let policy = AllocationPolicy::from(PoolingMode::Stop);
+///
+/// assert_eq!(policy.stealing, StealingPolicy::Oldest);
+/// assert_eq!(policy.protection, NoteProtection::None);
+/// assert_eq!(policy.channel_order, ChannelOrder::LeastRecentlyUsed);
+/// assert_eq!(policy.release_time, Duration::ZERO);
+/// ```
+impl From<PoolingMode> for AllocationPolicy {
+    fn from(pooling_mode: PoolingMode) -> Self {
         Self {
-            mode,
-            free: VecDeque::from_iter(channels),
+            pooling_mode,
+            stealing: StealingPolicy::Oldest,
+            protection: NoteProtection::None,
+            channel_order: ChannelOrder::LeastRecentlyUsed,
+            release_time: Duration::ZERO,
+        }
+    }
+}
+
+/// Defines which sounding key is stolen when no channel is available.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StealingPolicy {
+    /// Steal the key that has been pressed first.
+    Oldest,
+
+    /// Steal the key with the lowest velocity. Keys with the same velocity are stolen in the order they have been pressed.
+    Quietest,
+}
+
+/// Defines which sounding keys are protected from being stolen.
+///
+/// The lowest (highest) note is only protected if the new note is higher (lower) than it.
+///
+/// If all sounding notes are protected, there is no key to steal and the new key is not registered, even with [`PoolingMode::Stop`] or [`PoolingMode::Ignore`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NoteProtection {
+    None,
+    Lowest,
+    Highest,
+    LowestAndHighest,
+}
+
+/// Defines the order in which free channels are assigned to new keys.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChannelOrder {
+    /// Assign the channel that has been free for the longest time.
+    LeastRecentlyUsed,
+
+    /// Assign the channels in cyclic order.
+    RoundRobin,
+}
+
+impl<K: Copy + Eq + Hash, C: Copy + Eq, N: Copy + Ord> JitPool<K, C, N> {
+    fn new(policy: impl Into<AllocationPolicy>, channels: impl IntoIterator<Item = C>) -> Self {
+        let channels: Vec<_> = channels.into_iter().collect();
+        Self {
+            policy: policy.into(),
+            free: channels.iter().map(|&channel| (channel, None)).collect(),
+            channels,
+            next_channel: 0,
             tuned: BTreeMap::new(),
             active: HashMap::new(),
             curr_usage_id: 0,
+            now: Duration::ZERO,
         }
     }
 
-    fn key_pressed(&mut self, key: K, note: N) -> Option<(C, Option<(K, N)>)> {
-        if let Some(channel) = self.try_insert(key, note) {
+    fn key_pressed(&mut self, key: K, note: N, velocity: u8) -> Option<(C, Option<(K, N)>)> {
+        if let Some(channel) = self.try_insert(key, note, velocity) {
             return Some((channel, None));
         }
 
-        match self.mode {
+        match self.policy.pooling_mode {
             PoolingMode::Block => None,
-            PoolingMode::Stop => self.find_old_key().map(|(channel, old_key, old_location)| {
-                self.key_released(old_key);
-                self.try_insert(key, note).unwrap();
-                (channel, Some((old_key, old_location)))
-            }),
-            PoolingMode::Ignore => self.find_old_key().map(|(channel, old_key, _)| {
+            PoolingMode::Stop => {
+                self.find_stealable_key(note)
+                    .map(|(channel, old_key, old_location)| {
+                        self.key_released(old_key);
+                        self.try_insert(key, note, velocity).unwrap();
+                        (channel, Some((old_key, old_location)))
+                    })
+            }
+            PoolingMode::Ignore => self.find_stealable_key(note).map(|(channel, old_key, _)| {
                 self.weaken_key(old_key);
-                self.try_insert(key, note).unwrap();
+                self.try_insert(key, note, velocity).unwrap();
                 (channel, None)
             }),
         }
@@ -276,7 +507,7 @@ impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
     fn key_released(&mut self, key: K) -> Option<(C, N)> {
         self.active
             .remove(&key)
-            .map(|(usage_id, freed_channel, location)| {
+            .map(|(usage_id, freed_channel, location, _)| {
                 self.free_key(usage_id, freed_channel);
                 (freed_channel, location)
             })
@@ -285,53 +516,141 @@ impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
     fn find_key(&self, key: K) -> Option<(C, N)> {
         self.active
             .get(&key)
-            .map(|&(_, channel, location)| (channel, location))
+            .map(|&(_, channel, location, _)| (channel, location))
     }
 
     fn active_keys(&self) -> impl Iterator<Item = K> + '_ {
         self.active.keys().copied()
     }
 
-    fn try_insert(&mut self, key: K, note: N) -> Option<C> {
-        let free_channel = self.free.pop_front()?;
+    fn try_insert(&mut self, key: K, note: N, velocity: u8) -> Option<C> {
+        let free_index = self.find_free_channel()?;
+        let (free_channel, _) = self.free.remove(free_index)?;
+        if let Some(position) = self.position_of(free_channel) {
+            self.next_channel = (position + 1) % self.channels.len();
+        }
         self.tuned.insert(self.curr_usage_id, key);
         self.active
-            .insert(key, (self.curr_usage_id, free_channel, note));
+            .insert(key, (self.curr_usage_id, free_channel, note, velocity));
         self.curr_usage_id += 1;
         Some(free_channel)
     }
 
-    fn find_old_key(&mut self) -> Option<(C, K, N)> {
-        let key = *self.tuned.values().next()?;
-        let &(_, channel, location) = self.active.get(&key)?;
-        Some((channel, key, location))
+    fn find_free_channel(&self) -> Option<usize> {
+        let release_finished = |released: Option<Duration>| {
+            released.is_none_or(|released| {
+                self.now.saturating_sub(released) >= self.policy.release_time
+            })
+        };
+
+        let finished_channel = match self.policy.channel_order {
+            ChannelOrder::LeastRecentlyUsed => self
+                .free
+                .iter()
+                .position(|&(_, released)| release_finished(released)),
+            ChannelOrder::RoundRobin => self
+                .free
+                .iter()
+                .enumerate()
+                .filter(|&(_, &(_, released))| release_finished(released))
+                .min_by_key(|&(_, &(channel, _))| {
+                    self.position_of(channel).map(|position| {
+                        (position + self.channels.len() - self.next_channel) % self.channels.len()
+                    })
+                })
+                .map(|(index, _)| index),
+        };
+
+        // Fall back to the channel that has been released the longest time ago
+        finished_channel.or_else(|| {
+            self.free
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &(_, released))| released)
+                .map(|(index, _)| index)
+        })
+    }
+
+    fn position_of(&self, channel: C) -> Option<usize> {
+        self.channels.iter().position(|&c| c == channel)
+    }
+
+    fn find_stealable_key(&self, new_note: N) -> Option<(C, K, N)> {
+        let tuned_keys: Vec<_> = self
+            .tuned
+            .values()
+            .filter_map(|key| {
+                self.active
+                    .get(key)
+                    .map(|&(_, channel, location, velocity)| (channel, *key, location, velocity))
+            })
+            .collect();
+
+        let lowest = tuned_keys
+            .iter()
+            .map(|&(_, _, location, _)| location)
+            .min()
+            .filter(|&lowest| lowest < new_note);
+        let highest = tuned_keys
+            .iter()
+            .map(|&(_, _, location, _)| location)
+            .max()
+            .filter(|&highest| highest > new_note);
+
+        let is_protected = |location: N| match self.policy.protection {
+            NoteProtection::None => false,
+            NoteProtection::Lowest => lowest == Some(location),
+            NoteProtection::Highest => highest == Some(location),
+            NoteProtection::LowestAndHighest => {
+                lowest == Some(location) || highest == Some(location)
+            }
+        };
+
+        let mut candidates = tuned_keys
+            .into_iter()
+            .filter(|&(_, _, location, _)| !is_protected(location));
+
+        match self.policy.stealing {
+            StealingPolicy::Oldest => candidates.next(),
+            StealingPolicy::Quietest => candidates.min_by_key(|&(_, _, _, velocity)| velocity),
+        }
+        .map(|(channel, key, location, _)| (channel, key, location))
     }
 
     fn weaken_key(&mut self, key: K) {
-        if let Some(&(usage_id, freed_channel, _)) = self.active.get(&key) {
+        if let Some(&(usage_id, freed_channel, _, _)) = self.active.get(&key) {
             self.free_key(usage_id, freed_channel);
         }
     }
 
     fn free_key(&mut self, usage_id: u64, freed_channel: C) {
         if self.tuned.remove(&usage_id).is_some() {
-            self.free.push_back(freed_channel);
+            self.free.push_back((freed_channel, Some(self.now)));
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::note::NoteLetter;
+    use crate::tuner::AdaptiveStrategy;
+    use crate::tuner::MidiTarget;
+    use crate::tuner::MidiTunerMessage;
+    use crate::tuner::MidiTunerMessageHandler;
+    use crate::tuner::TunableMidi;
+
     use super::*;
 
     #[test]
     fn pooling_mode_block() {
         let mut pool = JitPool::new(PoolingMode::Block, 0..3);
 
-        assert_eq!(pool.key_pressed("keyA", "locA"), Some((0, None)));
-        assert_eq!(pool.key_pressed("keyB", "locB"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyC", "locC"), Some((2, None)));
-        assert_eq!(pool.key_pressed("keyD", "locD"), None);
+        assert_eq!(pool.key_pressed("keyA", "locA", 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", "locB", 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyC", "locC", 100), Some((2, None)));
+        assert_eq!(pool.key_pressed("keyD", "locD", 100), None);
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), Some((1, "locB")));
@@ -339,8 +658,8 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), None);
 
         assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyE", "locE"), None);
+        assert_eq!(pool.key_pressed("keyD", "locD", 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyE", "locE", 100), None);
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), None);
@@ -365,11 +684,11 @@ mod tests {
     fn pooling_mode_stop() {
         let mut pool = JitPool::new(PoolingMode::Stop, 0..3);
 
-        assert_eq!(pool.key_pressed("keyA", "locA"), Some((0, None)));
-        assert_eq!(pool.key_pressed("keyB", "locB"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyC", "locC"), Some((2, None)));
+        assert_eq!(pool.key_pressed("keyA", "locA", 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", "locB", 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyC", "locC", 100), Some((2, None)));
         assert_eq!(
-            pool.key_pressed("keyD", "locD"),
+            pool.key_pressed("keyD", "locD", 100),
             Some((0, Some(("keyA", "locA"))))
         );
 
@@ -379,9 +698,9 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), Some((0, "locD")));
 
         assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyD", "locD", 100), Some((1, None)));
         assert_eq!(
-            pool.key_pressed("keyE", "locE"),
+            pool.key_pressed("keyE", "locE", 100),
             Some((2, Some(("keyC", "locC"))))
         );
 
@@ -408,10 +727,10 @@ mod tests {
     fn pooling_mode_ignore() {
         let mut pool = JitPool::new(PoolingMode::Ignore, 0..3);
 
-        assert_eq!(pool.key_pressed("keyA", "locA"), Some((0, None)));
-        assert_eq!(pool.key_pressed("keyB", "locB"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyC", "locC"), Some((2, None)));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyA", "locA", 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", "locB", 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyC", "locC", 100), Some((2, None)));
+        assert_eq!(pool.key_pressed("keyD", "locD", 100), Some((0, None)));
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), Some((1, "locB")));
@@ -419,8 +738,8 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), Some((0, "locD")));
 
         assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyE", "locE"), Some((2, None)));
+        assert_eq!(pool.key_pressed("keyD", "locD", 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyE", "locE", 100), Some((2, None)));
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), None);
@@ -440,4 +759,254 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), None);
         assert_eq!(pool.find_key("keyE"), None);
     }
+
+    #[test]
+    fn stealing_policy_quietest() {
+        let mut pool = JitPool::new(
+            AllocationPolicy {
+                stealing: StealingPolicy::Quietest,
+                ..PoolingMode::Stop.into()
+            },
+            0..3,
+        );
+
+        assert_eq!(pool.key_pressed("keyA", 60, 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", 64, 30), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyC", 67, 80), Some((2, None)));
+        assert_eq!(
+            pool.key_pressed("keyD", 72, 90),
+            Some((1, Some(("keyB", 64))))
+        );
+        assert_eq!(
+            pool.key_pressed("keyE", 74, 90),
+            Some((2, Some(("keyC", 67))))
+        );
+        assert_eq!(
+            pool.key_pressed("keyF", 76, 90),
+            Some((1, Some(("keyD", 72))))
+        );
+    }
+
+    #[test]
+    fn note_protection() {
+        let mut pool = JitPool::new(
+            AllocationPolicy {
+                protection: NoteProtection::Lowest,
+                ..PoolingMode::Stop.into()
+            },
+            0..2,
+        );
+
+        assert_eq!(pool.key_pressed("keyA", 36, 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", 60, 100), Some((1, None)));
+        assert_eq!(
+            pool.key_pressed("keyC", 62, 100),
+            Some((1, Some(("keyB", 60))))
+        );
+        // The new note is lower than the protected note
+        assert_eq!(
+            pool.key_pressed("keyD", 30, 100),
+            Some((0, Some(("keyA", 36))))
+        );
+
+        let mut pool = JitPool::new(
+            AllocationPolicy {
+                protection: NoteProtection::LowestAndHighest,
+                ..PoolingMode::Block.into()
+            },
+            0..2,
+        );
+
+        assert_eq!(pool.key_pressed("keyA", 36, 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", 84, 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyC", 60, 100), None);
+
+        // All sounding notes are protected: The new note is dropped instead of stealing a key
+        let mut pool = JitPool::new(
+            AllocationPolicy {
+                protection: NoteProtection::LowestAndHighest,
+                ..PoolingMode::Stop.into()
+            },
+            0..2,
+        );
+
+        assert_eq!(pool.key_pressed("keyA", 36, 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", 84, 100), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyC", 60, 100), None);
+        assert_eq!(pool.find_key("keyA"), Some((0, 36)));
+        assert_eq!(pool.find_key("keyB"), Some((1, 84)));
+        assert_eq!(pool.find_key("keyC"), None);
+
+        // The highest note is no longer protected if the new note is above it
+        assert_eq!(
+            pool.key_pressed("keyD", 96, 100),
+            Some((1, Some(("keyB", 84))))
+        );
+    }
+
+    #[test]
+    fn round_robin_with_release_time() {
+        let mut pool = JitPool::new(
+            AllocationPolicy {
+                channel_order: ChannelOrder::RoundRobin,
+                release_time: Duration::from_millis(100),
+                ..PoolingMode::Stop.into()
+            },
+            0..3,
+        );
+
+        assert_eq!(pool.key_pressed("keyA", 60, 100), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyB", 62, 100), Some((1, None)));
+        assert_eq!(pool.key_released("keyA"), Some((0, 60)));
+        assert_eq!(pool.key_pressed("keyC", 64, 100), Some((2, None)));
+
+        pool.now = Duration::from_millis(10);
+        assert_eq!(pool.key_released("keyB"), Some((1, 62)));
+
+        // Both free channels are in their release phase: Use the one released first
+        pool.now = Duration::from_millis(50);
+        assert_eq!(pool.key_pressed("keyD", 65, 100), Some((0, None)));
+
+        // The release phase of channel 1 is over
+        pool.now = Duration::from_millis(200);
+        assert_eq!(pool.key_released("keyD"), Some((0, 65)));
+        assert_eq!(pool.key_pressed("keyE", 67, 100), Some((1, None)));
+    }
+
+    #[test]
+    fn adaptive_tuning_with_pooling_mode_block() {
+        let mut tuner = adaptive_tuner(PoolingMode::Block);
+
+        tuner.note_on("C", NoteLetter::C.in_octave(4).pitch(), 100);
+        tuner.note_on("E", NoteLetter::E.in_octave(4).pitch(), 100);
+        let pitch_c = adapted_pitch(&tuner, "C").unwrap();
+        let pitch_e = adapted_pitch(&tuner, "E").unwrap();
+        assert_approx_eq!(
+            Ratio::between_pitches(pitch_c, pitch_e).as_float(),
+            5.0 / 4.0
+        );
+
+        // The blocked key does not affect the sounding notes
+        tuner.note_on("A", NoteLetter::A.in_octave(4).pitch(), 100);
+        assert!(adapted_pitch(&tuner, "A").is_none());
+        assert_eq!(adapted_pitch(&tuner, "C"), Some(pitch_c));
+        assert_eq!(adapted_pitch(&tuner, "E"), Some(pitch_e));
+    }
+
+    #[test]
+    fn adaptive_tuning_with_pooling_mode_stop() {
+        let mut tuner = adaptive_tuner(PoolingMode::Stop);
+
+        tuner.note_on("C", NoteLetter::C.in_octave(4).pitch(), 100);
+        tuner.note_on("E", NoteLetter::E.in_octave(4).pitch(), 100);
+
+        // The stolen key no longer affects the sounding notes
+        tuner.note_on("G", NoteLetter::G.in_octave(4).pitch(), 100);
+        assert!(matches!(
+            tuner.model.access_key("C"),
+            AccessKeyResult::NotFound
+        ));
+        assert!(adapted_pitch(&tuner, "C").is_none());
+        let pitch_e = adapted_pitch(&tuner, "E").unwrap();
+        let pitch_g = adapted_pitch(&tuner, "G").unwrap();
+        assert_approx_eq!(
+            Ratio::between_pitches(pitch_e, pitch_g).as_float(),
+            6.0 / 5.0
+        );
+    }
+
+    fn adaptive_tuner(
+        policy: PoolingMode,
+    ) -> JitTuner<&'static str, TunableMidi<impl MidiTunerMessageHandler>> {
+        let target = MidiTarget {
+            handler: |_: MidiTunerMessage| {},
+            channels: vec![0, 1],
+        };
+        JitTuner::start_adaptive(
+            TunableMidi::channel_fine_tuning(target),
+            policy,
+            AdaptiveOptions {
+                strategy: AdaptiveStrategy::Anchor,
+                odd_limit: 5,
+                max_deviation: Ratio::from_cents(30.0),
+            },
+        )
+    }
+
+    fn adapted_pitch<S>(tuner: &JitTuner<&'static str, S>, key: &'static str) -> Option<Pitch> {
+        tuner.adaptive_model.as_ref()?.adapted_pitch(key)
+    }
+
+    #[test]
+    fn clash_statistics() {
+        fn count_steals(policy: impl Into<AllocationPolicy>) -> (usize, usize, u32) {
+            const BASS_KEY: u32 = 0;
+
+            let mut pool = JitPool::new(policy, 0..4);
+            let mut random_state = 12345u32;
+            let mut next_random = || {
+                random_state = random_state.wrapping_mul(1103515245).wrapping_add(12345);
+                (random_state >> 16) & 0x7fff
+            };
+
+            let mut num_steals = 0;
+            let mut num_bass_steals = 0;
+            let mut total_stolen_velocity = 0;
+
+            pool.key_pressed(BASS_KEY, 36, 100);
+            let mut velocities = HashMap::new();
+            let mut sounding_keys = VecDeque::new();
+            for key in 1..=200 {
+                let note = 48 + next_random() % 24;
+                let velocity = 20 + (next_random() % 100) as u8;
+                velocities.insert(key, velocity);
+                if let Some((_, Some((stolen_key, _)))) = pool.key_pressed(key, note, velocity) {
+                    num_steals += 1;
+                    if stolen_key == BASS_KEY {
+                        num_bass_steals += 1;
+                    } else {
+                        total_stolen_velocity += u32::from(velocities[&stolen_key]);
+                    }
+                }
+                sounding_keys.push_back(key);
+                let max_sounding_keys = 2 + next_random() as usize % 3;
+                while sounding_keys.len() > max_sounding_keys {
+                    pool.key_released(sounding_keys.pop_front().unwrap());
+                }
+            }
+
+            (num_steals, num_bass_steals, total_stolen_velocity)
+        }
+
+        fn average_stolen_velocity(
+            (num_steals, num_bass_steals, total_stolen_velocity): (usize, usize, u32),
+        ) -> f64 {
+            f64::from(total_stolen_velocity) / (num_steals - num_bass_steals) as f64
+        }
+
+        let oldest = count_steals(PoolingMode::Stop);
+        let quietest = count_steals(AllocationPolicy {
+            stealing: StealingPolicy::Quietest,
+            ..PoolingMode::Stop.into()
+        });
+        let oldest_protected = count_steals(AllocationPolicy {
+            protection: NoteProtection::Lowest,
+            ..PoolingMode::Stop.into()
+        });
+        let quietest_protected = count_steals(AllocationPolicy {
+            stealing: StealingPolicy::Quietest,
+            protection: NoteProtection::Lowest,
+            ..PoolingMode::Stop.into()
+        });
+
+        // The bass note is the oldest note and, therefore, stolen unless protected
+        assert_eq!(oldest.1, 1);
+        assert_eq!(oldest_protected.1, 0);
+        assert_eq!(quietest_protected.1, 0);
+
+        assert!(average_stolen_velocity(quietest) < average_stolen_velocity(oldest));
+        assert!(
+            average_stolen_velocity(quietest_protected) < average_stolen_velocity(oldest_protected)
+        );
+    }
 }
@@ -1,7 +1,13 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Display;
 
+use crate::midi;
 use crate::midi::ChannelMessage;
 use crate::midi::ChannelMessageType;
+use crate::midi::Midi2ChannelMessageType;
+use crate::midi::NoteAttribute;
+use crate::midi::UmpPacket;
 use crate::mts;
 use crate::mts::ScaleOctaveTuning;
 use crate::mts::ScaleOctaveTuningFormat;
@@ -16,6 +22,9 @@ use crate::pitch::Ratio;
 use crate::tuner::GroupBy;
 use crate::tuner::TunableSynth;
 
+/// The pitch-bend range of a MIDI channel, in semitones, as long as no Pitch Bend Sensitivity is sent.
+const DEFAULT_PITCH_BEND_RANGE: u8 = 2;
+
 pub struct TunableMidi<H> {
     midi_target: MidiTarget<H>,
     midi_tuning_creator: MidiTuningCreator,
@@ -62,10 +71,92 @@ impl<H> TunableMidi<H> {
         }
     }
 
+    /// Creates a synth which tunes each channel via Pitch Bend messages assuming the default pitch-bend range of 2 semitones.
+    ///
+    /// No Pitch Bend Sensitivity is sent. Use [`TunableMidi::pitch_bend_with_range`] to set up a different range.
     pub fn pitch_bend(midi_target: MidiTarget<H>) -> Self {
         Self {
             midi_target,
-            midi_tuning_creator: MidiTuningCreator::PitchBend,
+            midi_tuning_creator: MidiTuningCreator::PitchBend {
+                pitch_bend_range: Ratio::from_semitones(DEFAULT_PITCH_BEND_RANGE),
+                channel_bends: HashMap::new(),
+            },
+        }
+    }
+
+    /// Creates a MIDI 2.0 synth which attaches the pitch of each note as a 7.9 fixed-point attribute to its Note On message.
+    ///
+    /// All messages are emitted as MIDI 2.0 Universal MIDI Packets. Pitch updates of already sounding notes are not supported.
+    pub fn midi2_pitch_attribute(midi_target: MidiTarget<H>) -> Self {
+        Self {
+            midi_target,
+            midi_tuning_creator: MidiTuningCreator::Midi2PitchAttribute {
+                note_pitches: HashMap::new(),
+            },
+        }
+    }
+
+    /// Creates a MIDI 2.0 synth which tunes each note via the Registered Per-Note Controller #3 (Pitch 7.25).
+    ///
+    /// All messages are emitted as MIDI 2.0 Universal MIDI Packets.
+    pub fn midi2_per_note_pitch(midi_target: MidiTarget<H>) -> Self {
+        Self {
+            midi_target,
+            midi_tuning_creator: MidiTuningCreator::Midi2PerNotePitch,
+        }
+    }
+}
+
+impl<H: MidiTunerMessageHandler> TunableMidi<H> {
+    /// Creates a synth which tunes each channel via Pitch Bend messages.
+    ///
+    /// On creation, the Pitch Bend Sensitivity (`pitch_bend_range`) of all channels is sent immediately.
+    ///
+    /// Incoming pitch bend, set via [`TunableSynth::channel_attr`] or [`TunableSynth::global_attr`], is combined with the detuning of the channel.
+    /// Detunings exceeding `pitch_bend_range` are clamped and reported via [`MidiTunerMessageHandler::handle_warning`].
+    pub fn pitch_bend_with_range(mut midi_target: MidiTarget<H>, pitch_bend_range: Ratio) -> Self {
+        midi_target.send_pitch_bend_sensitivity(pitch_bend_range);
+
+        Self {
+            midi_target,
+            midi_tuning_creator: MidiTuningCreator::PitchBend {
+                pitch_bend_range,
+                channel_bends: HashMap::new(),
+            },
+        }
+    }
+
+    /// Creates a MIDI Polyphonic Expression (MPE) synth which plays each note on its own member channel.
+    ///
+    /// The channels of `midi_target` are used as member channels of the zone managed by `manager_channel` (0 for a lower zone, 15 for an upper zone).
+    /// On creation, the MPE Configuration Message and the Pitch Bend Sensitivity (`pitch_bend_range`) of all member channels are sent immediately.
+    ///
+    /// Per-note pitch bend, set via [`TunableSynth::channel_attr`], is combined with the detuning of the note and reset when the note is released.
+    pub fn mpe(
+        mut midi_target: MidiTarget<H>,
+        manager_channel: u8,
+        pitch_bend_range: Ratio,
+    ) -> Self {
+        let num_member_channels = u8::try_from(midi_target.channels.len()).unwrap_or(u8::MAX);
+
+        for channel_message in mts::mpe_configuration(manager_channel, num_member_channels)
+            .into_iter()
+            .flatten()
+        {
+            midi_target
+                .handler
+                .handle(MidiTunerMessage::new(channel_message));
+        }
+
+        midi_target.send_pitch_bend_sensitivity(pitch_bend_range);
+
+        Self {
+            midi_target,
+            midi_tuning_creator: MidiTuningCreator::Mpe {
+                manager_channel,
+                pitch_bend_range,
+                channel_bends: HashMap::new(),
+            },
         }
     }
 }
@@ -89,20 +180,37 @@ impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi<H> {
     }
 
     fn note_on(&mut self, channel: usize, started_note: Note, velocity: u8) {
-        if let Some(started_note) = started_note.checked_midi_number() {
-            self.midi_target.send(
-                ChannelMessageType::NoteOn {
-                    key: started_note,
-                    velocity,
-                },
-                channel,
-            );
+        if let Some(key) = started_note.checked_midi_number() {
+            let note_on = ChannelMessageType::NoteOn { key, velocity };
+
+            if let MidiTuningCreator::Midi2PitchAttribute { note_pitches } =
+                &self.midi_tuning_creator
+            {
+                let detuning = note_pitches
+                    .get(&(channel, started_note))
+                    .copied()
+                    .unwrap_or_default();
+                let pitch = midi::pitch_7_9(f64::from(key) + detuning.as_semitones());
+                let note_on = Midi2ChannelMessageType::from_midi1(note_on)
+                    .with_attribute(NoteAttribute::Pitch7_9(pitch));
+                self.midi_target.send_midi2(note_on, channel);
+            } else {
+                self.send(note_on, channel);
+            }
         }
     }
 
     fn note_off(&mut self, channel: usize, stopped_note: Note, velocity: u8) {
+        if let MidiTuningCreator::Mpe { channel_bends, .. } = &mut self.midi_tuning_creator {
+            // The member channel is released s.t. the per-note pitch bend must not leak into the next note allocated to it.
+            // The bend is not sent immediately to leave the release phase of the stopped note unaffected.
+            if let Some(channel_bend) = channel_bends.get_mut(&channel) {
+                channel_bend.1 = 0;
+            }
+        }
+
         if let Some(stopped_note) = stopped_note.checked_midi_number() {
-            self.midi_target.send(
+            self.send(
                 ChannelMessageType::NoteOff {
                     key: stopped_note,
                     velocity,
@@ -113,8 +221,12 @@ impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi<H> {
     }
 
     fn note_attr(&mut self, channel: usize, affected_note: Note, pressure: u8) {
-        if let Some(affected_note) = affected_note.checked_midi_number() {
-            self.midi_target.send(
+        if let MidiTuningCreator::Mpe { .. } = self.midi_tuning_creator {
+            // MPE member channels only carry a single note which is why Channel Pressure is used.
+            self.midi_target
+                .send(ChannelMessageType::ChannelPressure { pressure }, channel);
+        } else if let Some(affected_note) = affected_note.checked_midi_number() {
+            self.send(
                 ChannelMessageType::PolyphonicKeyPressure {
                     key: affected_note,
                     pressure,
@@ -124,13 +236,52 @@ impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi<H> {
         }
     }
 
+    fn channel_attr(&mut self, channel: usize, message_type: ChannelMessageType) {
+        match (self.midi_tuning_creator.channel_bends(), message_type) {
+            (
+                Some((pitch_bend_range, channel_bends)),
+                ChannelMessageType::PitchBendChange { value },
+            ) => {
+                let channel_bend = channel_bends.entry(channel).or_default();
+                channel_bend.1 = value;
+                let pitch_bend = pitch_bend_message(*channel_bend, pitch_bend_range);
+                self.midi_target.send(pitch_bend, channel);
+            }
+            _ => self.send(message_type, channel),
+        }
+    }
+
     fn global_attr(&mut self, message_type: ChannelMessageType) {
+        if let MidiTuningCreator::Mpe {
+            manager_channel, ..
+        } = self.midi_tuning_creator
+        {
+            // Zone-wide messages are sent to the manager channel.
+            self.midi_target
+                .handler
+                .handle_channel_message(message_type, manager_channel);
+            return;
+        }
+
         for channel in 0..self.num_channels() {
-            if self.midi_tuning_creator.allow_pitch_bend()
-                || !matches!(message_type, ChannelMessageType::PitchBendChange { .. })
-            {
-                self.midi_target.send(message_type, channel);
-            }
+            self.channel_attr(channel, message_type);
+        }
+    }
+
+    fn note_velocity(&self, velocity: &u8) -> u8 {
+        *velocity
+    }
+}
+
+impl<H: MidiTunerMessageHandler> TunableMidi<H> {
+    fn send(&mut self, message_type: ChannelMessageType, tuner_channel: usize) {
+        if self.midi_tuning_creator.is_midi2() {
+            self.midi_target.send_midi2(
+                Midi2ChannelMessageType::from_midi1(message_type),
+                tuner_channel,
+            );
+        } else {
+            self.midi_target.send(message_type, tuner_channel);
         }
     }
 }
@@ -146,6 +297,28 @@ impl<H: MidiTunerMessageHandler> MidiTarget<H> {
             .handle_channel_message(message, self.midi_channel(tuner_channel));
     }
 
+    fn send_midi2(&mut self, message_type: Midi2ChannelMessageType, tuner_channel: usize) {
+        if let Some(message) = message_type.in_channel(self.midi_channel(tuner_channel)) {
+            self.handler
+                .handle(MidiTunerMessage::new(UmpPacket::Midi2ChannelVoice {
+                    group: 0,
+                    message,
+                }));
+        }
+    }
+
+    fn send_pitch_bend_sensitivity(&mut self, pitch_bend_range: Ratio) {
+        for tuner_channel in 0..self.channels.len() {
+            let midi_channel = self.midi_channel(tuner_channel);
+            for channel_message in mts::pitch_bend_sensitivity(midi_channel, pitch_bend_range)
+                .into_iter()
+                .flatten()
+            {
+                self.handler.handle(MidiTunerMessage::new(channel_message));
+            }
+        }
+    }
+
     fn midi_channel(&self, tuner_channel: usize) -> u8 {
         self.channels[tuner_channel]
     }
@@ -155,6 +328,9 @@ impl<H: MidiTunerMessageHandler> MidiTarget<H> {
     }
 }
 
+/// The detuning and incoming pitch bend of each channel.
+type ChannelBends = HashMap<usize, (Ratio, i16)>;
+
 enum MidiTuningCreator {
     SingleNoteTuningChange {
         device_id: u8,
@@ -168,7 +344,19 @@ enum MidiTuningCreator {
         octave_tunings: HashMap<usize, ScaleOctaveTuning>,
     },
     ChannelFineTuning,
-    PitchBend,
+    PitchBend {
+        pitch_bend_range: Ratio,
+        channel_bends: ChannelBends,
+    },
+    Mpe {
+        manager_channel: u8,
+        pitch_bend_range: Ratio,
+        channel_bends: ChannelBends,
+    },
+    Midi2PitchAttribute {
+        note_pitches: HashMap<(usize, Note), Ratio>,
+    },
+    Midi2PerNotePitch,
 }
 
 impl MidiTuningCreator {
@@ -250,14 +438,54 @@ impl MidiTuningCreator {
                     }
                 }
             }
-            MidiTuningCreator::PitchBend => {
+            MidiTuningCreator::PitchBend {
+                pitch_bend_range,
+                channel_bends,
+            }
+            | MidiTuningCreator::Mpe {
+                pitch_bend_range,
+                channel_bends,
+                ..
+            } => {
                 for &(_, detuning) in detuned_notes {
-                    let channel_message = pitch_bend_message(detuning)
-                        .in_channel(midi_channel)
-                        .unwrap();
-                    target
-                        .handler
-                        .handle(MidiTunerMessage::new(channel_message));
+                    if detuning.as_semitones().abs() > pitch_bend_range.as_semitones() {
+                        target.handler.handle_warning(
+                            MidiTunerWarning::DetuningOutOfPitchBendRange {
+                                channel: midi_channel,
+                                detuning,
+                                pitch_bend_range: *pitch_bend_range,
+                            },
+                        );
+                    }
+
+                    let channel_bend = channel_bends.entry(tuner_channel).or_default();
+                    channel_bend.0 = detuning;
+                    target.send(
+                        pitch_bend_message(*channel_bend, *pitch_bend_range),
+                        tuner_channel,
+                    );
+                }
+            }
+            MidiTuningCreator::Midi2PitchAttribute { note_pitches } => {
+                // Only the latest detunings of a channel are relevant for upcoming Note On messages.
+                // Replacing them keeps the table bounded while held and replayed notes keep their pitch.
+                note_pitches.retain(|&(channel, _), _| channel != tuner_channel);
+                for &(note, detuning) in detuned_notes {
+                    note_pitches.insert((tuner_channel, note), detuning);
+                }
+            }
+            MidiTuningCreator::Midi2PerNotePitch => {
+                for &(note, detuning) in detuned_notes {
+                    if let Some(key) = note.checked_midi_number() {
+                        target.send_midi2(
+                            Midi2ChannelMessageType::RegisteredPerNoteController {
+                                key,
+                                index: midi::PITCH_7_25_CONTROLLER,
+                                value: midi::pitch_7_25(f64::from(key) + detuning.as_semitones()),
+                            },
+                            tuner_channel,
+                        );
+                    }
                 }
             }
         }
@@ -265,20 +493,38 @@ impl MidiTuningCreator {
 
     fn group_by(&self) -> GroupBy {
         match self {
-            MidiTuningCreator::SingleNoteTuningChange { .. } => GroupBy::Note,
+            MidiTuningCreator::SingleNoteTuningChange { .. }
+            | MidiTuningCreator::Midi2PitchAttribute { .. }
+            | MidiTuningCreator::Midi2PerNotePitch => GroupBy::Note,
             MidiTuningCreator::ScaleOctaveTuning { .. } => GroupBy::NoteLetter,
-            MidiTuningCreator::ChannelFineTuning | MidiTuningCreator::PitchBend => GroupBy::Channel,
+            MidiTuningCreator::ChannelFineTuning
+            | MidiTuningCreator::PitchBend { .. }
+            | MidiTuningCreator::Mpe { .. } => GroupBy::Channel,
         }
     }
 
-    fn allow_pitch_bend(&self) -> bool {
+    /// Returns the state required to combine incoming pitch bend with the detuning of a channel.
+    fn channel_bends(&mut self) -> Option<(Ratio, &mut ChannelBends)> {
         match self {
-            MidiTuningCreator::SingleNoteTuningChange { .. }
-            | MidiTuningCreator::ScaleOctaveTuning { .. }
-            | MidiTuningCreator::ChannelFineTuning => true,
-            MidiTuningCreator::PitchBend => false,
+            MidiTuningCreator::PitchBend {
+                pitch_bend_range,
+                channel_bends,
+            }
+            | MidiTuningCreator::Mpe {
+                pitch_bend_range,
+                channel_bends,
+                ..
+            } => Some((*pitch_bend_range, channel_bends)),
+            _ => None,
         }
     }
+
+    fn is_midi2(&self) -> bool {
+        matches!(
+            self,
+            MidiTuningCreator::Midi2PitchAttribute { .. } | MidiTuningCreator::Midi2PerNotePitch
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -306,6 +552,14 @@ impl MidiTunerMessage {
                     receiver(sysex_bytes);
                 }
             }
+            MidiTunerMessageVariant::Ump(packet) => {
+                let ump_bytes: Vec<_> = packet
+                    .to_words()
+                    .into_iter()
+                    .flat_map(u32::to_be_bytes)
+                    .collect();
+                receiver(&ump_bytes);
+            }
         }
     }
 }
@@ -315,6 +569,13 @@ enum MidiTunerMessageVariant {
     Channel(ChannelMessage),
     ScaleOctaveTuning(ScaleOctaveTuningMessage),
     SingleNoteTuningChange(SingleNoteTuningChangeMessage),
+    Ump(UmpPacket),
+}
+
+impl From<UmpPacket> for MidiTunerMessageVariant {
+    fn from(v: UmpPacket) -> Self {
+        Self::Ump(v)
+    }
 }
 
 impl From<ChannelMessage> for MidiTunerMessageVariant {
@@ -335,9 +596,42 @@ impl From<SingleNoteTuningChangeMessage> for MidiTunerMessageVariant {
     }
 }
 
+/// A problem that prevented a tuning from being applied exactly.
+#[derive(Clone, Debug)]
+pub enum MidiTunerWarning {
+    /// The detuning of a channel exceeds the pitch-bend range and has been clamped.
+    DetuningOutOfPitchBendRange {
+        channel: u8,
+        detuning: Ratio,
+        pitch_bend_range: Ratio,
+    },
+}
+
+impl Display for MidiTunerWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MidiTunerWarning::DetuningOutOfPitchBendRange {
+                channel,
+                detuning,
+                pitch_bend_range,
+            } => write!(
+                f,
+                "Detuning of {:+.1}c on channel {channel} exceeds the pitch-bend range of {:.1}c",
+                detuning.as_cents(),
+                pitch_bend_range.as_cents()
+            ),
+        }
+    }
+}
+
 pub trait MidiTunerMessageHandler {
     fn handle(&mut self, message: MidiTunerMessage);
 
+    /// Reports a tuning that could not be applied exactly. Warnings are ignored by default.
+    fn handle_warning(&mut self, warning: MidiTunerWarning) {
+        let _ = warning;
+    }
+
     fn handle_channel_message(&mut self, message_type: ChannelMessageType, channel: u8) {
         if let Some(message) = message_type.in_channel(channel) {
             self.handle(MidiTunerMessage::new(message));
@@ -351,10 +645,295 @@ impl<F: FnMut(MidiTunerMessage)> MidiTunerMessageHandler for F {
     }
 }
 
-fn pitch_bend_message(detuning: Ratio) -> ChannelMessageType {
+fn pitch_bend_message(
+    (detuning, input_bend): (Ratio, i16),
+    pitch_bend_range: Ratio,
+) -> ChannelMessageType {
+    let total_bend =
+        detuning.as_semitones() / pitch_bend_range.as_semitones() + f64::from(input_bend) / 8192.0;
+
     ChannelMessageType::PitchBendChange {
-        value: ((detuning.as_semitones() / 2.0 * 8192.0) as i16)
-            .max(-8192)
-            .min(8192),
+        value: ((total_bend * 8192.0) as i16).clamp(-8192, 8191),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::key::PianoKey;
+    use crate::pitch::Pitch;
+    use crate::scala::KbmRoot;
+    use crate::scala::Scl;
+    use crate::tuner::AotTuner;
+    use crate::tuner::JitTuner;
+    use crate::tuner::PoolingMode;
+
+    #[test]
+    fn pitch_bend_with_custom_range() {
+        struct Handler {
+            messages: mpsc::Sender<Vec<u8>>,
+            warnings: mpsc::Sender<String>,
+        }
+
+        impl MidiTunerMessageHandler for Handler {
+            fn handle(&mut self, message: MidiTunerMessage) {
+                message.send_to(|bytes| self.messages.send(bytes.to_vec()).unwrap())
+            }
+
+            fn handle_warning(&mut self, warning: MidiTunerWarning) {
+                self.warnings.send(warning.to_string()).unwrap()
+            }
+        }
+
+        let (messages, recv_messages) = mpsc::channel();
+        let (warnings, recv_warnings) = mpsc::channel();
+        let target = || MidiTarget {
+            handler: Handler {
+                messages: messages.clone(),
+                warnings: warnings.clone(),
+            },
+            channels: vec![0],
+        };
+
+        let synth = TunableMidi::pitch_bend_with_range(target(), Ratio::from_semitones(1));
+        assert_eq!(
+            recv_messages.try_iter().collect::<Vec<_>>(),
+            [
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 0],
+                [0xb0, 0x06, 1],
+                [0xb0, 0x26, 0],
+            ]
+        );
+
+        let mut tuner = JitTuner::start(synth, PoolingMode::Stop);
+
+        tuner.note_on("A", Pitch::from_hz(440.0) * Ratio::from_cents(30.0), 100);
+        assert_eq!(
+            recv_messages.try_iter().collect::<Vec<_>>(),
+            [[0xe0, 25, 83], [0x90, 69, 100]]
+        );
+
+        // Incoming pitch bend is combined with the detuning
+        tuner.global_attr(ChannelMessageType::PitchBendChange { value: 4096 });
+        assert_eq!(
+            recv_messages.try_iter().collect::<Vec<_>>(),
+            [[0xe0, 25, 115]]
+        );
+        assert_eq!(recv_warnings.try_iter().count(), 0);
+
+        // Detunings beyond the pitch-bend range are clamped
+        let mut synth = TunableMidi::pitch_bend_with_range(target(), Ratio::from_cents(20.0));
+        recv_messages.try_iter().count();
+
+        synth.notes_detune(0, &[(Note::from_midi_number(69), Ratio::from_cents(30.0))]);
+        assert_eq!(
+            recv_messages.try_iter().collect::<Vec<_>>(),
+            [[0xe0, 127, 127]]
+        );
+        assert_eq!(
+            recv_warnings.try_iter().collect::<Vec<_>>(),
+            ["Detuning of +30.0c on channel 0 exceeds the pitch-bend range of 20.0c"]
+        );
+    }
+
+    #[test]
+    fn aot_tuning_change_with_held_notes() {
+        let (send, recv) = mpsc::channel();
+        let target = MidiTarget {
+            handler: move |message: MidiTunerMessage| {
+                message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+            },
+            channels: vec![0, 1],
+        };
+
+        let mut tuner = AotTuner::start(TunableMidi::channel_fine_tuning(target));
+
+        let scl = Scl::builder().push_cents(1200.0).build().unwrap();
+        let tuning = |cents| {
+            let kbm_root = KbmRoot {
+                ref_key: PianoKey::from_midi_number(60),
+                ref_pitch: Note::from_midi_number(60).pitch() * Ratio::from_cents(cents),
+                root_offset: 0,
+            };
+            (scl.clone(), kbm_root.to_kbm())
+        };
+        let key = PianoKey::from_midi_number(60);
+
+        tuner.set_tuning(tuning(0.0), [key]).unwrap();
+        tuner.note_on(key, 100);
+        recv.try_iter().count();
+
+        // The held note is stopped on its original channel
+        tuner.set_tuning(tuning(-150.0), [key]).unwrap();
+        tuner.note_off(key, 0);
+        tuner.note_on(key, 100);
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                vec![0xb0, 0x65, 0],
+                vec![0xb0, 0x64, 1],
+                vec![0xb0, 0x06, 96],
+                vec![0xb0, 0x26, 0],
+                vec![0x80, 60, 0],
+                vec![0x90, 58, 100],
+            ]
+        );
+    }
+
+    #[test]
+    fn midi2_per_note_pitch() {
+        let (send, recv) = mpsc::channel();
+        let target = || {
+            let send = send.clone();
+            MidiTarget {
+                handler: move |message: MidiTunerMessage| {
+                    message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+                },
+                channels: vec![0],
+            }
+        };
+
+        let pitch = Pitch::from_hz(440.0) * Ratio::from_cents(25.0);
+
+        let mut tuner = JitTuner::start(
+            TunableMidi::midi2_pitch_attribute(target()),
+            PoolingMode::Stop,
+        );
+        tuner.note_on("A", pitch, 100);
+        tuner.note_off("A", 0);
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                vec![0x40, 0x90, 69, 3, 0xc9, 0x24, 0x8a, 0x80],
+                vec![0x40, 0x80, 69, 0, 0x00, 0x00, 0x00, 0x00],
+            ]
+        );
+
+        let mut tuner = JitTuner::start(
+            TunableMidi::midi2_per_note_pitch(target()),
+            PoolingMode::Stop,
+        );
+        tuner.note_on("A", pitch, 100);
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                vec![0x40, 0x00, 69, 3, 0x8a, 0x80, 0x00, 0x00],
+                vec![0x40, 0x90, 69, 0, 0xc9, 0x24, 0x00, 0x00],
+            ]
+        );
+    }
+
+    #[test]
+    fn midi2_pitch_attribute_does_not_accumulate_note_pitches() {
+        let (send, recv) = mpsc::channel();
+        let target = || {
+            let send = send.clone();
+            MidiTarget {
+                handler: move |message: MidiTunerMessage| {
+                    message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+                },
+                channels: vec![0, 1],
+            }
+        };
+        let num_note_pitches = |synth: TunableMidi<_>| match synth.midi_tuning_creator {
+            MidiTuningCreator::Midi2PitchAttribute { note_pitches } => note_pitches.len(),
+            _ => unreachable!(),
+        };
+
+        let mut tuner = JitTuner::start(
+            TunableMidi::midi2_pitch_attribute(target()),
+            PoolingMode::Stop,
+        );
+        for midi_number in 0..128 {
+            let pitch = Note::from_midi_number(midi_number).pitch() * Ratio::from_cents(25.0);
+            tuner.note_on(midi_number, pitch, 100);
+            tuner.note_off(midi_number, 0);
+        }
+        assert_eq!(num_note_pitches(tuner.stop()), 1);
+
+        // Replayed notes keep their pitch
+        let scl = Scl::builder().push_cents(125.0).build().unwrap();
+        let kbm = KbmRoot::from(Note::from_midi_number(69)).to_kbm();
+        let keys = || (60..72).map(PianoKey::from_midi_number);
+
+        let mut tuner = AotTuner::start(TunableMidi::midi2_pitch_attribute(target()));
+        tuner.set_tuning((scl, kbm), keys()).unwrap();
+        recv.try_iter().count();
+
+        let key = PianoKey::from_midi_number(70);
+        tuner.note_on(key, 100);
+        tuner.note_off(key, 0);
+        tuner.note_on(key, 100);
+        let messages: Vec<_> = recv.try_iter().collect();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0], messages[2]);
+        assert_eq!(num_note_pitches(tuner.stop()), 12);
+    }
+
+    #[test]
+    fn mpe_per_note_expression() {
+        let (send, recv) = mpsc::channel();
+        let target = MidiTarget {
+            handler: move |message: MidiTunerMessage| {
+                message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+            },
+            channels: vec![1, 2],
+        };
+
+        let synth = TunableMidi::mpe(target, 0, Ratio::from_semitones(48));
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 6],
+                [0xb0, 0x06, 2],
+                [0xb1, 0x65, 0],
+                [0xb1, 0x64, 0],
+                [0xb1, 0x06, 48],
+                [0xb1, 0x26, 0],
+                [0xb2, 0x65, 0],
+                [0xb2, 0x64, 0],
+                [0xb2, 0x06, 48],
+                [0xb2, 0x26, 0],
+            ]
+        );
+
+        let mut tuner = JitTuner::start(synth, PoolingMode::Stop);
+
+        let pitch = Pitch::from_hz(440.0) * Ratio::from_cents(30.0);
+        tuner.note_on("A", pitch, 100);
+        tuner.note_on("B", Pitch::from_hz(440.0), 100);
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                [0xe1, 51, 64],
+                [0x91, 69, 100],
+                [0xe2, 0, 64],
+                [0x92, 69, 100]
+            ]
+        );
+
+        // Per-note pitch bend is combined with the detuning
+        tuner.note_channel_attr("A", ChannelMessageType::PitchBendChange { value: 4096 });
+        tuner.note_attr("A", 50);
+        tuner.global_attr(ChannelMessageType::ControlChange {
+            controller: 64,
+            value: 127,
+        });
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [[0xe1, 51, 96], [0xd1, 50, 0], [0xb0, 64, 127]]
+        );
+
+        // The per-note pitch bend is reset when the member channel is reused
+        tuner.note_off("A", 0);
+        tuner.note_on("C", pitch, 100);
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [[0x81, 69, 0], [0xe1, 51, 64], [0x91, 69, 100]]
+        );
     }
 }
@@ -1,11 +1,16 @@
 //! Generate tuning maps to enhance the capabilities of synthesizers with limited tuning support.
 
+mod adaptive;
 mod aot;
 mod jit;
 mod midi;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 use std::hash::Hash;
 
+pub use adaptive::*;
 pub use aot::*;
 pub use jit::*;
 pub use midi::*;
@@ -14,6 +19,9 @@ use crate::note::Note;
 use crate::note::NoteLetter;
 use crate::pitch::Ratio;
 
+/// The velocity assumed for notes whose velocity is unknown.
+const DEFAULT_VELOCITY: u8 = 64;
+
 /// A note-based multichannel synthesizer with note detuning capabilities.
 pub trait TunableSynth {
     type Result: IsErr;
@@ -43,7 +51,22 @@ pub trait TunableSynth {
         attr: Self::NoteAttr,
     ) -> Self::Result;
 
+    /// Applies `attr` to a single channel only, e.g. to forward per-note expression.
+    ///
+    /// By default, the attribute is ignored.
+    fn channel_attr(&mut self, _channel: usize, _attr: Self::GlobalAttr) -> Self::Result {
+        Self::Result::ok()
+    }
+
     fn global_attr(&mut self, attr: Self::GlobalAttr) -> Self::Result;
+
+    /// Returns the velocity of a note started with the given `attr`.
+    ///
+    /// The velocity is used to determine which note to steal when a synthesizer runs out of channels.
+    /// By default, all notes are considered to have the same velocity (64).
+    fn note_velocity(&self, _attr: &Self::NoteAttr) -> u8 {
+        DEFAULT_VELOCITY
+    }
 }
 
 pub trait IsErr {
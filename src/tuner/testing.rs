@@ -0,0 +1,769 @@
+//! A virtual MIDI synthesizer for testing tuners.
+//!
+//! The [`VirtualSynth`] interprets the raw MIDI output of a [`TunableMidi`](crate::tuner::TunableMidi) synth in the same way a real synthesizer would.
+//! It tracks the actual sounding pitch of every voice and reports clashes, wrong pitches and stuck notes.
+//!
+//! This module requires the `testing` feature.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::midi;
+use crate::midi::ChannelMessage;
+use crate::midi::ChannelMessageType;
+use crate::midi::ControlChangeDecoder;
+use crate::midi::ControlEventType;
+use crate::midi::Midi2ChannelMessage;
+use crate::midi::Midi2ChannelMessageType;
+use crate::midi::MidiMessage;
+use crate::midi::MidiStreamParser;
+use crate::midi::NoteAttribute;
+use crate::midi::ParameterChange;
+use crate::midi::ParameterNumber;
+use crate::midi::UmpPacket;
+use crate::note::Note;
+use crate::pitch::Pitch;
+use crate::pitch::Pitched;
+use crate::pitch::Ratio;
+use crate::tuner::MidiTarget;
+use crate::tuner::MidiTunerMessage;
+use crate::tuner::MidiTunerMessageHandler;
+
+const NUM_CHANNELS: usize = 16;
+const DEFAULT_PITCH_BEND_RANGE: f64 = 2.0;
+const DEFAULT_MPE_PITCH_BEND_RANGE: f64 = 48.0;
+
+/// A multichannel synthesizer which understands MIDI 1.0 channel messages, MIDI Tuning Standard messages and MIDI 2.0 Universal MIDI Packets.
+///
+/// Clones share the same state s.t. a clone can be handed over to a [`MidiTarget`] while the original is used for inspection.
+///
+/// Supported tuning mechanisms:
+/// - Single Note Tuning Change (with and without Bank Select) + Tuning Program/Bank Change (RPN 3 and 4)
+/// - Scale/Octave Tuning (1-Byte and 2-Byte)
+/// - Channel Fine Tuning (RPN 1) and Channel Coarse Tuning (RPN 2)
+/// - Pitch Bend + Pitch Bend Sensitivity (RPN 0)
+/// - MPE Configuration (RPN 6) with zone-wide pitch bend of the manager channel
+/// - MIDI 2.0 Pitch 7.9 Note On attribute and Registered Per-Note Controller #3 (Pitch 7.25)
+///
+/// All tuning changes, including non-real-time MTS messages, immediately affect sounding notes. Sustain is not emulated.
+#[derive(Clone, Debug, Default)]
+pub struct VirtualSynth {
+    state: Arc<Mutex<SynthState>>,
+}
+
+impl VirtualSynth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`MidiTarget`] which sends all messages to (a clone of) `self`.
+    pub fn target(&self, channels: impl IntoIterator<Item = u8>) -> MidiTarget<VirtualSynth> {
+        MidiTarget {
+            handler: self.clone(),
+            channels: channels.into_iter().collect(),
+        }
+    }
+
+    /// Processes a raw MIDI 1.0 byte sequence or a sequence of big-endian UMP words.
+    ///
+    /// Byte sequences starting with a status byte (`0x80..=0xff`) are considered MIDI 1.0 data, all other byte sequences are considered UMP data.
+    pub fn process_bytes(&self, bytes: &[u8]) {
+        self.state.lock().unwrap().process_bytes(bytes);
+    }
+
+    /// Returns all currently sounding notes ordered by channel and key.
+    pub fn sounding_notes(&self) -> Vec<SoundingNote> {
+        self.state.lock().unwrap().sounding_notes()
+    }
+
+    /// Compares the currently sounding notes with the `expected_pitches` and reports all issues that occurred since the last call.
+    ///
+    /// Expected pitches are matched with the closest sounding pitch within `tolerance` first. Unmatched pitches are paired in ascending order and reported as [`SynthIssue::WrongPitch`].
+    /// Any remaining expected pitches are reported as [`SynthIssue::MissingNote`], any remaining sounding notes as [`SynthIssue::StuckNote`].
+    pub fn check(
+        &self,
+        expected_pitches: impl IntoIterator<Item = Pitch>,
+        tolerance: Ratio,
+    ) -> Vec<SynthIssue> {
+        let mut state = self.state.lock().unwrap();
+        let mut issues = mem::take(&mut state.issues);
+
+        let mut unmatched_notes = state.sounding_notes();
+        let mut unmatched_pitches = Vec::new();
+        for expected_pitch in expected_pitches {
+            let closest_note = unmatched_notes
+                .iter()
+                .enumerate()
+                .map(|(index, note)| {
+                    (
+                        index,
+                        Ratio::between_pitches(expected_pitch, note.pitch).abs(),
+                    )
+                })
+                .filter(|&(_, deviation)| deviation <= tolerance)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            match closest_note {
+                Some((index, _)) => {
+                    unmatched_notes.remove(index);
+                }
+                None => unmatched_pitches.push(expected_pitch),
+            }
+        }
+
+        unmatched_notes.sort_by(|a, b| a.pitch.as_hz().total_cmp(&b.pitch.as_hz()));
+        unmatched_pitches.sort_by(|a, b| a.as_hz().total_cmp(&b.as_hz()));
+
+        let mut unmatched_notes = unmatched_notes.into_iter();
+        let mut unmatched_pitches = unmatched_pitches.into_iter();
+        loop {
+            match (unmatched_pitches.next(), unmatched_notes.next()) {
+                (Some(expected), Some(note)) => issues.push(SynthIssue::WrongPitch {
+                    channel: note.channel,
+                    key: note.key,
+                    expected,
+                    actual: note.pitch,
+                }),
+                (Some(expected), None) => issues.push(SynthIssue::MissingNote { expected }),
+                (None, Some(note)) => issues.push(SynthIssue::StuckNote {
+                    channel: note.channel,
+                    key: note.key,
+                    pitch: note.pitch,
+                }),
+                (None, None) => break,
+            }
+        }
+
+        issues
+    }
+}
+
+impl MidiTunerMessageHandler for VirtualSynth {
+    fn handle(&mut self, message: MidiTunerMessage) {
+        message.send_to(|bytes| self.process_bytes(bytes));
+    }
+}
+
+/// A note that is currently sounding on a [`VirtualSynth`].
+#[derive(Copy, Clone, Debug)]
+pub struct SoundingNote {
+    pub channel: u8,
+    pub key: u8,
+    pub pitch: Pitch,
+}
+
+/// A problem detected by a [`VirtualSynth`].
+#[derive(Copy, Clone, Debug)]
+pub enum SynthIssue {
+    /// A note was started on a channel where the same key is already sounding. The old note is replaced.
+    Clash { channel: u8, key: u8 },
+    /// A note was stopped that is not sounding.
+    UnmatchedNoteOff { channel: u8, key: u8 },
+    /// A sounding note does not have the expected pitch.
+    WrongPitch {
+        channel: u8,
+        key: u8,
+        expected: Pitch,
+        actual: Pitch,
+    },
+    /// An expected pitch is not sounding.
+    MissingNote { expected: Pitch },
+    /// A note is sounding although it is not expected.
+    StuckNote { channel: u8, key: u8, pitch: Pitch },
+}
+
+#[derive(Debug)]
+struct SynthState {
+    parser: MidiStreamParser,
+    decoder: ControlChangeDecoder,
+    channels: [ChannelState; NUM_CHANNELS],
+    /// Note numbers of the tuning programs, indexed by (bank, program).
+    tuning_programs: HashMap<(u8, u8), [f64; 128]>,
+    /// Number of member channels of the lower and the upper MPE zone.
+    mpe_zones: [u8; 2],
+    /// Sounding voices, indexed by (channel, key), with their pitch overrides in fractional note numbers.
+    voices: BTreeMap<(u8, u8), Option<f64>>,
+    issues: Vec<SynthIssue>,
+}
+
+#[derive(Clone, Debug)]
+struct ChannelState {
+    pitch_bend: f64,
+    pitch_bend_range: f64,
+    fine_tuning: f64,
+    coarse_tuning: f64,
+    tuning_bank: u8,
+    tuning_program: u8,
+    octave_tuning: [f64; 12],
+    note_pitches: HashMap<u8, f64>,
+}
+
+impl Default for SynthState {
+    fn default() -> Self {
+        Self {
+            parser: MidiStreamParser::new(),
+            decoder: ControlChangeDecoder::new(),
+            channels: Default::default(),
+            tuning_programs: HashMap::new(),
+            mpe_zones: [0, 0],
+            voices: BTreeMap::new(),
+            issues: Vec::new(),
+        }
+    }
+}
+
+impl Default for ChannelState {
+    fn default() -> Self {
+        Self {
+            pitch_bend: 0.0,
+            pitch_bend_range: DEFAULT_PITCH_BEND_RANGE,
+            fine_tuning: 0.0,
+            coarse_tuning: 0.0,
+            tuning_bank: 0,
+            tuning_program: 0,
+            octave_tuning: [0.0; 12],
+            note_pitches: HashMap::new(),
+        }
+    }
+}
+
+impl SynthState {
+    fn process_bytes(&mut self, bytes: &[u8]) {
+        match bytes.first() {
+            Some(0x80..) => {
+                let messages: Vec<_> = self.parser.parse(bytes).collect();
+                for message in messages {
+                    match message {
+                        MidiMessage::Channel(message) => self.process_channel_message(message),
+                        MidiMessage::SysEx(sysex) => self.process_sysex(&sysex),
+                        MidiMessage::SystemCommon(_) | MidiMessage::SystemRealTime(_) => {}
+                    }
+                }
+            }
+            Some(_) => {
+                let words: Vec<_> = bytes
+                    .chunks_exact(4)
+                    .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+                    .collect();
+                let mut remaining_words = &words[..];
+                while let Some(&first_word) = remaining_words.first() {
+                    match UmpPacket::from_words(remaining_words) {
+                        Some(UmpPacket::Midi1ChannelVoice { message, .. }) => {
+                            self.process_channel_message(message)
+                        }
+                        Some(UmpPacket::Midi2ChannelVoice { message, .. }) => {
+                            self.process_midi2_message(message)
+                        }
+                        None => {}
+                    }
+                    let packet_size = midi::ump_packet_size(first_word).min(remaining_words.len());
+                    remaining_words = &remaining_words[packet_size..];
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn process_channel_message(&mut self, message: ChannelMessage) {
+        let channel = message.channel();
+        match message.message_type() {
+            ChannelMessageType::NoteOff { key, .. }
+            | ChannelMessageType::NoteOn { key, velocity: 0 } => self.note_off(channel, key),
+            ChannelMessageType::NoteOn { key, .. } => self.note_on(channel, key, None),
+            ChannelMessageType::PitchBendChange { value } => {
+                self.channels[usize::from(channel)].pitch_bend = f64::from(value) / 8192.0;
+            }
+            ChannelMessageType::ControlChange { .. } => {
+                if let Some(event) = self.decoder.decode(message) {
+                    self.process_control_event(event.channel, event.event_type);
+                }
+            }
+            ChannelMessageType::PolyphonicKeyPressure { .. }
+            | ChannelMessageType::ProgramChange { .. }
+            | ChannelMessageType::ChannelPressure { .. } => {}
+        }
+    }
+
+    fn process_midi2_message(&mut self, message: Midi2ChannelMessage) {
+        let channel = message.channel();
+        match message.message_type() {
+            Midi2ChannelMessageType::NoteOff { key, .. } => self.note_off(channel, key),
+            Midi2ChannelMessageType::NoteOn { key, attribute, .. } => {
+                let attribute_pitch = match attribute {
+                    NoteAttribute::Pitch7_9(pitch) => Some(f64::from(pitch) / 512.0),
+                    _ => None,
+                };
+                self.note_on(channel, key, attribute_pitch);
+            }
+            Midi2ChannelMessageType::RegisteredPerNoteController {
+                key,
+                index: midi::PITCH_7_25_CONTROLLER,
+                value,
+            } => {
+                let note_number = f64::from(value) / 33554432.0;
+                self.channels[usize::from(channel)]
+                    .note_pitches
+                    .insert(key, note_number);
+                if let Some(voice) = self.voices.get_mut(&(channel, key)) {
+                    *voice = Some(note_number);
+                }
+            }
+            Midi2ChannelMessageType::PitchBendChange { value } => {
+                self.channels[usize::from(channel)].pitch_bend =
+                    (f64::from(value) - 2147483648.0) / 2147483648.0;
+            }
+            Midi2ChannelMessageType::ControlChange { controller, value } => {
+                let message_type = ChannelMessageType::ControlChange {
+                    controller,
+                    value: u8::try_from(value >> 25).unwrap(),
+                };
+                if let Some(message) = message_type.in_channel(channel) {
+                    self.process_channel_message(message);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn process_control_event(&mut self, channel: u8, event_type: ControlEventType) {
+        let ControlEventType::Parameter {
+            parameter: ParameterNumber::Registered(parameter),
+            change: ParameterChange::Set(value),
+        } = event_type
+        else {
+            return;
+        };
+
+        let (value_msb, value_lsb) = (value >> 7, value & 0x7f);
+        let channel_state = &mut self.channels[usize::from(channel)];
+        match parameter {
+            midi::PITCH_BEND_SENSITIVITY => {
+                channel_state.pitch_bend_range = f64::from(value_msb) + f64::from(value_lsb) / 100.0
+            }
+            midi::CHANNEL_FINE_TUNING => {
+                channel_state.fine_tuning = (f64::from(value) - 8192.0) / 8192.0
+            }
+            midi::CHANNEL_COARSE_TUNING => {
+                channel_state.coarse_tuning = f64::from(value_msb) - 64.0
+            }
+            midi::TUNING_PROGRAM_CHANGE => channel_state.tuning_program = value_msb as u8,
+            midi::TUNING_BANK_SELECT => channel_state.tuning_bank = value_msb as u8,
+            midi::MPE_CONFIGURATION => {
+                let zone = match channel {
+                    0 => 0,
+                    15 => 1,
+                    _ => return,
+                };
+                let num_member_channels = (value_msb as u8).min(15);
+                self.mpe_zones[zone] = num_member_channels;
+                for member_channel in self.mpe_member_channels(zone) {
+                    self.channels[usize::from(member_channel)].pitch_bend_range =
+                        DEFAULT_MPE_PITCH_BEND_RANGE;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn process_sysex(&mut self, sysex: &[u8]) {
+        // f0 7e/7f <device id> 08 <sub-id 2> ... f7
+        let [0xf0, 0x7e | 0x7f, _, 0x08, sub_id, payload @ .., 0xf7] = sysex else {
+            return;
+        };
+
+        match (sub_id, payload) {
+            (0x02, [tuning_program, _, tuning_changes @ ..]) => {
+                self.single_note_tuning_change(0, *tuning_program, tuning_changes)
+            }
+            (0x07, [tuning_bank, tuning_program, _, tuning_changes @ ..]) => {
+                self.single_note_tuning_change(*tuning_bank, *tuning_program, tuning_changes)
+            }
+            (0x08, [channels @ .., _]) if payload.len() == 3 + 12 => {
+                let detunings = &payload[3..];
+                self.scale_octave_tuning(&channels[..3], |letter| {
+                    (f64::from(detunings[letter]) - 64.0) / 100.0
+                })
+            }
+            (0x09, [channels @ .., _]) if payload.len() == 3 + 24 => {
+                let detunings = &payload[3..];
+                self.scale_octave_tuning(&channels[..3], |letter| {
+                    let value = u16::from(detunings[2 * letter]) << 7
+                        | u16::from(detunings[2 * letter + 1]);
+                    f64::from(value) / 8192.0 - 1.0
+                })
+            }
+            _ => {}
+        }
+    }
+
+    fn single_note_tuning_change(
+        &mut self,
+        tuning_bank: u8,
+        tuning_program: u8,
+        tuning_changes: &[u8],
+    ) {
+        let tuning = self
+            .tuning_programs
+            .entry((tuning_bank, tuning_program))
+            .or_insert_with(|| std::array::from_fn(|key| key as f64));
+
+        for tuning_change in tuning_changes.chunks_exact(4) {
+            let &[key, semitone, fraction_msb, fraction_lsb] = tuning_change else {
+                unreachable!()
+            };
+            // 7f 7f 7f means "no change"
+            if (semitone, fraction_msb, fraction_lsb) == (0x7f, 0x7f, 0x7f) {
+                continue;
+            }
+            let fraction = u16::from(fraction_msb) << 7 | u16::from(fraction_lsb);
+            tuning[usize::from(key & 0x7f)] = f64::from(semitone) + f64::from(fraction) / 16384.0;
+        }
+    }
+
+    fn scale_octave_tuning(&mut self, channels: &[u8], detuning: impl Fn(usize) -> f64) {
+        for (row, &channel_bits) in channels.iter().rev().enumerate() {
+            for bit in 0..7 {
+                let channel = row * 7 + bit;
+                if channel < NUM_CHANNELS && channel_bits & (1 << bit) != 0 {
+                    let octave_tuning = &mut self.channels[channel].octave_tuning;
+                    for (letter, letter_tuning) in octave_tuning.iter_mut().enumerate() {
+                        *letter_tuning = detuning(letter);
+                    }
+                }
+            }
+        }
+    }
+
+    fn note_on(&mut self, channel: u8, key: u8, attribute_pitch: Option<f64>) {
+        let note_pitch = attribute_pitch.or_else(|| {
+            self.channels[usize::from(channel)]
+                .note_pitches
+                .get(&key)
+                .copied()
+        });
+        if self.voices.insert((channel, key), note_pitch).is_some() {
+            self.issues.push(SynthIssue::Clash { channel, key });
+        }
+    }
+
+    fn note_off(&mut self, channel: u8, key: u8) {
+        if self.voices.remove(&(channel, key)).is_none() {
+            self.issues
+                .push(SynthIssue::UnmatchedNoteOff { channel, key });
+        }
+    }
+
+    fn sounding_notes(&self) -> Vec<SoundingNote> {
+        self.voices
+            .iter()
+            .map(|(&(channel, key), &note_pitch)| SoundingNote {
+                channel,
+                key,
+                pitch: self.pitch_of(channel, key, note_pitch),
+            })
+            .collect()
+    }
+
+    fn pitch_of(&self, channel: u8, key: u8, note_pitch: Option<f64>) -> Pitch {
+        let channel_state = &self.channels[usize::from(channel)];
+
+        let note_number = note_pitch.unwrap_or_else(|| {
+            let tuned_key = self
+                .tuning_programs
+                .get(&(channel_state.tuning_bank, channel_state.tuning_program))
+                .map(|tuning| tuning[usize::from(key)])
+                .unwrap_or(f64::from(key));
+            tuned_key + channel_state.octave_tuning[usize::from(key % 12)]
+        });
+
+        let manager_bend = (0..2)
+            .filter(|&zone| {
+                self.mpe_member_channels(zone)
+                    .any(|member| member == channel)
+            })
+            .map(|zone| {
+                let manager_state = &self.channels[[0, 15][zone]];
+                manager_state.pitch_bend * manager_state.pitch_bend_range
+            })
+            .sum::<f64>();
+
+        let total_note_number = note_number
+            + channel_state.coarse_tuning
+            + channel_state.fine_tuning
+            + channel_state.pitch_bend * channel_state.pitch_bend_range
+            + manager_bend;
+
+        Note::from_midi_number(0).pitch() * Ratio::from_semitones(total_note_number)
+    }
+
+    fn mpe_member_channels(&self, zone: usize) -> impl Iterator<Item = u8> + use<> {
+        let num_member_channels = self.mpe_zones[zone];
+        match zone {
+            0 => 1..num_member_channels + 1,
+            _ => 15 - num_member_channels..15,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::PianoKey;
+    use crate::mts::ScaleOctaveTuningFormat;
+    use crate::scala::KbmRoot;
+    use crate::scala::Scl;
+    use crate::tuner::AotTuner;
+    use crate::tuner::JitTuner;
+    use crate::tuner::PoolingMode;
+    use crate::tuner::TunableMidi;
+    use crate::tuning::KeyboardMapping;
+
+    const NUM_KEYS: u8 = 9;
+
+    #[test]
+    fn jit_tuner_plays_intended_pitches() {
+        for (name, capabilities, synth, tunable_midi) in create_synths() {
+            let mut tuner = JitTuner::start(tunable_midi, PoolingMode::Stop);
+            let tuning = edo_31_tuning(0.0);
+
+            let keys = || (60..60 + i32::from(NUM_KEYS)).map(PianoKey::from_midi_number);
+            for key in keys() {
+                tuner.note_on(key, tuning.maybe_pitch_of(key).unwrap(), 100);
+            }
+            assert_no_issues(
+                name,
+                synth.check(
+                    keys().map(|key| tuning.maybe_pitch_of(key).unwrap()),
+                    tolerance(),
+                ),
+            );
+
+            if capabilities.retunes_held_notes {
+                // Held notes keep their MIDI note which is why larger retunings would exceed the range of the 1-byte format
+                let tuning = edo_31_tuning(10.0);
+                for key in keys() {
+                    tuner.note_pitch(key, tuning.maybe_pitch_of(key).unwrap());
+                }
+                assert_no_issues(
+                    name,
+                    synth.check(
+                        keys().map(|key| tuning.maybe_pitch_of(key).unwrap()),
+                        tolerance(),
+                    ),
+                );
+            }
+
+            for key in keys() {
+                tuner.note_off(key, 0);
+            }
+            assert_no_issues(name, synth.check([], tolerance()));
+        }
+    }
+
+    #[test]
+    fn aot_tuner_plays_intended_pitches() {
+        for (name, _, synth, tunable_midi) in create_synths() {
+            let mut tuner = AotTuner::start(tunable_midi);
+            let keys = || (60..60 + i32::from(NUM_KEYS)).map(PianoKey::from_midi_number);
+
+            for detuning in [0.0, 30.0] {
+                let tuning = edo_31_tuning(detuning);
+                tuner.set_tuning(&tuning, keys()).unwrap();
+                assert!(tuner.tuned(), "{name}");
+
+                for key in keys() {
+                    tuner.note_on(key, 100);
+                }
+                assert_no_issues(
+                    name,
+                    synth.check(
+                        keys().map(|key| tuning.maybe_pitch_of(key).unwrap()),
+                        tolerance(),
+                    ),
+                );
+
+                for key in keys() {
+                    tuner.note_off(key, 0);
+                }
+                assert_no_issues(name, synth.check([], tolerance()));
+            }
+        }
+    }
+
+    #[test]
+    fn report_issues() {
+        let synth = VirtualSynth::new();
+        let note_on = |key| ChannelMessageType::NoteOn { key, velocity: 100 };
+        let note_off = |key| ChannelMessageType::NoteOff { key, velocity: 0 };
+        let send = |message_type: ChannelMessageType, channel| {
+            synth.process_bytes(&message_type.in_channel(channel).unwrap().to_raw_message())
+        };
+
+        send(note_on(69), 0);
+        send(note_on(69), 0);
+        send(note_off(70), 1);
+        send(note_on(72), 1);
+        send(ChannelMessageType::PitchBendChange { value: 4096 }, 1);
+
+        let a4 = Pitch::from_hz(440.0);
+        let issues = synth.check(
+            [
+                a4,
+                a4 * Ratio::from_semitones(3),
+                a4 * Ratio::from_semitones(5),
+            ],
+            tolerance(),
+        );
+        assert!(
+            matches!(
+                issues[..],
+                [
+                    SynthIssue::Clash {
+                        channel: 0,
+                        key: 69
+                    },
+                    SynthIssue::UnmatchedNoteOff {
+                        channel: 1,
+                        key: 70
+                    },
+                    SynthIssue::WrongPitch {
+                        channel: 1,
+                        key: 72,
+                        ..
+                    },
+                    SynthIssue::MissingNote { .. },
+                ]
+            ),
+            "{issues:?}"
+        );
+        let SynthIssue::WrongPitch {
+            expected, actual, ..
+        } = issues[2]
+        else {
+            unreachable!()
+        };
+        assert!((expected.as_hz() - a4.as_hz() * 2f64.powf(3.0 / 12.0)).abs() < 1e-9);
+        assert!(Ratio::between_pitches(a4 * Ratio::from_semitones(4), actual).is_negligible());
+
+        // Issues are only reported once
+        assert!(matches!(
+            synth.check([], tolerance())[..],
+            [SynthIssue::StuckNote { .. }, SynthIssue::StuckNote { .. }]
+        ));
+    }
+
+    type CreateSynth = fn(MidiTarget<VirtualSynth>) -> TunableMidi<VirtualSynth>;
+
+    #[derive(Clone, Copy)]
+    struct Capabilities {
+        /// Channel 0 is reserved for messages that affect all notes.
+        has_manager_channel: bool,
+        /// Pitch updates of already sounding notes are supported.
+        retunes_held_notes: bool,
+    }
+
+    const STANDARD: Capabilities = Capabilities {
+        has_manager_channel: false,
+        retunes_held_notes: true,
+    };
+
+    fn create_synths() -> Vec<(
+        &'static str,
+        Capabilities,
+        VirtualSynth,
+        TunableMidi<VirtualSynth>,
+    )> {
+        let channels = 0..NUM_KEYS;
+        let mpe_channels = 1..NUM_KEYS + 1;
+
+        let synths: [(&str, Capabilities, CreateSynth); 9] = [
+            ("single_note_tuning_change", STANDARD, |target| {
+                TunableMidi::single_note_tuning_change(target, true, 0x7f, 0)
+            }),
+            ("single_note_tuning_change_non_rt", STANDARD, |target| {
+                TunableMidi::single_note_tuning_change(target, false, 0x7f, 10)
+            }),
+            ("scale_octave_tuning_1_byte", STANDARD, |target| {
+                TunableMidi::scale_octave_tuning(
+                    target,
+                    true,
+                    0x7f,
+                    ScaleOctaveTuningFormat::OneByte,
+                )
+            }),
+            ("scale_octave_tuning_2_byte", STANDARD, |target| {
+                TunableMidi::scale_octave_tuning(
+                    target,
+                    false,
+                    0x7f,
+                    ScaleOctaveTuningFormat::TwoByte,
+                )
+            }),
+            (
+                "channel_fine_tuning",
+                STANDARD,
+                TunableMidi::channel_fine_tuning,
+            ),
+            ("pitch_bend", STANDARD, TunableMidi::pitch_bend),
+            (
+                "mpe",
+                Capabilities {
+                    has_manager_channel: true,
+                    ..STANDARD
+                },
+                |target| TunableMidi::mpe(target, 0, Ratio::from_semitones(48)),
+            ),
+            (
+                "midi2_pitch_attribute",
+                Capabilities {
+                    retunes_held_notes: false,
+                    ..STANDARD
+                },
+                TunableMidi::midi2_pitch_attribute,
+            ),
+            (
+                "midi2_per_note_pitch",
+                STANDARD,
+                TunableMidi::midi2_per_note_pitch,
+            ),
+        ];
+
+        synths
+            .into_iter()
+            .map(|(name, capabilities, create_synth)| {
+                let synth = VirtualSynth::new();
+                let target = if capabilities.has_manager_channel {
+                    synth.target(mpe_channels.clone())
+                } else {
+                    synth.target(channels.clone())
+                };
+                (name, capabilities, synth, create_synth(target))
+            })
+            .collect()
+    }
+
+    fn edo_31_tuning(cents: f64) -> impl KeyboardMapping<PianoKey> {
+        let scl = Scl::builder()
+            .push_ratio(Ratio::octave().divided_into_equal_steps(31))
+            .build()
+            .unwrap();
+        let kbm_root = KbmRoot {
+            ref_key: PianoKey::from_midi_number(60),
+            ref_pitch: Note::from_midi_number(60).pitch() * Ratio::from_cents(cents),
+            root_offset: 0,
+        };
+        (scl, kbm_root.to_kbm())
+    }
+
+    fn tolerance() -> Ratio {
+        // The resolution of the 1-byte Scale/Octave Tuning format and the MPE pitch-bend range (48 semitones) is up to 1 cent
+        Ratio::from_cents(1.0)
+    }
+
+    fn assert_no_issues(name: &str, issues: Vec<SynthIssue>) {
+        assert!(issues.is_empty(), "{name}: {issues:?}");
+    }
+}
@@ -1,16 +1,24 @@
 use std::collections::HashMap;
 use std::io;
+use std::path::PathBuf;
 
 use io::Read;
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde::Serializer;
+use serde::de;
 use tune::key::PianoKey;
+use tune::note::Note;
 use tune::pitch::Pitch;
+use tune::pitch::Ratio;
+use tune::tuner::AdaptiveStrategy;
 use tune::tuning::KeyboardMapping;
 
 use crate::CliError;
 use crate::CliResult;
 use crate::error::ResultExt;
+use crate::midi::MidiInArgs;
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
@@ -70,3 +78,194 @@ impl KeyboardMapping<PianoKey> for DtoKeyboardMapping {
         self.key_map.get(&key).copied()
     }
 }
+
+/// A list of scales which can be selected at runtime.
+///
+/// Relative file locations are resolved wrt. the location of the playlist file.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlaylistDto {
+    pub scales: Vec<PlaylistItemDto>,
+}
+
+impl PlaylistDto {
+    pub fn read(input: impl Read) -> CliResult<PlaylistDto> {
+        serde_yaml::from_reader(input).display_err::<CliError>("Could not parse playlist file")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PlaylistItemDto {
+    SclAndKbm { scl: PathBuf, kbm: PathBuf },
+    ScaleFile { scale_file: PathBuf },
+}
+
+/// Additional MIDI inputs and outputs of a `tune live` session.
+///
+/// Relative file locations are resolved wrt. the location of the routing file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RoutingDto {
+    #[serde(default)]
+    pub inputs: Vec<RoutingInputDto>,
+    #[serde(default)]
+    pub outputs: Vec<RoutingOutputDto>,
+}
+
+impl RoutingDto {
+    pub fn read(input: impl Read) -> CliResult<RoutingDto> {
+        serde_yaml::from_reader(input).display_err::<CliError>("Could not parse routing file")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RoutingInputDto {
+    pub device: String,
+    #[serde(flatten)]
+    pub in_args: MidiInArgs,
+    /// Fixed scale of the input. If absent, the input follows the selected scale.
+    pub scale: Option<PlaylistItemDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RoutingOutputDto {
+    pub device: String,
+    pub out_channel: Option<u8>,
+    pub num_out_channels: Option<u8>,
+    /// Indexes of the inputs to accept. Index 0 refers to the `--midi-in` device.
+    pub inputs: Option<Vec<usize>>,
+    /// Input channels to accept.
+    pub channels: Option<Vec<u8>>,
+    /// Lower key bound (inclusive) of the accepted notes, e.g. 48 or C3.
+    #[serde(default, with = "serde_note")]
+    pub lo_key: Option<Note>,
+    /// Upper key bound (exclusive) of the accepted notes, e.g. 72 or C5.
+    #[serde(default, with = "serde_note")]
+    pub up_key: Option<Note>,
+}
+
+/// A command line argument value of a session config file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ArgValueDto {
+    Integer(i64),
+    Decimal(f64),
+    Text(String),
+}
+
+impl ArgValueDto {
+    pub fn from_raw(raw: String) -> Self {
+        match raw.parse() {
+            Ok(integer) => ArgValueDto::Integer(integer),
+            Err(_) => ArgValueDto::Text(raw),
+        }
+    }
+
+    pub fn to_raw(&self) -> String {
+        match self {
+            ArgValueDto::Integer(integer) => integer.to_string(),
+            ArgValueDto::Decimal(decimal) => decimal.to_string(),
+            ArgValueDto::Text(text) => text.clone(),
+        }
+    }
+}
+
+/// Serializes an optional [`Note`] as MIDI number. Notes can be given as MIDI number or note name, e.g. 62 or D4.
+pub mod serde_note {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(note: &Option<Note>, serializer: S) -> Result<S::Ok, S::Error> {
+        note.map(Note::midi_number).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Note>, D::Error> {
+        Option::<ArgValueDto>::deserialize(deserializer)?
+            .map(|note| note.to_raw().parse().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Serializes a [`Ratio`] in cents, e.g. `30c`. Ratios can be given in any ratio syntax, e.g. 3/2 or 30c.
+pub mod serde_ratio {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(ratio: &Ratio, serializer: S) -> Result<S::Ok, S::Error> {
+        // Rounding avoids floating-point noise, e.g. 29.999999999999996c
+        let cents = (ratio.as_cents() * 1e6).round() / 1e6;
+        format!("{cents}c").serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ratio, D::Error> {
+        ArgValueDto::deserialize(deserializer)?
+            .to_raw()
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Serializes an optional [`AdaptiveStrategy`] by its command line name, e.g. `anchor`.
+pub mod serde_adaptive_strategy {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        strategy: &Option<AdaptiveStrategy>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        strategy
+            .map(|strategy| match strategy {
+                AdaptiveStrategy::Anchor => "anchor",
+                AdaptiveStrategy::Drift => "drift",
+                AdaptiveStrategy::Center => "center",
+            })
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<AdaptiveStrategy>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|strategy| strategy.parse().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// An incoming channel message of a `tune live` session together with its effect.
+///
+/// MIDI messages are given as space-separated hex bytes, e.g. `"90 3c 64"`. The `time`, `input` and `message` fields are sufficient to replay a session.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LiveLogEntryDto {
+    /// Unix time in seconds at which the session has been started.
+    /// Only given for the first entry of a session s.t. sessions appended to the same file can be told apart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_start: Option<f64>,
+    /// Seconds since the start of the session.
+    pub time: f64,
+    pub input: usize,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<LiveLogNoteDto>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<LiveLogOutputDto>,
+}
+
+/// The key and the target pitch of a started note.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LiveLogNoteDto {
+    pub key_midi_number: i32,
+    /// Index of the key relative to the origin of the scale.
+    pub scale_index: i32,
+    pub pitch_in_hz: f64,
+    pub nearest_midi_number: i32,
+    pub deviation_in_cents: f64,
+}
+
+/// The MIDI messages emitted to a single output.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LiveLogOutputDto {
+    pub output: usize,
+    /// The output channel of the started note, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u8>,
+    pub messages: Vec<String>,
+}
@@ -0,0 +1,170 @@
+use clap::Parser;
+use clap::ValueEnum;
+use tune::layout::ColorPalette;
+use tune::layout::IsomorphicKeyboard;
+use tune::layout::IsomorphicLayout;
+use tune::layout::KeyGrid;
+use tune::pitch::Ratio;
+use tune::svg;
+use tune::svg::SvgKey;
+
+use crate::App;
+use crate::CliResult;
+use crate::scala::SclCommand;
+
+#[derive(Parser)]
+pub(crate) enum LayoutCommand {
+    /// Render an isomorphic keyboard layout or scale chart as SVG
+    #[command(name = "svg")]
+    Svg(SvgOptions),
+}
+
+impl LayoutCommand {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        match self {
+            LayoutCommand::Svg(options) => options.run(app),
+        }
+    }
+}
+
+#[derive(Parser)]
+pub(crate) struct SvgOptions {
+    /// Kind of chart to render
+    #[arg(long = "chart", value_enum, default_value = "hex")]
+    chart: Chart,
+
+    /// Number of key columns of the keyboard
+    #[arg(long = "cols", default_value = "16")]
+    num_columns: u16,
+
+    /// Number of key rows of the keyboard
+    #[arg(long = "rows", default_value = "8")]
+    num_rows: u16,
+
+    /// Labels to display on each key
+    #[arg(
+        long = "labels",
+        value_enum,
+        use_value_delimiter = true,
+        default_value = "name,cents"
+    )]
+    labels: Vec<Label>,
+
+    /// Index of the isomorphic layout to use if the scale supports multiple layouts
+    #[arg(long = "layout", default_value = "0")]
+    layout_index: usize,
+
+    /// Odd limit for ratio labels
+    #[arg(long = "lim", default_value = "11")]
+    odd_limit: u16,
+
+    #[command(subcommand)]
+    scl: SclCommand,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Chart {
+    /// Hexagonal keyboard
+    #[value(name = "hex")]
+    Hex,
+    /// Rectangular keyboard
+    #[value(name = "rect")]
+    Rect,
+    /// Piano-style strip containing one period of the layout
+    #[value(name = "strip")]
+    Strip,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Label {
+    /// Generalized note name
+    #[value(name = "name")]
+    Name,
+    /// Pitch relative to the root in cents
+    #[value(name = "cents")]
+    Cents,
+    /// Nearest fraction within the odd limit
+    #[value(name = "ratio")]
+    Ratio,
+    /// Scale degree
+    #[value(name = "degree")]
+    Degree,
+}
+
+impl SvgOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let scl = self.scl.to_scl(None)?;
+
+        let avg_step_size = if scl.period().is_negligible() {
+            Ratio::from_octaves(1)
+        } else {
+            scl.period()
+        }
+        .divided_into_equal_steps(scl.num_items());
+
+        let layouts = IsomorphicLayout::find_by_step_size(avg_step_size);
+        let Some(layout) = layouts.get(self.layout_index) else {
+            return Err(format!(
+                "Could not find isomorphic layout with index {} ({} layouts available)",
+                self.layout_index,
+                layouts.len()
+            )
+            .into());
+        };
+
+        let palette = default_palette();
+        let layers = layout.get_layers();
+        let period = i32::from(layout.pergen().period());
+
+        let get_key = |degree: i32| {
+            let index = u16::try_from(degree.rem_euclid(period)).unwrap();
+            SvgKey {
+                color: palette.get_color(layers[usize::from(index)]).to_owned(),
+                labels: self
+                    .labels
+                    .iter()
+                    .map(|label| match label {
+                        Label::Name => layout.get_note_name(index),
+                        Label::Cents => {
+                            format!("{:.1}c", scl.relative_pitch_of(degree).as_cents())
+                        }
+                        Label::Ratio => {
+                            let fraction = scl
+                                .relative_pitch_of(degree)
+                                .nearest_fraction(self.odd_limit);
+                            format!("{}/{}", fraction.numer, fraction.denom)
+                        }
+                        Label::Degree => degree.to_string(),
+                    })
+                    .collect(),
+            }
+        };
+
+        let svg = match self.chart {
+            Chart::Hex => svg::render_keyboard(
+                &IsomorphicKeyboard::from_mos(KeyGrid::Hex, layout.mos()),
+                self.num_columns,
+                self.num_rows,
+                get_key,
+            ),
+            Chart::Rect => svg::render_keyboard(
+                &IsomorphicKeyboard::from_mos(KeyGrid::Rect, layout.mos()),
+                self.num_columns,
+                self.num_rows,
+                get_key,
+            ),
+            Chart::Strip => svg::render_strip((0..period).map(get_key)),
+        };
+
+        Ok(app.write(svg)?)
+    }
+}
+
+fn default_palette() -> ColorPalette<&'static str> {
+    ColorPalette {
+        natural_color: "#ffffff",
+        sharp_colors: vec!["#8000ff", "#0000ff", "#0080ff", "#8080ff"],
+        flat_colors: vec!["#80ff00", "#00ff00", "#00ff80", "#80ff80"],
+        enharmonic_colors: vec!["#008080", "#ff8080", "#ff00ff", "#ff80ff"],
+    }
+}
@@ -1,6 +1,7 @@
 mod dto;
 mod error;
 mod est;
+mod layout;
 mod live;
 mod midi;
 mod mos;
@@ -8,6 +9,7 @@ mod mts;
 mod portable;
 mod scala;
 mod scale;
+mod smf;
 
 use std::fmt;
 use std::fmt::Display;
@@ -18,11 +20,15 @@ use std::io::ErrorKind;
 use std::io::Write;
 use std::path::PathBuf;
 
+use clap::ArgMatches;
+use clap::CommandFactory;
+use clap::FromArgMatches;
 use clap::Parser;
 use error::ResultExt;
 use est::EstOptions;
 use futures::executor;
 use io::Read;
+use layout::LayoutCommand;
 use live::LiveOptions;
 use mos::MosCommand;
 use mts::MtsOptions;
@@ -31,6 +37,7 @@ use scala::SclOptions;
 use scale::DiffOptions;
 use scale::DumpOptions;
 use scale::ScaleCommand;
+use smf::SmfOptions;
 
 #[doc(hidden)]
 pub mod shared;
@@ -64,6 +71,10 @@ enum MainCommand {
     #[command(subcommand, name = "mos")]
     Mos(MosCommand),
 
+    /// Render keyboard layouts and scale charts
+    #[command(subcommand, name = "layout")]
+    Layout(LayoutCommand),
+
     /// Print a scale to stdout
     #[command(subcommand, name = "scale")]
     Scale(ScaleCommand),
@@ -86,13 +97,18 @@ enum MainCommand {
     #[command(name = "live")]
     Live(LiveOptions),
 
+    /// Retune a Standard MIDI File offline.
+    /// The note events of the input file are processed in the same way as in the `live` command and the tuning messages are injected into the output file.
+    #[command(name = "smf")]
+    Smf(SmfOptions),
+
     /// List MIDI devices
     #[command(name = "devices")]
     Devices,
 }
 
 impl MainOptions {
-    async fn run(self) -> Result<(), CliError> {
+    async fn run(self, matches: &ArgMatches) -> Result<(), CliError> {
         let output: Box<dyn Write> = match self.output_file {
             Some(output_file) => Box::new(File::create(output_file)?),
             None => Box::new(io::stdout()),
@@ -104,22 +120,28 @@ impl MainOptions {
             error: Box::new(io::stderr()),
         };
 
-        self.command.run(&mut app).await
+        self.command.run(&mut app, matches).await
     }
 }
 
 impl MainCommand {
-    async fn run(self, app: &mut App<'_>) -> CliResult {
+    async fn run(self, app: &mut App<'_>, matches: &ArgMatches) -> CliResult {
         match self {
             MainCommand::Scl(options) => options.run(app),
             MainCommand::Kbm(options) => options.run(app),
             MainCommand::Est(options) => options.run(app),
             MainCommand::Mos(options) => options.run(app),
+            MainCommand::Layout(options) => options.run(app),
             MainCommand::Scale(options) => options.run(app),
             MainCommand::Dump(options) => options.run(app),
             MainCommand::Diff(options) => options.run(app),
             MainCommand::Mts(options) => options.run(app),
-            MainCommand::Live(options) => options.run(app).await,
+            MainCommand::Live(options) => {
+                options
+                    .run(app, matches.subcommand_matches("live").unwrap())
+                    .await
+            }
+            MainCommand::Smf(options) => options.run(app),
             MainCommand::Devices => midi::print_midi_devices(&mut app.output, "tune-cli")
                 .debug_err("Could not print MIDI devices"),
         }
@@ -127,7 +149,11 @@ impl MainCommand {
 }
 
 pub fn run_in_shell_env() {
-    let options = match MainOptions::try_parse() {
+    let parse_result = MainOptions::command()
+        .try_get_matches()
+        .and_then(|matches| Ok((MainOptions::from_arg_matches(&matches)?, matches)));
+
+    let (options, matches) = match parse_result {
         Err(err) => {
             if err.use_stderr() {
                 eprintln!("{err}")
@@ -136,10 +162,10 @@ pub fn run_in_shell_env() {
             };
             return;
         }
-        Ok(options) => options,
+        Ok(parsed) => parsed,
     };
 
-    match executor::block_on(options.run()) {
+    match executor::block_on(options.run(&matches)) {
         Ok(()) => {}
         // The BrokenPipe case occurs when stdout tries to communicate with a process that has already terminated.
         // Since tune is an idempotent tool with repeatable results, it is okay to ignore this error and terminate successfully.
@@ -160,7 +186,11 @@ pub fn run_in_wasm_env(
         error: Box::new(error),
     };
 
-    let command = match MainCommand::try_parse_from(args) {
+    let parse_result = MainCommand::command()
+        .try_get_matches_from(args)
+        .and_then(|matches| Ok((MainCommand::from_arg_matches(&matches)?, matches)));
+
+    let (command, matches) = match parse_result {
         Err(err) => {
             if err.use_stderr() {
                 app.errln(err).unwrap()
@@ -169,10 +199,10 @@ pub fn run_in_wasm_env(
             };
             return;
         }
-        Ok(command) => command,
+        Ok(parsed) => parsed,
     };
 
-    match executor::block_on(command.run(&mut app)) {
+    match executor::block_on(command.run(&mut app, &matches)) {
         Ok(()) => {}
         Err(err) => app.errln(err).unwrap(),
     }
@@ -1,157 +1,668 @@
+use std::any::TypeId;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::iter;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use clap::Arg;
+use clap::ArgAction;
+use clap::ArgMatches;
+use clap::Command;
+use clap::FromArgMatches;
 use clap::Parser;
+use clap::Subcommand;
+use clap::parser::ValueSource;
+use flume::Receiver;
 use flume::Sender;
-use tune::midi::ChannelMessage;
+use midir::MidiOutputConnection;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de;
+use serde_yaml::Value;
+use tune::key::PianoKey;
 use tune::midi::ChannelMessageType;
+use tune::midi::ControlChangeDecoder;
+use tune::midi::ControlEvent;
+use tune::midi::ControlEventType;
+use tune::midi::MidiMessage;
+use tune::midi::MidiStreamParser;
+use tune::midi::NOTE_ON;
+use tune::midi::NRPN_LSB;
+use tune::midi::NRPN_MSB;
+use tune::midi::RPN_LSB;
+use tune::midi::RPN_MSB;
+use tune::note::Note;
+use tune::pitch::Pitch;
+use tune::pitch::Pitched;
+use tune::pitch::Ratio;
+use tune::tuner::AdaptiveOptions;
+use tune::tuner::AdaptiveStrategy;
+use tune::tuner::AotOptimization;
 use tune::tuner::AotTuner;
+use tune::tuner::AotTuningModel;
 use tune::tuner::JitTuner;
 use tune::tuner::MidiTarget;
+use tune::tuner::MidiTunerMessage;
 use tune::tuner::MidiTunerMessageHandler;
-use tune::tuner::PoolingMode;
+use tune::tuner::MidiTunerWarning;
+use tune::tuner::TunableMidi;
+use tune::tuner::TunableSynth;
+use tune::tuning::KeyboardMapping;
 
 use crate::App;
 use crate::CliError;
 use crate::CliResult;
 use crate::ScaleCommand;
+use crate::dto::ArgValueDto;
+use crate::dto::LiveLogEntryDto;
+use crate::dto::LiveLogNoteDto;
+use crate::dto::LiveLogOutputDto;
+use crate::dto::RoutingDto;
+use crate::dto::serde_adaptive_strategy;
+use crate::dto::serde_note;
+use crate::dto::serde_ratio;
 use crate::error::ResultExt;
 use crate::midi;
+use crate::midi::AllocationArgs;
 use crate::midi::MidiInArgs;
 use crate::midi::MidiOutArgs;
 use crate::midi::MidiSource;
 use crate::midi::MultiChannelOffset;
 use crate::midi::TuningMethod;
+use crate::scale::Scale;
 
-#[derive(Parser)]
+#[derive(Default, Deserialize, Parser, Serialize)]
+#[serde(default, deny_unknown_fields)]
 pub(crate) struct LiveOptions {
+    /// Session config file in YAML format providing the options and the mode of the live session.
+    /// Options given on the command line override the options of the config file.
+    /// A mode given on the command line replaces the mode of the config file.
+    /// Relative file locations are resolved wrt. the location of the config file
+    #[arg(long = "config")]
+    #[serde(skip)]
+    config_file: Option<PathBuf>,
+
+    /// Print the effective session config in YAML format and exit
+    #[arg(long = "dump-config")]
+    #[serde(skip)]
+    dump_config: bool,
+
     /// MIDI input device
-    #[arg(long = "midi-in")]
-    midi_in_device: String,
+    #[arg(long = "midi-in", required_unless_present = "config_file")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    midi_in_device: Option<String>,
 
     #[command(flatten)]
     midi_in_args: MidiInArgs,
 
     /// MIDI output device
-    #[arg(long = "midi-out")]
-    midi_out_device: String,
+    #[arg(long = "midi-out", required_unless_present_any = ["ump_out_file", "config_file", "routing_file"])]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    midi_out_device: Option<String>,
+
+    /// Write raw Universal MIDI Packets (big-endian) to a file instead of a MIDI output device. Use `-` to write to the standard output.
+    /// Intended to be used with the `midi2-attr` and `midi2-rc` tuning methods.
+    #[arg(long = "ump-out", conflicts_with = "midi_out_device")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ump_out_file: Option<PathBuf>,
 
     #[command(flatten)]
     midi_out_args: MidiOutArgs,
 
+    /// Routing table in YAML format listing additional MIDI inputs (`inputs`) and additional MIDI outputs (`outputs`).
+    /// Inputs can have their own scale. Outputs can be restricted to certain inputs, input channels or key ranges
+    #[arg(long = "routing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    routing_file: Option<PathBuf>,
+
+    /// Print each incoming channel message together with the target pitch of the played note, the assigned output channels and the emitted MIDI messages
+    #[arg(long = "monitor", action = ArgAction::Set, num_args = 0..=1, require_equals = true, default_value_t = false, default_missing_value = "true")]
+    monitor: bool,
+
+    /// Append each incoming channel message and its effect to the given file in YAML format.
+    /// The time and the raw bytes of each incoming message are recorded s.t. the session can be replayed
+    #[arg(long = "log")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    log_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    scale_switch_args: ScaleSwitchArgs,
+
     #[command(subcommand)]
-    mode: LiveMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<LiveMode>,
 }
 
-#[derive(Parser)]
-enum LiveMode {
+#[derive(Deserialize, Parser, Serialize)]
+pub(crate) enum LiveMode {
     /// Just-in-time: Tracks which notes are active and injects tuning messages into the stream of MIDI events.
     /// This mode uses a dynamic key-to-channel mapping to avoid tuning clashes.
     /// The number of output channels can be selected by the user and can be set to a small number.
     /// When tuning clashes occur several mitigation strategies can be applied.
     #[command(name = "jit")]
+    #[serde(rename = "jit")]
     JustInTime(JustInTimeOptions),
 
     /// Ahead-of-time: Sends all necessary tuning messages at startup.
     /// The key-to-channel mapping is fixed and eliminates tuning clashes s.t. this mode offers the highest degree of musical freedom.
     /// On the downside, the number of output channels cannot be changed by the user and might be a large number.
     #[command(name = "aot")]
+    #[serde(rename = "aot")]
     AheadOfTime(AheadOfTimeOptions),
 }
 
-#[derive(Parser)]
-struct JustInTimeOptions {
-    /// Describes what to do when a note is triggered that cannot be handled by any channel without tuning clashes.
-    /// [block] Do not accept the new note. It will remain silent.
-    /// [stop] Stop an old note and accept the new note.
-    /// [ignore] Neither block nor stop. Accept that an old note receives an arbitrary tuning update.
-    #[arg(long = "clash", default_value = "stop", value_parser = parse_mitigation)]
-    clash_mitigation: PoolingMode,
+#[derive(Default, Deserialize, Parser, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct ScaleSwitchArgs {
+    /// Playlist file in YAML format listing additional scales that can be selected at runtime.
+    /// Each entry refers to an scl and a kbm file (`scl`, `kbm`) or to a scale file (`scale_file`)
+    #[arg(long = "playlist")]
+    playlist_file: Option<PathBuf>,
+
+    /// Additional scale that can be selected at runtime, given as scl and kbm file
+    #[arg(long = "add-scale", num_args = 2, value_names = ["SCL_FILE", "KBM_FILE"])]
+    additional_scale_files: Vec<PathBuf>,
+
+    /// Select the scale via program change messages.
+    /// Program 0 selects the scale given on the command line, followed by the playlist and --add-scale scales
+    #[arg(long = "switch-prog", action = ArgAction::Set, num_args = 0..=1, require_equals = true, default_value_t = false, default_missing_value = "true")]
+    switch_via_program_change: bool,
+
+    /// Select the scale via the value of the given controller
+    #[arg(long = "switch-cc")]
+    switch_controller: Option<u8>,
+
+    /// Select the scale via a range of keys starting at the given key, e.g. 21 or A0.
+    /// The range contains one key per scale and is not played
+    #[arg(long = "switch-keys")]
+    #[serde(with = "serde_note")]
+    switch_key_range_start: Option<Note>,
+
+    /// Retune held notes when the scale is switched in jit mode. Otherwise, held notes keep their pitch
+    #[arg(long = "retune-held", action = ArgAction::Set, num_args = 0..=1, require_equals = true, default_value_t = false, default_missing_value = "true")]
+    retune_held_notes: bool,
+}
+
+const DEFAULT_ADAPTIVE_ODD_LIMIT: u16 = 5;
+
+#[derive(Deserialize, Parser, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct JustInTimeOptions {
+    #[command(flatten)]
+    #[serde(default)]
+    allocation_args: AllocationArgs,
+
+    /// Continuously retune the sounding notes toward pure ratios.
+    /// [anchor] The oldest sounding note keeps its pitch.
+    /// [drift] Minimize the total deviation from the scale.
+    /// [center] Keep the average deviation from the scale at zero (Hermode-style).
+    #[arg(long = "adapt")]
+    #[serde(default, with = "serde_adaptive_strategy")]
+    adaptive_strategy: Option<AdaptiveStrategy>,
+
+    /// Odd limit of the pure ratios in adaptive mode
+    #[arg(long = "adapt-lim", default_value_t = DEFAULT_ADAPTIVE_ODD_LIMIT)]
+    #[serde(default = "default_adaptive_odd_limit")]
+    adaptive_odd_limit: u16,
+
+    /// Maximum retuning of a single note in adaptive mode
+    #[arg(long = "adapt-dev", default_value = "30c")]
+    #[serde(default = "default_adaptive_max_deviation", with = "serde_ratio")]
+    adaptive_max_deviation: Ratio,
 
     /// MIDI-out tuning method
     #[arg(value_enum)]
     method: TuningMethod,
 
     #[command(subcommand)]
-    scale: ScaleCommand,
+    scale: ScaleArgs,
+}
+
+fn default_adaptive_odd_limit() -> u16 {
+    DEFAULT_ADAPTIVE_ODD_LIMIT
 }
 
-fn parse_mitigation(src: &str) -> Result<PoolingMode, &'static str> {
-    Ok(match &*src.to_lowercase() {
-        "block" => PoolingMode::Block,
-        "stop" => PoolingMode::Stop,
-        "ignore" => PoolingMode::Ignore,
-        _ => return Err("Invalid mode. Should be `block`, `stop` or `ignore`"),
-    })
+fn default_adaptive_max_deviation() -> Ratio {
+    Ratio::from_cents(30.0)
 }
 
-#[derive(Parser)]
-struct AheadOfTimeOptions {
+#[derive(Deserialize, Parser, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AheadOfTimeOptions {
+    /// Minimize the number of MIDI channels by solving a graph-colouring problem
+    #[arg(long = "optimize")]
+    #[serde(default)]
+    optimize: bool,
+
+    /// Accepted pitch error per key when optimizing
+    #[arg(long = "opt-tol", default_value = "0c", requires = "optimize")]
+    #[serde(default, with = "serde_ratio")]
+    optimization_tolerance: Ratio,
+
+    /// Maximum detuning of a single note when optimizing. Values above 50c allow keys to be played on neighboring notes
+    #[arg(long = "opt-detune", default_value = "50c", requires = "optimize")]
+    #[serde(default = "default_optimization_max_detuning", with = "serde_ratio")]
+    optimization_max_detuning: Ratio,
+
     /// MIDI-out tuning method
     #[arg(value_enum)]
     method: TuningMethod,
 
     #[command(subcommand)]
-    scale: ScaleCommand,
+    scale: ScaleArgs,
+}
+
+fn default_optimization_max_detuning() -> Ratio {
+    Ratio::from_cents(50.0)
+}
+
+/// A [`ScaleCommand`] that keeps its command line arguments s.t. it can be stored in a session config file.
+pub(crate) struct ScaleArgs {
+    command: ScaleCommand,
+    args: Vec<String>,
+}
+
+impl ScaleArgs {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, clap::Error> {
+        let matches =
+            scale_command().try_get_matches_from(iter::once("scale".to_owned()).chain(args))?;
+        Self::from_arg_matches(&matches)
+    }
+
+    fn resolve_paths(&mut self, base_dir: &Path) -> Result<(), clap::Error> {
+        let command = scale_command();
+        let matches = command
+            .clone()
+            .try_get_matches_from(iter::once("scale".to_owned()).chain(self.args.clone()))?;
+        let args = subcommand_to_args(&command, &matches, &|arg, value| {
+            if arg.get_value_parser().type_id() == TypeId::of::<PathBuf>() {
+                base_dir.join(value).to_string_lossy().into_owned()
+            } else {
+                value
+            }
+        });
+        *self = Self::from_args(args)?;
+        Ok(())
+    }
+}
+
+fn scale_command() -> Command {
+    let mut command =
+        ScaleCommand::augment_subcommands(Command::new("scale").subcommand_required(true));
+    command.build();
+    command
+}
+
+impl FromArgMatches for ScaleArgs {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        Ok(Self {
+            command: ScaleCommand::from_arg_matches(matches)?,
+            args: subcommand_to_args(&scale_command(), matches, &|_, value| value),
+        })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+impl Subcommand for ScaleArgs {
+    fn augment_subcommands(command: Command) -> Command {
+        ScaleCommand::augment_subcommands(command)
+    }
+
+    fn augment_subcommands_for_update(command: Command) -> Command {
+        ScaleCommand::augment_subcommands_for_update(command)
+    }
+
+    fn has_subcommand(name: &str) -> bool {
+        ScaleCommand::has_subcommand(name)
+    }
+}
+
+impl Serialize for ScaleArgs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.args
+            .iter()
+            .cloned()
+            .map(ArgValueDto::from_raw)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScaleArgs {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let args = Vec::<ArgValueDto>::deserialize(deserializer)?;
+        Self::from_args(args.iter().map(ArgValueDto::to_raw)).map_err(de::Error::custom)
+    }
 }
 
 impl LiveOptions {
-    pub async fn run(self, app: &mut App<'_>) -> CliResult {
+    pub async fn run(self, app: &mut App<'_>, matches: &ArgMatches) -> CliResult {
+        let dump_config = self.dump_config;
+
+        let options = match self.config_file.clone() {
+            Some(config_file) => self.override_config_file(&config_file, matches)?,
+            None => self,
+        };
+
+        if dump_config {
+            return serde_yaml::to_writer(&mut app.output, &options)
+                .display_err::<CliError>("Could not write config file");
+        }
+
+        options.start(app).await
+    }
+
+    /// Reads the given config file and overrides its options with the options given on the command line.
+    fn override_config_file(self, config_file: &Path, matches: &ArgMatches) -> CliResult<Self> {
+        let mut config: LiveOptions = serde_yaml::from_reader(
+            File::open(config_file).display_err::<CliError>("Could not read config file")?,
+        )
+        .display_err::<CliError>("Could not parse config file")?;
+        config
+            .resolve_paths(config_file.parent().unwrap_or(Path::new("")))
+            .display_err::<CliError>("Invalid session config")?;
+
+        let mut config =
+            serde_yaml::to_value(config).debug_err::<CliError>("Could not serialize config")?;
+        let cli_options =
+            serde_yaml::to_value(self).debug_err::<CliError>("Could not serialize options")?;
+        override_explicit_values(&mut config, cli_options, matches);
+
+        serde_yaml::from_value(config).display_err::<CliError>("Invalid session config")
+    }
+
+    fn resolve_paths(&mut self, base_dir: &Path) -> Result<(), clap::Error> {
+        let resolve = |path: &mut PathBuf| *path = base_dir.join(&*path);
+
+        // `-` refers to the standard output
+        self.ump_out_file
+            .iter_mut()
+            .filter(|ump_out_file| ump_out_file.as_os_str() != "-")
+            .for_each(resolve);
+        self.routing_file.iter_mut().for_each(resolve);
+        self.log_file.iter_mut().for_each(resolve);
+        self.scale_switch_args
+            .playlist_file
+            .iter_mut()
+            .for_each(resolve);
+        self.scale_switch_args
+            .additional_scale_files
+            .iter_mut()
+            .for_each(resolve);
+
+        match &mut self.mode {
+            Some(LiveMode::JustInTime(options)) => options.scale.resolve_paths(base_dir),
+            Some(LiveMode::AheadOfTime(options)) => options.scale.resolve_paths(base_dir),
+            None => Ok(()),
+        }
+    }
+
+    async fn start(self, app: &mut App<'_>) -> CliResult {
+        let (Some(midi_in_device), Some(mode)) = (self.midi_in_device, self.mode) else {
+            return Err("No MIDI input device or no mode (`jit` or `aot`) given"
+                .to_owned()
+                .into());
+        };
+        if self.midi_out_device.is_some() && self.ump_out_file.is_some() {
+            return Err(
+                "A MIDI output device and a UMP output cannot be used at the same time"
+                    .to_owned()
+                    .into(),
+            );
+        }
+
+        let routing = match &self.routing_file {
+            Some(routing_file) => RoutingDto::read(
+                File::open(routing_file).display_err::<CliError>("Could not read routing file")?,
+            )?,
+            None => RoutingDto::default(),
+        };
+        let routing_dir = self
+            .routing_file
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""));
+
+        let mut inputs = vec![(midi_in_device, self.midi_in_args.get_midi_source()?)];
+        let mut input_scales = vec![None];
+        for input in routing.inputs {
+            inputs.push((input.device, input.in_args.get_midi_source()?));
+            input_scales.push(
+                input
+                    .scale
+                    .map(|scale| Scale::from_playlist_item(&scale, routing_dir))
+                    .transpose()?,
+            );
+        }
+
+        let mut outputs = Vec::new();
+        match (self.midi_out_device, &self.ump_out_file) {
+            (Some(midi_out_device), _) => outputs.push((
+                Some(midi_out_device),
+                self.midi_out_args.clone(),
+                Route::default(),
+            )),
+            (None, Some(_)) => outputs.push((None, self.midi_out_args.clone(), Route::default())),
+            (None, None) => {}
+        }
+        for output in routing.outputs {
+            let mut midi_out_args = self.midi_out_args.clone();
+            if let Some(out_channel) = output.out_channel {
+                midi_out_args.out_channel = out_channel;
+            }
+            if let Some(num_out_channels) = output.num_out_channels {
+                midi_out_args.num_out_channels = num_out_channels;
+            }
+            let route = Route {
+                inputs: output.inputs,
+                channels: output.channels,
+                lower_key_bound: output.lo_key.map(Note::midi_number),
+                upper_key_bound: output.up_key.map(Note::midi_number),
+            };
+            outputs.push((Some(output.device), midi_out_args, route));
+        }
+        if outputs.is_empty() {
+            return Err("No MIDI output device given".to_owned().into());
+        }
+        for (midi_out_device, midi_out_args, _) in &outputs {
+            midi_out_args.validate_method(mode.method(), midi_out_device.is_none())?;
+        }
+
         let (midi_send, midi_recv) = flume::unbounded();
         let (status_send, status_recv) = flume::unbounded();
+        let (capture_send, capture_recv) = flume::unbounded();
+        let (log_send, log_recv) = flume::unbounded();
 
-        let handler = move |message| midi_send.send(message).unwrap();
+        let mut log_file = self
+            .log_file
+            .as_ref()
+            .map(|log_file| OpenOptions::new().append(true).create(true).open(log_file))
+            .transpose()
+            .display_err::<CliError>("Could not open log file")?;
+        let monitoring = self.monitor || log_file.is_some();
 
-        let source = self.midi_in_args.get_midi_source()?;
-        let target = self.midi_out_args.get_midi_target(handler)?;
+        let additional_scales = self.scale_switch_args.load_scales()?;
+        if !additional_scales.is_empty() {
+            app.writeln(format_args!(
+                "{} scales available for switching",
+                additional_scales.len() + 1
+            ))?;
+        }
+        let scales = LiveScales::new(
+            iter::once(mode.scale().to_scale(app)?)
+                .chain(additional_scales)
+                .collect(),
+            input_scales,
+        );
 
-        let in_chans = source.channels.clone();
-        let out_chans = target.channels.clone();
+        let mut tuners = Vec::new();
+        let mut out_chans = Vec::new();
+        for (output, (_, midi_out_args, route)) in outputs.iter().enumerate() {
+            let handler = LiveHandler {
+                output,
+                midi_send: midi_send.clone(),
+                status_send: status_send.clone(),
+                capture_send: monitoring.then(|| capture_send.clone()),
+            };
+            let target = midi_out_args.get_midi_target(handler)?;
+            out_chans.push(target.channels.iter().copied().collect());
+            tuners.push(mode.create_tuner(app, target, midi_out_args, route.clone(), &scales)?);
+        }
 
-        match &self.mode {
-            LiveMode::JustInTime(options) => options.run(
-                app,
-                source,
-                target,
-                self.midi_in_device,
-                self.midi_out_args,
-                status_send.clone(),
-            )?,
-            LiveMode::AheadOfTime(options) => options.run(
-                app,
+        let mut router = LiveRouter::new(scales, tuners);
+        if self.scale_switch_args.is_enabled() {
+            let status_send = status_send.clone();
+            router.set_scale_switch(ScaleSwitch {
+                program_change: self.scale_switch_args.switch_via_program_change,
+                controller: self.scale_switch_args.switch_controller,
+                key_range_start: self.scale_switch_args.switch_key_range_start,
+                retune_held_notes: self.scale_switch_args.retune_held_notes,
+                status: Box::new(move |status| {
+                    status_send.send(format!("[Scale] {status}")).unwrap()
+                }),
+            });
+        }
+        let router = Arc::new(Mutex::new(router));
+        let start_time = Instant::now();
+        let mut session_start = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|unix_time| unix_time.as_secs_f64());
+
+        // Messages sent ahead of time are not related to any incoming message
+        capture_recv.drain();
+        let monitor = monitoring.then(|| LiveMonitor {
+            print: self.monitor,
+            status_send: status_send.clone(),
+            log_send: log_file.is_some().then(|| log_send.clone()),
+            capture_recv,
+        });
+
+        // System messages are MIDI 1.0 byte sequences and cannot be passed through to a UMP stream
+        let pass_through = self.ump_out_file.is_none();
+
+        let mut in_chans = Vec::new();
+        for (input, (device, source)) in inputs.into_iter().enumerate() {
+            in_chans.push(source.channels.clone());
+            let midi_in = MidiIn {
+                device,
+                through_send: pass_through.then(|| midi_send.clone()),
+            };
+            let router = router.clone();
+            let status_send = status_send.clone();
+            let monitor = monitor.clone();
+            connect_to_in_device(
+                midi_in,
                 source,
-                target,
-                self.midi_in_device,
-                self.midi_out_args,
-                status_send.clone(),
-            )?,
+                move |message_type, channel, offset| {
+                    let mut router = router.lock().unwrap();
+                    let time = start_time.elapsed();
+                    router.set_time(time);
+                    router.process(input, message_type, channel, offset);
+                    if let Some(monitor) = &monitor {
+                        monitor.report(&router, time, input, message_type, channel, offset);
+                    }
+                },
+                move |status| status_send.send(format!("[MIDI-in] {status}")).unwrap(),
+            );
+        }
+
+        let App { output, error, .. } = app;
+        let mut ump_file;
+
+        let mut out_connections = Vec::new();
+        let status_output: &mut dyn Write = match &self.ump_out_file {
+            // Status messages are redirected to the error output s.t. they do not interfere with the raw UMP data
+            Some(ump_out_file) if ump_out_file.as_os_str() == "-" => {
+                out_connections.push(OutConnection::Stream(&mut **output));
+                &mut **error
+            }
+            Some(ump_out_file) => {
+                writeln!(output, "Writing UMP data to {}", ump_out_file.display())?;
+                ump_file = File::create(ump_out_file)?;
+                out_connections.push(OutConnection::Stream(&mut ump_file));
+                &mut **output
+            }
+            None => &mut **output,
         };
 
-        let (out_device, mut out_connection) =
-            midi::connect_to_out_device("tune-cli", &self.midi_out_device)
-                .debug_err::<CliError>("Could not connect to MIDI output device")?;
+        // Outputs sharing the same device also share the same connection
+        let mut connection_indexes = Vec::new();
+        let mut connected_devices = HashMap::new();
+        for (midi_out_device, _, _) in &outputs {
+            let connection_index = match midi_out_device {
+                Some(midi_out_device) => match connected_devices.get(midi_out_device) {
+                    Some(&connection_index) => connection_index,
+                    None => {
+                        let (out_device, out_connection) =
+                            midi::connect_to_out_device("tune-cli", midi_out_device)
+                                .debug_err::<CliError>("Could not connect to MIDI output device")?;
+                        writeln!(status_output, "Sending MIDI data to {out_device}")?;
+                        out_connections.push(OutConnection::Device(out_connection));
+                        connected_devices.insert(midi_out_device, out_connections.len() - 1);
+                        out_connections.len() - 1
+                    }
+                },
+                None => 0,
+            };
+            connection_indexes.push(connection_index);
+        }
 
-        app.writeln(format_args!("Sending MIDI data to {out_device}"))?;
-        app.writeln(format_args!(
-            "in-channels {{{}}} -> out-channels {{{}}}",
-            in_chans
-                .iter()
-                .map(|c| c.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
-            out_chans
-                .iter()
-                .map(|c| c.to_string())
-                .collect::<Vec<_>>()
-                .join(", ")
-        ))?;
+        writeln!(
+            status_output,
+            "in-channels {} -> out-channels {}",
+            format_channel_sets(&in_chans),
+            format_channel_sets(&out_chans),
+        )?;
 
         futures::join!(
             async {
-                while let Ok(message) = midi_recv.recv_async().await {
-                    message.send_to(|message| out_connection.send(message).unwrap());
+                while let Ok((output, message)) = midi_recv.recv_async().await {
+                    let send = |out_connection: &mut OutConnection| {
+                        if let Err(err) = out_connection.send(&message) {
+                            status_send
+                                .send(format!("[MIDI-out] Could not send message: {err}"))
+                                .unwrap();
+                        }
+                    };
+                    match output {
+                        Some(output) => send(&mut out_connections[connection_indexes[output]]),
+                        None => out_connections.iter_mut().for_each(send),
+                    }
                 }
             },
             async {
                 while let Ok(status) = status_recv.recv_async().await {
-                    app.writeln(status).unwrap();
+                    writeln!(status_output, "{status}").unwrap();
+                }
+            },
+            async {
+                while let Ok(mut log_entry) = log_recv.recv_async().await {
+                    if let Some(log_file) = &mut log_file {
+                        log_entry.session_start = session_start.take();
+                        // Each entry is appended as an item of a top-level YAML sequence
+                        let yaml = serde_yaml::to_string(&[log_entry]).unwrap();
+                        log_file
+                            .write_all(yaml.trim_start_matches("---\n").as_bytes())
+                            .unwrap();
+                    }
                 }
             }
         );
@@ -160,138 +671,1462 @@ impl LiveOptions {
     }
 }
 
-impl JustInTimeOptions {
-    fn run(
+fn format_channel_sets(channel_sets: &[BTreeSet<u8>]) -> String {
+    channel_sets
+        .iter()
+        .map(|channels| {
+            format!(
+                "{{{}}}",
+                channels
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Replaces the values of `config` with the values of `cli_options` that have been given on the command line.
+///
+/// The values are identified by their field name which is also the ID of the corresponding command line argument.
+/// A mode given on the command line replaces the mode of the config.
+fn override_explicit_values(config: &mut Value, cli_options: Value, matches: &ArgMatches) {
+    let (Value::Mapping(config), Value::Mapping(cli_options)) = (config, cli_options) else {
+        return;
+    };
+
+    for (key, cli_value) in cli_options {
+        let Some(id) = key.as_str() else {
+            continue;
+        };
+        let is_explicit = match id {
+            "mode" => matches.subcommand().is_some(),
+            _ if cli_value.is_mapping() => {
+                if let Some(config_value) = config.get_mut(&key) {
+                    override_explicit_values(config_value, cli_value, matches);
+                }
+                continue;
+            }
+            _ => matches.value_source(id) == Some(ValueSource::CommandLine),
+        };
+        if is_explicit {
+            config.insert(key, cli_value);
+        }
+    }
+}
+
+/// Reconstructs the command line arguments of a subcommand given on the command line.
+///
+/// Each value is passed through `map_value` which can be used to rewrite, e.g., file locations.
+fn subcommand_to_args(
+    command: &Command,
+    matches: &ArgMatches,
+    map_value: &dyn Fn(&Arg, String) -> String,
+) -> Vec<String> {
+    let mut args = Vec::new();
+
+    let explicit_args = command.get_arguments().filter(|arg| {
+        matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+    });
+    let (positionals, options): (Vec<_>, Vec<_>) =
+        explicit_args.partition(|arg| arg.is_positional());
+
+    for arg in options {
+        let (Some(long), Some(occurrences)) = (
+            arg.get_long(),
+            matches.get_raw_occurrences(arg.get_id().as_str()),
+        ) else {
+            continue;
+        };
+        if is_flag(arg) {
+            args.push(format!("--{long}"));
+            continue;
+        }
+        for values in occurrences {
+            let values = values
+                .map(|value| map_value(arg, value.to_string_lossy().into_owned()))
+                .collect::<Vec<_>>();
+            push_occurrence(&mut args, long, values);
+        }
+    }
+
+    for arg in positionals {
+        if let Some(values) = matches.get_raw(arg.get_id().as_str()) {
+            args.extend(values.map(|value| map_value(arg, value.to_string_lossy().into_owned())));
+        }
+    }
+
+    if let Some((name, sub_matches)) = matches.subcommand() {
+        args.push(name.to_owned());
+        args.extend(subcommand_to_args(
+            command.find_subcommand(name).unwrap(),
+            sub_matches,
+            map_value,
+        ));
+    }
+
+    args
+}
+
+fn push_occurrence(args: &mut Vec<String>, long: &str, values: Vec<String>) {
+    // The `--long=value` syntax allows for values starting with a hyphen, e.g. negative numbers
+    match <[_; 1]>::try_from(values) {
+        Ok([value]) => args.push(format!("--{long}={value}")),
+        Err(values) => {
+            args.push(format!("--{long}"));
+            args.extend(values);
+        }
+    }
+}
+
+fn is_flag(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::SetTrue)
+}
+
+struct MidiIn {
+    device: String,
+    /// Receives all non-channel messages, e.g. SysEx or clock, which are passed through unmodified to all outputs.
+    through_send: Option<Sender<(Option<usize>, Vec<u8>)>>,
+}
+
+struct LiveHandler {
+    output: usize,
+    midi_send: Sender<(Option<usize>, Vec<u8>)>,
+    status_send: Sender<String>,
+    /// Receives a copy of all emitted messages if the session is monitored.
+    capture_send: Option<Sender<(usize, Vec<u8>)>>,
+}
+
+impl MidiTunerMessageHandler for LiveHandler {
+    fn handle(&mut self, message: MidiTunerMessage) {
+        message.send_to(|message| {
+            self.midi_send
+                .send((Some(self.output), message.to_vec()))
+                .unwrap();
+            if let Some(capture_send) = &self.capture_send {
+                capture_send.send((self.output, message.to_vec())).unwrap();
+            }
+        });
+    }
+
+    fn handle_warning(&mut self, warning: MidiTunerWarning) {
+        self.status_send
+            .send(format!("[MIDI-out] Warning: {warning}"))
+            .unwrap();
+    }
+}
+
+/// Reports each incoming channel message together with the MIDI messages emitted in response.
+#[derive(Clone)]
+struct LiveMonitor {
+    print: bool,
+    status_send: Sender<String>,
+    log_send: Option<Sender<LiveLogEntryDto>>,
+    capture_recv: Receiver<(usize, Vec<u8>)>,
+}
+
+impl LiveMonitor {
+    fn report<H: MidiTunerMessageHandler>(
+        &self,
+        router: &LiveRouter<H>,
+        time: Duration,
+        input: usize,
+        message_type: ChannelMessageType,
+        channel: u8,
+        offset: MultiChannelOffset,
+    ) {
+        let Some(message) = message_type.in_channel(channel) else {
+            return;
+        };
+
+        let note = match message_type {
+            ChannelMessageType::NoteOn { key, velocity } if velocity > 0 => {
+                let piano_key = offset.get_piano_key(key);
+                router
+                    .scale_position(input, piano_key)
+                    .map(|(scale_index, pitch)| {
+                        let approximation = pitch.find_in_tuning(());
+                        LiveLogNoteDto {
+                            key_midi_number: piano_key.midi_number(),
+                            scale_index,
+                            pitch_in_hz: pitch.as_hz(),
+                            nearest_midi_number: approximation.approx_value.midi_number(),
+                            deviation_in_cents: approximation.deviation.as_cents(),
+                        }
+                    })
+            }
+            _ => None,
+        };
+
+        let mut outputs = Vec::<LiveLogOutputDto>::new();
+        for (output, emitted_message) in self.capture_recv.drain() {
+            let output_index = match outputs.iter().position(|dto| dto.output == output) {
+                Some(output_index) => output_index,
+                None => {
+                    outputs.push(LiveLogOutputDto {
+                        output,
+                        channel: None,
+                        messages: Vec::new(),
+                    });
+                    outputs.len() - 1
+                }
+            };
+            let output_dto = &mut outputs[output_index];
+            if note.is_some() {
+                output_dto.channel = output_dto
+                    .channel
+                    .or_else(|| note_on_channel(&emitted_message));
+            }
+            output_dto.messages.push(format_hex(&emitted_message));
+        }
+
+        let log_entry = LiveLogEntryDto {
+            session_start: None,
+            time: time.as_secs_f64(),
+            input,
+            message: format_hex(&message.to_raw_message()),
+            note,
+            outputs,
+        };
+
+        if self.print {
+            self.status_send
+                .send(format!("[Monitor] {}", format_log_entry(&log_entry)))
+                .unwrap();
+        }
+        if let Some(log_send) = &self.log_send {
+            log_send.send(log_entry).unwrap();
+        }
+    }
+}
+
+fn format_log_entry(log_entry: &LiveLogEntryDto) -> String {
+    let mut formatted = format!(
+        "{:>9.3}s | in {} [{}]",
+        log_entry.time, log_entry.input, log_entry.message
+    );
+    if let Some(note) = &log_entry.note {
+        formatted.push_str(&format!(
+            " | key {} IDX {} | {:.3} Hz = {} {:+.1}¢",
+            note.key_midi_number,
+            note.scale_index,
+            note.pitch_in_hz,
+            Note::from_midi_number(note.nearest_midi_number),
+            note.deviation_in_cents,
+        ));
+    }
+    for output in &log_entry.outputs {
+        formatted.push_str(&format!(" | out {}", output.output));
+        if let Some(channel) = output.channel {
+            formatted.push_str(&format!(" ch {channel}"));
+        }
+        for message in &output.messages {
+            formatted.push_str(&format!(" [{message}]"));
+        }
+    }
+    formatted
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the channel of a MIDI 1.0 Note On message or a Note On Universal MIDI Packet.
+fn note_on_channel(message: &[u8]) -> Option<u8> {
+    let status = match *message {
+        [status @ 0x80..=0xff, ..] => status,
+        [message_type_and_group, status, ..]
+            if matches!(message_type_and_group >> 4, 0x2 | 0x4) =>
+        {
+            status
+        }
+        _ => return None,
+    };
+    (status >> 4 == NOTE_ON).then_some(status & 0x0f)
+}
+
+enum OutConnection<'a> {
+    Device(MidiOutputConnection),
+    Stream(&'a mut dyn Write),
+}
+
+impl OutConnection<'_> {
+    fn send(&mut self, message: &[u8]) -> Result<(), String> {
+        match self {
+            OutConnection::Device(connection) => {
+                connection.send(message).map_err(|err| err.to_string())
+            }
+            OutConnection::Stream(stream) => stream
+                .write_all(message)
+                .and_then(|()| stream.flush())
+                .map_err(|err| err.to_string()),
+        }
+    }
+}
+
+impl ScaleSwitchArgs {
+    fn load_scales(&self) -> CliResult<Vec<Scale>> {
+        let mut scales = match &self.playlist_file {
+            Some(playlist_file) => Scale::from_playlist_file(playlist_file)?,
+            None => Vec::new(),
+        };
+        for scale_files in self.additional_scale_files.chunks(2) {
+            scales.push(Scale::from_scl_and_kbm_files(
+                &scale_files[0],
+                &scale_files[1],
+            )?);
+        }
+        Ok(scales)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.switch_via_program_change
+            || self.switch_controller.is_some()
+            || self.switch_key_range_start.is_some()
+    }
+}
+
+impl LiveMode {
+    pub fn method(&self) -> TuningMethod {
+        match self {
+            LiveMode::JustInTime(options) => options.method,
+            LiveMode::AheadOfTime(options) => options.method,
+        }
+    }
+
+    pub fn scale(&self) -> &ScaleCommand {
+        match self {
+            LiveMode::JustInTime(options) => &options.scale.command,
+            LiveMode::AheadOfTime(options) => &options.scale.command,
+        }
+    }
+
+    /// Creates the tuner of a single output. The output receives all messages accepted by the given `route`.
+    pub fn create_tuner<H: MidiTunerMessageHandler>(
         &self,
         app: &mut App,
-        source: MidiSource,
-        target: MidiTarget<impl MidiTunerMessageHandler + Send + 'static>,
-        midi_in_device: String,
-        midi_out_args: MidiOutArgs,
-        status_send: Sender<String>,
-    ) -> CliResult<()> {
-        let tuning = self.scale.to_scale(app)?.tuning;
+        target: MidiTarget<H>,
+        midi_out_args: &MidiOutArgs,
+        route: Route,
+        scales: &LiveScales,
+    ) -> CliResult<LiveTuner<H>> {
+        match self {
+            LiveMode::JustInTime(options) => Ok(options.create_tuner(target, midi_out_args, route)),
+            LiveMode::AheadOfTime(options) => {
+                options.create_tuner(app, target, midi_out_args, route, scales)
+            }
+        }
+    }
+}
 
+impl JustInTimeOptions {
+    fn create_tuner<H: MidiTunerMessageHandler>(
+        &self,
+        target: MidiTarget<H>,
+        midi_out_args: &MidiOutArgs,
+        route: Route,
+    ) -> LiveTuner<H> {
         let synth = midi_out_args.create_synth(target, self.method);
-        let mut tuner = JitTuner::start(synth, self.clash_mitigation);
-
-        connect_to_in_device(
-            midi_in_device,
-            source,
-            move |message_type, offset| match message_type {
-                ChannelMessageType::NoteOff { key, velocity }
-                | ChannelMessageType::NoteOn {
-                    key,
-                    velocity: velocity @ 0,
-                } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_off(piano_key, velocity);
-                }
-                ChannelMessageType::NoteOn { key, velocity } => {
-                    let piano_key = offset.get_piano_key(key);
-                    if let Some(pitch) = tuning.maybe_pitch_of(piano_key) {
-                        tuner.note_on(piano_key, pitch, velocity);
-                    }
-                }
-                ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_attr(piano_key, pressure);
-                }
-                message_type @ (ChannelMessageType::ControlChange { .. }
-                | ChannelMessageType::ProgramChange { .. }
-                | ChannelMessageType::ChannelPressure { .. }
-                | ChannelMessageType::PitchBendChange { .. }) => {
-                    tuner.global_attr(message_type);
-                }
-            },
-            move |status| status_send.send(format!("[MIDI-in] {status}")).unwrap(),
-        );
+        let policy = self.allocation_args.to_allocation_policy();
 
-        Ok(())
+        let tuner = match self.adaptive_strategy {
+            Some(strategy) => JitTuner::start_adaptive(
+                synth,
+                policy,
+                AdaptiveOptions {
+                    strategy,
+                    odd_limit: self.adaptive_odd_limit,
+                    max_deviation: self.adaptive_max_deviation,
+                },
+            ),
+            None => JitTuner::start(synth, policy),
+        };
+
+        LiveTuner::new(LiveTunerKind::Jit(tuner), route, self.method)
     }
 }
 
 impl AheadOfTimeOptions {
-    fn run(
+    fn create_tuner<H: MidiTunerMessageHandler>(
         &self,
         app: &mut App,
-        source: MidiSource,
-        target: MidiTarget<impl MidiTunerMessageHandler + Send + 'static>,
-        midi_in_device: String,
-        midi_out_args: MidiOutArgs,
-        status_send: Sender<String>,
-    ) -> CliResult<()> {
-        let scale = self.scale.to_scale(app)?;
-
+        target: MidiTarget<H>,
+        midi_out_args: &MidiOutArgs,
+        route: Route,
+        scales: &LiveScales,
+    ) -> CliResult<LiveTuner<H>> {
         let synth = midi_out_args.create_synth(target, self.method);
+        let optimization = self.optimize.then_some(AotOptimization {
+            tolerance: self.optimization_tolerance,
+            max_detuning: self.optimization_max_detuning,
+        });
+        let available_channels = midi_out_args.num_out_channels;
+
+        // Make sure that switching scales at runtime cannot fail
+        for scale_index in 1..scales.num_scales() {
+            let selection = scales.selection(scale_index);
+            let keys = selection.keys().filter(|&key| route.accepts_key(key));
+            let (model, _) = match &optimization {
+                Some(optimization) => AotTuningModel::apply_optimized_tuning(
+                    synth.group_by(),
+                    selection,
+                    keys,
+                    optimization,
+                ),
+                None => AotTuningModel::apply_tuning(synth.group_by(), selection, keys),
+            };
+            let required_channels = model.num_channels();
+            if required_channels > usize::from(available_channels) {
+                return Err(format!(
+                    "Scale {scale_index} requires {required_channels} MIDI channels but only {available_channels} MIDI channels are available",
+                )
+                .into());
+            }
+            app.writeln(format_args!(
+                "Scale {scale_index} requires {required_channels} MIDI channels"
+            ))?
+        }
+
         let mut tuner = AotTuner::start(synth);
 
-        let required_channels = tuner.set_tuning(&*scale.tuning, scale.keys).unwrap();
+        let required_channels = set_aot_tuning(&mut tuner, scales, &route, optimization.as_ref());
         if tuner.tuned() {
             app.writeln(format_args!(
                 "Tuning requires {required_channels} MIDI channels"
             ))?
         } else {
-            let available_channels = midi_out_args.num_out_channels;
             return Err(format!(
                 "Tuning requires {required_channels} MIDI channels but only {available_channels} MIDI channels are available",
             )
             .into());
         }
 
-        connect_to_in_device(
-            midi_in_device,
-            source,
-            move |message_type, offset| match message_type {
-                ChannelMessageType::NoteOff { key, velocity }
-                | ChannelMessageType::NoteOn {
-                    key,
-                    velocity: velocity @ 0,
-                } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_off(piano_key, velocity);
+        Ok(LiveTuner::new(
+            LiveTunerKind::Aot {
+                tuner,
+                optimization,
+            },
+            route,
+            self.method,
+        ))
+    }
+}
+
+fn set_aot_tuning<H: MidiTunerMessageHandler>(
+    tuner: &mut AotTuner<SourceKey, TunableMidi<H>>,
+    scales: &LiveScales,
+    route: &Route,
+    optimization: Option<&AotOptimization>,
+) -> usize {
+    let selection = scales.current();
+    let keys = selection.keys().filter(|&key| route.accepts_key(key));
+    match optimization {
+        Some(optimization) => tuner.set_optimized_tuning(selection, keys, optimization),
+        None => tuner.set_tuning(selection, keys),
+    }
+    .unwrap()
+}
+
+/// Identifies a key of one of the merged MIDI inputs.
+///
+/// Keys of different inputs are tracked separately even if they share the same [`PianoKey`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct SourceKey {
+    input: usize,
+    key: PianoKey,
+}
+
+/// Describes which messages of the merged MIDI inputs are sent to an output.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Route {
+    inputs: Option<Vec<usize>>,
+    channels: Option<Vec<u8>>,
+    lower_key_bound: Option<i32>,
+    upper_key_bound: Option<i32>,
+}
+
+impl Route {
+    fn accepts(&self, input: usize, channel: u8) -> bool {
+        self.inputs
+            .as_ref()
+            .is_none_or(|inputs| inputs.contains(&input))
+            && self
+                .channels
+                .as_ref()
+                .is_none_or(|channels| channels.contains(&channel))
+    }
+
+    fn accepts_key(&self, source_key: SourceKey) -> bool {
+        let midi_number = source_key.key.midi_number();
+        self.inputs
+            .as_ref()
+            .is_none_or(|inputs| inputs.contains(&source_key.input))
+            && self
+                .lower_key_bound
+                .is_none_or(|lower_key_bound| midi_number >= lower_key_bound)
+            && self
+                .upper_key_bound
+                .is_none_or(|upper_key_bound| midi_number < upper_key_bound)
+    }
+}
+
+/// The scales of the merged MIDI inputs.
+pub(crate) struct LiveScales {
+    scales: Vec<Scale>,
+    current_scale: usize,
+    input_scales: Vec<Option<Scale>>,
+}
+
+impl LiveScales {
+    /// Creates a new [`LiveScales`] instance where `scales` are the scales that can be selected at runtime.
+    ///
+    /// Inputs with an `input_scales` entry of [`None`] follow the selected scale.
+    pub fn new(scales: Vec<Scale>, input_scales: Vec<Option<Scale>>) -> Self {
+        Self {
+            scales,
+            current_scale: 0,
+            input_scales,
+        }
+    }
+
+    fn num_scales(&self) -> usize {
+        self.scales.len()
+    }
+
+    fn current(&self) -> ScaleSelection<'_> {
+        self.selection(self.current_scale)
+    }
+
+    fn selection(&self, scale_index: usize) -> ScaleSelection<'_> {
+        ScaleSelection {
+            scales: self,
+            scale_index,
+        }
+    }
+}
+
+/// The scales of all inputs given that the scale with index `scale_index` is selected.
+#[derive(Clone, Copy)]
+struct ScaleSelection<'a> {
+    scales: &'a LiveScales,
+    scale_index: usize,
+}
+
+impl<'a> ScaleSelection<'a> {
+    fn scale_of(&self, input: usize) -> &'a Scale {
+        match self.scales.input_scales.get(input) {
+            Some(Some(input_scale)) => input_scale,
+            _ => &self.scales.scales[self.scale_index],
+        }
+    }
+
+    fn keys(self) -> impl Iterator<Item = SourceKey> + 'a {
+        (0..self.scales.input_scales.len()).flat_map(move |input| {
+            self.scale_of(input)
+                .keys
+                .iter()
+                .map(move |&key| SourceKey { input, key })
+        })
+    }
+}
+
+impl KeyboardMapping<SourceKey> for ScaleSelection<'_> {
+    fn maybe_pitch_of(&self, source_key: SourceKey) -> Option<Pitch> {
+        self.scale_of(source_key.input)
+            .tuning
+            .maybe_pitch_of(source_key.key)
+    }
+}
+
+/// Routes incoming channel messages of the merged MIDI inputs to the [`LiveTuner`]s of the outputs.
+pub(crate) struct LiveRouter<H> {
+    scales: LiveScales,
+    tuners: Vec<LiveTuner<H>>,
+    scale_switch: Option<ScaleSwitch>,
+}
+
+impl<H: MidiTunerMessageHandler> LiveRouter<H> {
+    pub fn new(scales: LiveScales, tuners: Vec<LiveTuner<H>>) -> Self {
+        Self {
+            scales,
+            tuners,
+            scale_switch: None,
+        }
+    }
+
+    pub fn set_scale_switch(&mut self, scale_switch: ScaleSwitch) {
+        self.scale_switch = Some(scale_switch);
+    }
+
+    /// Updates the current time which is relevant for the release time of just-in-time channel allocation.
+    pub fn set_time(&mut self, time: Duration) {
+        for tuner in &mut self.tuners {
+            tuner.set_time(time);
+        }
+    }
+
+    pub fn process(
+        &mut self,
+        input: usize,
+        message_type: ChannelMessageType,
+        channel: u8,
+        offset: MultiChannelOffset,
+    ) {
+        if let Some(scale_switch) = &self.scale_switch {
+            match scale_switch.intercept(message_type, &offset, self.scales.num_scales()) {
+                Some(SwitchCommand::Select(scale_index)) => return self.switch_scale(scale_index),
+                Some(SwitchCommand::Ignore) => return,
+                None => {}
+            }
+        }
+
+        let source_key = match message_type {
+            ChannelMessageType::NoteOff { key, .. }
+            | ChannelMessageType::NoteOn { key, .. }
+            | ChannelMessageType::PolyphonicKeyPressure { key, .. } => Some(SourceKey {
+                input,
+                key: offset.get_piano_key(key),
+            }),
+            _ => None,
+        };
+
+        for tuner in &mut self.tuners {
+            if tuner.route.accepts(input, channel)
+                && source_key.is_none_or(|source_key| tuner.route.accepts_key(source_key))
+            {
+                tuner.process(&self.scales, input, message_type, channel, offset);
+            }
+        }
+    }
+
+    /// Returns the index of `key` relative to the origin of the selected scale and its pitch.
+    fn scale_position(&self, input: usize, key: PianoKey) -> Option<(i32, Pitch)> {
+        let scale = self.scales.current().scale_of(input);
+        let pitch = scale.tuning.maybe_pitch_of(key)?;
+        Some((scale.origin.num_keys_before(key), pitch))
+    }
+
+    fn switch_scale(&mut self, scale_index: usize) {
+        let Some(scale_switch) = &mut self.scale_switch else {
+            return;
+        };
+        if scale_index >= self.scales.num_scales() {
+            (scale_switch.status)(format!("Scale {scale_index} is not available"));
+            return;
+        }
+
+        self.scales.current_scale = scale_index;
+        for tuner in &mut self.tuners {
+            tuner.update_tuning(&self.scales, scale_switch.retune_held_notes);
+        }
+
+        (scale_switch.status)(format!("Switched to scale {scale_index}"));
+    }
+}
+
+/// Sends incoming channel messages of a single output to a [`JitTuner`] or an [`AotTuner`].
+pub(crate) struct LiveTuner<H> {
+    tuner: LiveTunerKind<H>,
+    route: Route,
+    method: TuningMethod,
+    note_channels: NoteChannels,
+    control_decoders: HashMap<usize, ControlChangeDecoder>,
+    pending_selections: HashMap<(usize, u8), Vec<(u8, u8)>>,
+}
+
+enum LiveTunerKind<H> {
+    Jit(JitTuner<SourceKey, TunableMidi<H>>),
+    Aot {
+        tuner: AotTuner<SourceKey, TunableMidi<H>>,
+        optimization: Option<AotOptimization>,
+    },
+}
+
+/// Selects the current scale in response to incoming channel messages.
+pub(crate) struct ScaleSwitch {
+    program_change: bool,
+    controller: Option<u8>,
+    key_range_start: Option<Note>,
+    retune_held_notes: bool,
+    status: Box<dyn FnMut(String) + Send>,
+}
+
+enum SwitchCommand {
+    Select(usize),
+    Ignore,
+}
+
+impl ScaleSwitch {
+    /// Returns a [`SwitchCommand`] if the given message is reserved for switching scales.
+    fn intercept(
+        &self,
+        message_type: ChannelMessageType,
+        offset: &MultiChannelOffset,
+        num_scales: usize,
+    ) -> Option<SwitchCommand> {
+        let switch_key_index = |key| {
+            let key_range_start = self.key_range_start?;
+            let index = offset.get_piano_key(key).midi_number() - key_range_start.midi_number();
+            usize::try_from(index)
+                .ok()
+                .filter(|&index| index < num_scales)
+        };
+
+        match message_type {
+            ChannelMessageType::NoteOff { key, .. }
+            | ChannelMessageType::NoteOn {
+                key, velocity: 0, ..
+            } => switch_key_index(key).map(|_| SwitchCommand::Ignore),
+            ChannelMessageType::NoteOn { key, .. } => {
+                switch_key_index(key).map(SwitchCommand::Select)
+            }
+            ChannelMessageType::ProgramChange { program } if self.program_change => {
+                Some(SwitchCommand::Select(program.into()))
+            }
+            ChannelMessageType::ControlChange { controller, value }
+                if self.controller == Some(controller) =>
+            {
+                Some(SwitchCommand::Select(value.into()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<H: MidiTunerMessageHandler> LiveTuner<H> {
+    fn new(tuner: LiveTunerKind<H>, route: Route, method: TuningMethod) -> Self {
+        Self {
+            tuner,
+            route,
+            method,
+            note_channels: NoteChannels::new(method),
+            control_decoders: HashMap::new(),
+            pending_selections: HashMap::new(),
+        }
+    }
+
+    fn set_time(&mut self, time: Duration) {
+        if let LiveTunerKind::Jit(tuner) = &mut self.tuner {
+            tuner.set_time(time);
+        }
+    }
+
+    fn process(
+        &mut self,
+        scales: &LiveScales,
+        input: usize,
+        message_type: ChannelMessageType,
+        channel: u8,
+        offset: MultiChannelOffset,
+    ) {
+        let source_key = |key| SourceKey {
+            input,
+            key: offset.get_piano_key(key),
+        };
+
+        match message_type {
+            ChannelMessageType::NoteOff { key, velocity }
+            | ChannelMessageType::NoteOn {
+                key,
+                velocity: velocity @ 0,
+            } => {
+                let source_key = source_key(key);
+                self.note_channels.note_off(input, channel, source_key);
+                match &mut self.tuner {
+                    LiveTunerKind::Jit(tuner) => tuner.note_off(source_key, velocity),
+                    LiveTunerKind::Aot { tuner, .. } => tuner.note_off(source_key, velocity),
                 }
-                ChannelMessageType::NoteOn { key, velocity } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_on(piano_key, velocity);
+            }
+            ChannelMessageType::NoteOn { key, velocity } => {
+                let source_key = source_key(key);
+                match &mut self.tuner {
+                    LiveTunerKind::Jit(tuner) => {
+                        if let Some(pitch) = scales.current().maybe_pitch_of(source_key) {
+                            let pending = self.note_channels.note_on(input, channel, source_key);
+                            tuner.note_on(source_key, pitch, velocity);
+                            for message_type in pending {
+                                tuner.note_channel_attr(source_key, message_type);
+                            }
+                        }
+                    }
+                    LiveTunerKind::Aot { tuner, .. } => {
+                        let pending = self.note_channels.note_on(input, channel, source_key);
+                        tuner.note_on(source_key, velocity);
+                        for message_type in pending {
+                            tuner.note_channel_attr(source_key, message_type);
+                        }
+                    }
                 }
-                ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_attr(piano_key, pressure);
+            }
+            ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
+                let source_key = source_key(key);
+                match &mut self.tuner {
+                    LiveTunerKind::Jit(tuner) => tuner.note_attr(source_key, pressure),
+                    LiveTunerKind::Aot { tuner, .. } => tuner.note_attr(source_key, pressure),
+                }
+            }
+            message_type @ (ChannelMessageType::ControlChange { .. }
+            | ChannelMessageType::ProgramChange { .. }
+            | ChannelMessageType::ChannelPressure { .. }
+            | ChannelMessageType::PitchBendChange { .. }) => {
+                for message_type in self.filter_parameter_changes(input, message_type, channel) {
+                    self.channel_attr(input, message_type, channel);
                 }
-                message_type @ (ChannelMessageType::ControlChange { .. }
-                | ChannelMessageType::ProgramChange { .. }
-                | ChannelMessageType::ChannelPressure { .. }
-                | ChannelMessageType::PitchBendChange { .. }) => {
+            }
+        }
+    }
+
+    fn channel_attr(&mut self, input: usize, message_type: ChannelMessageType, channel: u8) {
+        if self
+            .note_channels
+            .defer_expression(input, channel, message_type)
+        {
+            return;
+        }
+        let affected_keys = self
+            .note_channels
+            .per_note_keys(input, channel, message_type);
+        match &mut self.tuner {
+            LiveTunerKind::Jit(tuner) => {
+                if affected_keys.is_empty() {
                     tuner.global_attr(message_type);
                 }
-            },
-            move |status| status_send.send(format!("[MIDI-in] {status}")).unwrap(),
-        );
+                for &source_key in affected_keys {
+                    tuner.note_channel_attr(source_key, message_type);
+                }
+            }
+            LiveTunerKind::Aot { tuner, .. } => {
+                if affected_keys.is_empty() {
+                    tuner.global_attr(message_type);
+                }
+                for &source_key in affected_keys {
+                    tuner.note_channel_attr(source_key, message_type);
+                }
+            }
+        }
+    }
 
-        Ok(())
+    /// Removes messages that change a parameter controlled by the tuning method, e.g. the pitch-bend range in `pitch-bend` mode.
+    ///
+    /// Such messages must not be forwarded since they would break the tuning of the output.
+    /// Parameter selection messages are held back until the selected parameter is known s.t. the output's parameter pointer never lands on a reserved parameter.
+    fn filter_parameter_changes(
+        &mut self,
+        input: usize,
+        message_type: ChannelMessageType,
+        channel: u8,
+    ) -> Vec<ChannelMessageType> {
+        let Some(message) = message_type.in_channel(channel) else {
+            return vec![message_type];
+        };
+
+        let decoder = self.control_decoders.entry(input).or_default();
+        let event = decoder.decode(message);
+
+        if let ChannelMessageType::ControlChange {
+            controller: controller @ (RPN_MSB | RPN_LSB | NRPN_MSB | NRPN_LSB),
+            value,
+        } = message_type
+        {
+            // Only the latest value of each parameter number byte is relevant
+            let pending = self.pending_selections.entry((input, channel)).or_default();
+            pending.retain(|&(pending_controller, _)| pending_controller != controller);
+            pending.push((controller, value));
+
+            return match decoder.selected_parameter(channel) {
+                Some(parameter) if !self.method.reserves_parameter(parameter) => pending
+                    .drain(..)
+                    .map(|(controller, value)| ChannelMessageType::ControlChange {
+                        controller,
+                        value,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+        }
+
+        match event {
+            Some(ControlEvent {
+                event_type: ControlEventType::Parameter { parameter, .. },
+                ..
+            }) if self.method.reserves_parameter(parameter) => Vec::new(),
+            _ => vec![message_type],
+        }
     }
+
+    /// Applies the currently selected scale.
+    fn update_tuning(&mut self, scales: &LiveScales, retune_held_notes: bool) {
+        match &mut self.tuner {
+            LiveTunerKind::Jit(tuner) => {
+                if retune_held_notes {
+                    for source_key in self.note_channels.all_keys() {
+                        if let Some(pitch) = scales.current().maybe_pitch_of(source_key) {
+                            tuner.note_pitch(source_key, pitch);
+                        }
+                    }
+                }
+            }
+            LiveTunerKind::Aot {
+                tuner,
+                optimization,
+            } => {
+                set_aot_tuning(tuner, scales, &self.route, optimization.as_ref());
+            }
+        }
+    }
+}
+
+/// Tracks the input and input channel of each active key s.t. MPE per-note expression can be forwarded to the output channel of the affected note.
+///
+/// Per-note expression received on a member channel without active keys is deferred until the next note on that channel is started.
+struct NoteChannels {
+    per_note_expression: bool,
+    active_keys: HashMap<(usize, u8), Vec<SourceKey>>,
+    pending_expression: HashMap<(usize, u8), Vec<ChannelMessageType>>,
 }
 
+impl NoteChannels {
+    fn new(method: TuningMethod) -> Self {
+        Self {
+            per_note_expression: method == TuningMethod::Mpe,
+            active_keys: HashMap::new(),
+            pending_expression: HashMap::new(),
+        }
+    }
+
+    /// Returns the per-note expression that was received on the given channel before the note was started.
+    fn note_on(
+        &mut self,
+        input: usize,
+        channel: u8,
+        source_key: SourceKey,
+    ) -> Vec<ChannelMessageType> {
+        self.active_keys
+            .entry((input, channel))
+            .or_default()
+            .push(source_key);
+        self.pending_expression
+            .remove(&(input, channel))
+            .unwrap_or_default()
+    }
+
+    fn note_off(&mut self, input: usize, channel: u8, source_key: SourceKey) {
+        if let Some(keys) = self.active_keys.get_mut(&(input, channel)) {
+            keys.retain(|&key| key != source_key);
+        }
+    }
+
+    fn all_keys(&self) -> impl Iterator<Item = SourceKey> + '_ {
+        self.active_keys.values().flatten().copied()
+    }
+
+    /// Defers the given message if it is per-note expression received on a member channel without active keys.
+    ///
+    /// Only the latest message of each kind is kept. Messages on the MPE manager channels (0 and 15) are never deferred.
+    fn defer_expression(
+        &mut self,
+        input: usize,
+        channel: u8,
+        message_type: ChannelMessageType,
+    ) -> bool {
+        let is_deferred = self.per_note_expression
+            && is_per_note_expression(message_type)
+            && !MPE_MANAGER_CHANNELS.contains(&channel)
+            && self
+                .active_keys
+                .get(&(input, channel))
+                .is_none_or(|keys| keys.is_empty());
+
+        if is_deferred {
+            let pending = self.pending_expression.entry((input, channel)).or_default();
+            pending
+                .retain(|pending| mem::discriminant(pending) != mem::discriminant(&message_type));
+            pending.push(message_type);
+        }
+
+        is_deferred
+    }
+
+    fn per_note_keys(
+        &self,
+        input: usize,
+        channel: u8,
+        message_type: ChannelMessageType,
+    ) -> &[SourceKey] {
+        match self.active_keys.get(&(input, channel)) {
+            Some(keys) if self.per_note_expression && is_per_note_expression(message_type) => keys,
+            _ => &[],
+        }
+    }
+}
+
+fn is_per_note_expression(message_type: ChannelMessageType) -> bool {
+    matches!(
+        message_type,
+        ChannelMessageType::PitchBendChange { .. }
+            | ChannelMessageType::ChannelPressure { .. }
+            | ChannelMessageType::ControlChange {
+                controller: MPE_SLIDE_CONTROLLER,
+                ..
+            }
+    )
+}
+
+const MPE_MANAGER_CHANNELS: [u8; 2] = [0, 15];
+const MPE_SLIDE_CONTROLLER: u8 = 74;
+
 fn connect_to_in_device(
-    port_name: String,
+    midi_in: MidiIn,
     source: MidiSource,
-    mut callback: impl FnMut(ChannelMessageType, MultiChannelOffset) + Send + 'static,
+    mut callback: impl FnMut(ChannelMessageType, u8, MultiChannelOffset) + Send + 'static,
     status: impl FnMut(String) + Send + 'static,
 ) {
+    let mut parser = MidiStreamParser::new();
+
     midi::start_in_connect_loop(
         "tune-cli".to_owned(),
-        port_name,
+        midi_in.device,
         move |raw_message| {
-            if let Some(parsed_message) = ChannelMessage::from_raw_message(raw_message)
-                && source.channels.contains(&parsed_message.channel())
-            {
-                callback(
-                    parsed_message.message_type(),
-                    source.get_offset(parsed_message.channel()),
-                );
+            for message in parser.parse(raw_message) {
+                match message {
+                    MidiMessage::Channel(parsed_message) => {
+                        if source.channels.contains(&parsed_message.channel()) {
+                            callback(
+                                parsed_message.message_type(),
+                                parsed_message.channel(),
+                                source.get_offset(parsed_message.channel()),
+                            );
+                        }
+                    }
+                    other_message => {
+                        if let Some(through_send) = &midi_in.through_send {
+                            // The output side may have shut down already. Pass-through messages can be dropped then.
+                            let _ = through_send.send((None, other_message.to_raw_message()));
+                        }
+                    }
+                }
             }
         },
         status,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use tune::midi::ChannelMessage;
+    use tune::mts;
+    use tune::note::Note;
+    use tune::pitch::Pitched;
+    use tune::scala::KbmRoot;
+    use tune::scala::Scl;
+    use tune::tuner::PoolingMode;
+
+    use crate::dto::RoutingOutputDto;
+
+    use super::*;
+
+    #[test]
+    fn switch_scales() {
+        let (send, recv) = mpsc::channel();
+        let target = MidiTarget {
+            handler: move |message: MidiTunerMessage| {
+                message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+            },
+            channels: vec![0, 1],
+        };
+
+        let (status_send, status_recv) = mpsc::channel();
+        let tuner = LiveTuner::new(
+            LiveTunerKind::Jit(JitTuner::start(
+                TunableMidi::channel_fine_tuning(target),
+                PoolingMode::Stop,
+            )),
+            Route::default(),
+            TuningMethod::ChannelFineTuning,
+        );
+        let scales = LiveScales::new(vec![scale(0.0), scale(30.0)], vec![None]);
+        let mut router = LiveRouter::new(scales, vec![tuner]);
+        router.set_scale_switch(ScaleSwitch {
+            program_change: true,
+            controller: None,
+            key_range_start: Some(Note::from_midi_number(0)),
+            retune_held_notes: true,
+            status: Box::new(move |status| status_send.send(status).unwrap()),
+        });
+
+        let offset = || MultiChannelOffset { offset: 0 };
+        let note_on = |key| ChannelMessageType::NoteOn { key, velocity: 100 };
+
+        router.process(0, note_on(60), 0, offset());
+        recv.try_iter().count();
+
+        // The held note is retuned and the program change is not forwarded
+        router.process(
+            0,
+            ChannelMessageType::ProgramChange { program: 1 },
+            0,
+            offset(),
+        );
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 1],
+                [0xb0, 0x06, 83],
+                [0xb0, 0x26, 25],
+            ]
+        );
+
+        // New notes use the selected scale
+        router.process(0, note_on(62), 0, offset());
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                [0xb1, 0x65, 0],
+                [0xb1, 0x64, 1],
+                [0xb1, 0x06, 83],
+                [0xb1, 0x26, 25],
+                [0x91, 62, 100],
+            ]
+        );
+
+        // Switch keys are not played
+        router.process(0, note_on(0), 0, offset());
+        router.process(
+            0,
+            ChannelMessageType::NoteOff {
+                key: 0,
+                velocity: 0,
+            },
+            0,
+            offset(),
+        );
+        router.process(0, note_on(1), 0, offset());
+        router.process(0, note_on(2), 0, offset());
+        router.process(
+            0,
+            ChannelMessageType::ProgramChange { program: 5 },
+            0,
+            offset(),
+        );
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 1],
+                [0xb0, 0x06, 64],
+                [0xb0, 0x26, 0],
+                [0xb1, 0x65, 0],
+                [0xb1, 0x64, 1],
+                [0xb1, 0x06, 64],
+                [0xb1, 0x26, 0],
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 1],
+                [0xb0, 0x06, 83],
+                [0xb0, 0x26, 25],
+                [0xb1, 0x65, 0],
+                [0xb1, 0x64, 1],
+                [0xb1, 0x06, 83],
+                [0xb1, 0x26, 25],
+                // Key 2 is outside the switch range and steals the channel of key 60
+                [0x80, 60, 100],
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 1],
+                [0xb0, 0x06, 83],
+                [0xb0, 0x26, 25],
+                [0x90, 2, 100],
+            ]
+        );
+        assert_eq!(
+            status_recv.try_iter().collect::<Vec<_>>(),
+            [
+                "Switched to scale 1",
+                "Switched to scale 0",
+                "Switched to scale 1",
+                "Scale 5 is not available",
+            ]
+        );
+    }
+
+    #[test]
+    fn route_merged_inputs() {
+        let (send, recv) = mpsc::channel();
+        let target = |output| {
+            let send = send.clone();
+            MidiTarget {
+                handler: move |message: MidiTunerMessage| {
+                    message.send_to(|bytes| send.send((output, bytes.to_vec())).unwrap())
+                },
+                channels: vec![0, 1],
+            }
+        };
+        let tuner = |output, route| {
+            LiveTuner::new(
+                LiveTunerKind::Jit(JitTuner::start(
+                    TunableMidi::channel_fine_tuning(target(output)),
+                    PoolingMode::Stop,
+                )),
+                route,
+                TuningMethod::ChannelFineTuning,
+            )
+        };
+
+        let lower_route = Route {
+            upper_key_bound: Some(60),
+            ..Route::default()
+        };
+        let upper_route = Route {
+            inputs: Some(vec![1]),
+            lower_key_bound: Some(60),
+            ..Route::default()
+        };
+        let scales = LiveScales::new(vec![scale(0.0)], vec![None, Some(scale(30.0))]);
+        let mut router =
+            LiveRouter::new(scales, vec![tuner(0, lower_route), tuner(1, upper_route)]);
+
+        let offset = || MultiChannelOffset { offset: 0 };
+        let note_on = |key| ChannelMessageType::NoteOn { key, velocity: 100 };
+
+        router.process(0, note_on(50), 0, offset());
+        // Input 0 is not routed to the upper output
+        router.process(0, note_on(70), 0, offset());
+        router.process(1, note_on(70), 0, offset());
+        // The same key on a different input is a different note
+        router.process(1, note_on(50), 0, offset());
+        router.process(
+            0,
+            ChannelMessageType::NoteOff {
+                key: 50,
+                velocity: 0,
+            },
+            0,
+            offset(),
+        );
+
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                (0, vec![0xb0, 0x65, 0]),
+                (0, vec![0xb0, 0x64, 1]),
+                (0, vec![0xb0, 0x06, 63]),
+                (0, vec![0xb0, 0x26, 127]),
+                (0, vec![0x90, 50, 100]),
+                (1, vec![0xb0, 0x65, 0]),
+                (1, vec![0xb0, 0x64, 1]),
+                (1, vec![0xb0, 0x06, 83]),
+                (1, vec![0xb0, 0x26, 25]),
+                (1, vec![0x90, 70, 100]),
+                (0, vec![0xb1, 0x65, 0]),
+                (0, vec![0xb1, 0x64, 1]),
+                (0, vec![0xb1, 0x06, 83]),
+                (0, vec![0xb1, 0x26, 25]),
+                (0, vec![0x91, 50, 100]),
+                (0, vec![0x80, 50, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_routing_key_bounds() {
+        let routing: RoutingDto = serde_yaml::from_str(
+            "outputs:
+  - device: Bass Synth
+    up_key: C3
+  - device: Lead Synth
+    lo_key: 48
+",
+        )
+        .unwrap();
+
+        let key_bounds = |output: &RoutingOutputDto| {
+            (
+                output.lo_key.map(Note::midi_number),
+                output.up_key.map(Note::midi_number),
+            )
+        };
+        assert_eq!(key_bounds(&routing.outputs[0]), (None, Some(48)));
+        assert_eq!(key_bounds(&routing.outputs[1]), (Some(48), None));
+
+        assert!(
+            serde_yaml::from_str::<RoutingDto>("outputs: [{device: Synth, lo_key: H3}]").is_err()
+        );
+    }
+
+    #[test]
+    fn defer_mpe_expression() {
+        let (send, recv) = mpsc::channel();
+        let target = MidiTarget {
+            handler: move |message: MidiTunerMessage| {
+                message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+            },
+            channels: vec![1, 2],
+        };
+        let tuner = LiveTuner::new(
+            LiveTunerKind::Jit(JitTuner::start(
+                TunableMidi::mpe(target, 0, Ratio::from_semitones(48)),
+                PoolingMode::Stop,
+            )),
+            Route::default(),
+            TuningMethod::Mpe,
+        );
+        let scales = LiveScales::new(vec![scale(0.0)], vec![None]);
+        let mut router = LiveRouter::new(scales, vec![tuner]);
+        recv.try_iter().count();
+
+        let offset = || MultiChannelOffset { offset: 0 };
+        let pitch_bend = |value| ChannelMessageType::PitchBendChange { value };
+
+        // Per-note expression on a member channel is deferred until the note is started
+        router.process(0, pitch_bend(2048), 3, offset());
+        router.process(0, pitch_bend(4096), 3, offset());
+        router.process(
+            0,
+            ChannelMessageType::ChannelPressure { pressure: 50 },
+            3,
+            offset(),
+        );
+        assert_eq!(recv.try_iter().count(), 0);
+
+        router.process(
+            0,
+            ChannelMessageType::NoteOn {
+                key: 60,
+                velocity: 100,
+            },
+            3,
+            offset(),
+        );
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [[0xe1, 0, 64], [0x91, 60, 100], [0xe1, 0, 96], [0xd1, 50, 0],]
+        );
+
+        // Expression on the manager channel is zone-wide
+        router.process(0, pitch_bend(4096), 0, offset());
+        assert_eq!(recv.try_iter().collect::<Vec<_>>(), [[0xe0, 0, 96]]);
+    }
+
+    #[test]
+    fn filter_reserved_parameters() {
+        let (send, recv) = mpsc::channel();
+        let target = MidiTarget {
+            handler: move |message: MidiTunerMessage| {
+                message.send_to(|bytes| send.send(bytes.to_vec()).unwrap())
+            },
+            channels: vec![0],
+        };
+        let tuner = LiveTuner::new(
+            LiveTunerKind::Jit(JitTuner::start(
+                TunableMidi::channel_fine_tuning(target),
+                PoolingMode::Stop,
+            )),
+            Route::default(),
+            TuningMethod::ChannelFineTuning,
+        );
+        let scales = LiveScales::new(vec![scale(0.0)], vec![None]);
+        let mut router = LiveRouter::new(scales, vec![tuner]);
+
+        let offset = || MultiChannelOffset { offset: 0 };
+        let mut process_all = |messages: &[ChannelMessage]| {
+            for message in messages {
+                router.process(0, message.message_type(), message.channel(), offset());
+            }
+        };
+
+        // The fine tuning is controlled by the tuner
+        process_all(&mts::channel_fine_tuning(3, Ratio::from_cents(20.0)).unwrap());
+        assert_eq!(recv.try_iter().count(), 0);
+
+        // Other parameters are forwarded
+        process_all(&mts::pitch_bend_sensitivity(3, Ratio::from_semitones(12)).unwrap());
+        assert_eq!(
+            recv.try_iter().collect::<Vec<_>>(),
+            [
+                [0xb0, 0x65, 0],
+                [0xb0, 0x64, 0],
+                [0xb0, 0x06, 12],
+                [0xb0, 0x26, 0]
+            ]
+        );
+    }
+
+    #[test]
+    fn monitor_note_on() {
+        let (midi_send, _midi_recv) = flume::unbounded();
+        let (status_send, status_recv) = flume::unbounded();
+        let (capture_send, capture_recv) = flume::unbounded();
+        let (log_send, log_recv) = flume::unbounded();
+
+        let target = MidiTarget {
+            handler: LiveHandler {
+                output: 0,
+                midi_send,
+                status_send: status_send.clone(),
+                capture_send: Some(capture_send),
+            },
+            channels: vec![0, 1],
+        };
+        let tuner = LiveTuner::new(
+            LiveTunerKind::Jit(JitTuner::start(
+                TunableMidi::channel_fine_tuning(target),
+                PoolingMode::Stop,
+            )),
+            Route::default(),
+            TuningMethod::ChannelFineTuning,
+        );
+        let scales = LiveScales::new(vec![scale(30.0)], vec![None]);
+        let mut router = LiveRouter::new(scales, vec![tuner]);
+        let monitor = LiveMonitor {
+            print: true,
+            status_send,
+            log_send: Some(log_send),
+            capture_recv,
+        };
+
+        let offset = MultiChannelOffset { offset: 0 };
+        let note_on = ChannelMessageType::NoteOn {
+            key: 62,
+            velocity: 100,
+        };
+        router.process(0, note_on, 1, offset);
+        monitor.report(&router, Duration::from_millis(1500), 0, note_on, 1, offset);
+
+        assert_eq!(
+            status_recv.try_iter().collect::<Vec<_>>(),
+            [
+                "[Monitor]     1.500s | in 0 [91 3e 64] | key 62 IDX 2 | 298.798 Hz = D 4 +30.0¢ \
+                 | out 0 ch 0 [b0 65 00] [b0 64 01] [b0 06 53] [b0 26 19] [90 3e 64]"
+            ]
+        );
+        let log_entries = log_recv.try_iter().collect::<Vec<_>>();
+        let [log_entry] = &log_entries[..] else {
+            panic!("{log_entries:?}")
+        };
+        assert_eq!(log_entry.message, "91 3e 64");
+        assert_eq!(log_entry.note.as_ref().unwrap().scale_index, 2);
+        assert_eq!(log_entry.outputs[0].channel, Some(0));
+        assert_eq!(log_entry.outputs[0].messages.len(), 5);
+    }
+
+    fn scale(cents: f64) -> Scale {
+        let scl = Scl::builder().push_cents(100.0).build().unwrap();
+        let kbm_root = KbmRoot {
+            ref_key: PianoKey::from_midi_number(60),
+            ref_pitch: Note::from_midi_number(60).pitch() * Ratio::from_cents(cents),
+            root_offset: 0,
+        };
+        Scale {
+            origin: kbm_root.ref_key,
+            keys: vec![],
+            tuning: Box::new((scl, kbm_root.to_kbm())),
+        }
+    }
+}
@@ -17,32 +17,60 @@ use midir::MidiOutputConnection;
 use serde::Deserialize;
 use serde::Serialize;
 use tune::key::PianoKey;
+use tune::midi::CHANNEL_COARSE_TUNING;
+use tune::midi::CHANNEL_FINE_TUNING;
+use tune::midi::PITCH_BEND_SENSITIVITY;
+use tune::midi::ParameterNumber;
+use tune::midi::TUNING_BANK_SELECT;
+use tune::midi::TUNING_PROGRAM_CHANGE;
 use tune::mts::ScaleOctaveTuningFormat;
+use tune::pitch::Ratio;
+use tune::tuner::AllocationPolicy;
+use tune::tuner::ChannelOrder;
 use tune::tuner::MidiTarget;
+use tune::tuner::MidiTunerMessageHandler;
+use tune::tuner::NoteProtection;
+use tune::tuner::PoolingMode;
+use tune::tuner::StealingPolicy;
 use tune::tuner::TunableMidi;
 
 use crate::CliResult;
 use crate::portable;
 use crate::portable::SendTask;
 
-#[derive(Parser)]
+const DEFAULT_IN_CHANNEL: u8 = 0;
+const DEFAULT_NUM_IN_CHANS: u8 = 16;
+const DEFAULT_CHANNEL_OFFSET: i16 = 0;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Parser)]
+#[serde(default, deny_unknown_fields)]
 pub struct MidiInArgs {
     /// First MIDI channel to listen to for MIDI events
-    #[arg(long = "in-chan", default_value = "0")]
+    #[arg(long = "in-chan", default_value_t = DEFAULT_IN_CHANNEL)]
     pub in_channel: u8,
 
     /// Number of MIDI input channels to listen to.
     /// Wraps around at zero-based channel number 15.
     /// For example --in-chan=10 and --in-chans=15 uses all channels but the drum channel.
-    #[arg(long = "in-chans", default_value = "16")]
+    #[arg(long = "in-chans", default_value_t = DEFAULT_NUM_IN_CHANS)]
     pub num_in_channels: u8,
 
     /// Offset in scale steps per channel number.
     /// Required for keyboards with more than 128 keys like the Lumatone.
-    #[arg(long = "chan-offs", default_value = "0")]
+    #[arg(long = "chan-offs", default_value_t = DEFAULT_CHANNEL_OFFSET)]
     pub channel_offset: i16,
 }
 
+impl Default for MidiInArgs {
+    fn default() -> Self {
+        Self {
+            in_channel: DEFAULT_IN_CHANNEL,
+            num_in_channels: DEFAULT_NUM_IN_CHANS,
+            channel_offset: DEFAULT_CHANNEL_OFFSET,
+        }
+    }
+}
+
 impl MidiInArgs {
     pub fn get_midi_source(&self) -> CliResult<MidiSource> {
         Ok(MidiSource {
@@ -65,6 +93,7 @@ impl MidiSource {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct MultiChannelOffset {
     pub offset: i32,
 }
@@ -77,8 +106,11 @@ impl MultiChannelOffset {
 
 const DEFAULT_OUT_CHANNEL: u8 = 0;
 const DEFAULT_NUM_OUT_CHANS: u8 = 9;
+const DEFAULT_PITCH_BEND_RANGE: u8 = 2;
+const DEFAULT_MPE_PITCH_BEND_RANGE: u8 = 48;
 
 #[derive(Clone, Debug, Deserialize, Serialize, Parser)]
+#[serde(default, deny_unknown_fields)]
 pub struct MidiOutArgs {
     /// First MIDI channel to send the modified MIDI events to
     #[arg(long = "out-chan", default_value_t = DEFAULT_OUT_CHANNEL)]
@@ -90,7 +122,6 @@ pub struct MidiOutArgs {
     #[arg(long = "out-chans", default_value_t = DEFAULT_NUM_OUT_CHANS)]
     pub num_out_channels: u8,
 
-    #[serde(flatten)]
     #[command(flatten)]
     pub device_id: DeviceIdArg,
 
@@ -98,6 +129,13 @@ pub struct MidiOutArgs {
     /// Wraps around at tuning program number 127.
     #[arg(long = "tun-pg", default_value = "0")]
     pub tuning_program: u8,
+
+    /// Pitch-bend range of the output channels in semitones [default: 2, mpe: 48].
+    /// The range is sent to the synth via RPN 0 (Pitch Bend Sensitivity) when the `pitch-bend` or `mpe` tuning method is started.
+    /// In MPE mode, the first output channel is used as the manager channel and must be 0 (lower zone) or 15 (upper zone).
+    #[arg(long = "bend-range", alias = "mpe-range")]
+    #[serde(alias = "mpe_pitch_bend_range")]
+    pub pitch_bend_range: Option<u8>,
 }
 
 impl Default for MidiOutArgs {
@@ -107,11 +145,34 @@ impl Default for MidiOutArgs {
             num_out_channels: DEFAULT_NUM_OUT_CHANS,
             device_id: Default::default(),
             tuning_program: Default::default(),
+            pitch_bend_range: None,
         }
     }
 }
 
 impl MidiOutArgs {
+    /// Checks whether the given tuning `method` can be used with the current output settings.
+    ///
+    /// `ump_output` indicates that the messages are written as Universal MIDI Packets instead of being sent to a MIDI 1.0 device.
+    pub fn validate_method(&self, method: TuningMethod, ump_output: bool) -> Result<(), String> {
+        if method.is_midi2() && !ump_output {
+            return Err("MIDI 2.0 tuning methods require a UMP output (--ump-out)".to_owned());
+        }
+        if !method.is_midi2() && ump_output {
+            return Err(
+                "A UMP output (--ump-out) requires a MIDI 2.0 tuning method (midi2-attr or midi2-rc)"
+                    .to_owned(),
+            );
+        }
+        if method == TuningMethod::Mpe && ![0, 15].contains(&self.out_channel) {
+            return Err(format!(
+                "MPE requires the output channel (--out-chan) to be 0 or 15 but was {}",
+                self.out_channel
+            ));
+        }
+        Ok(())
+    }
+
     pub fn get_midi_target<H>(&self, handler: H) -> Result<MidiTarget<H>, String> {
         Ok(MidiTarget {
             handler,
@@ -119,7 +180,11 @@ impl MidiOutArgs {
         })
     }
 
-    pub fn create_synth<H>(&self, target: MidiTarget<H>, method: TuningMethod) -> TunableMidi<H> {
+    pub fn create_synth<H: MidiTunerMessageHandler>(
+        &self,
+        mut target: MidiTarget<H>,
+        method: TuningMethod,
+    ) -> TunableMidi<H> {
         match method {
             TuningMethod::FullKeyboard => TunableMidi::single_note_tuning_change(
                 target,
@@ -158,7 +223,26 @@ impl MidiOutArgs {
                 ScaleOctaveTuningFormat::TwoByte,
             ),
             TuningMethod::ChannelFineTuning => TunableMidi::channel_fine_tuning(target),
-            TuningMethod::PitchBend => TunableMidi::pitch_bend(target),
+            TuningMethod::PitchBend => TunableMidi::pitch_bend_with_range(
+                target,
+                Ratio::from_semitones(self.pitch_bend_range.unwrap_or(DEFAULT_PITCH_BEND_RANGE)),
+            ),
+            TuningMethod::Mpe => {
+                let manager_channel = self.out_channel;
+                target
+                    .channels
+                    .retain(|&channel| channel != manager_channel);
+                TunableMidi::mpe(
+                    target,
+                    manager_channel,
+                    Ratio::from_semitones(
+                        self.pitch_bend_range
+                            .unwrap_or(DEFAULT_MPE_PITCH_BEND_RANGE),
+                    ),
+                )
+            }
+            TuningMethod::Midi2PitchAttribute => TunableMidi::midi2_pitch_attribute(target),
+            TuningMethod::Midi2PerNotePitch => TunableMidi::midi2_per_note_pitch(target),
         }
     }
 }
@@ -183,6 +267,7 @@ fn get_channels(
 const DEFAULT_DEVICE_ID: u8 = 0x7f;
 
 #[derive(Clone, Debug, Deserialize, Serialize, Parser)]
+#[serde(transparent)]
 pub struct DeviceIdArg {
     /// ID of the device that should respond to MTS messages
     #[arg(long = "dev-id", default_value_t = DEFAULT_DEVICE_ID)]
@@ -223,6 +308,146 @@ pub enum TuningMethod {
     #[value(name = "pitch-bend")]
     #[serde(rename = "pitch-bend")]
     PitchBend,
+    #[value(name = "mpe")]
+    #[serde(rename = "mpe")]
+    Mpe,
+    #[value(name = "midi2-attr")]
+    #[serde(rename = "midi2-attr")]
+    Midi2PitchAttribute,
+    #[value(name = "midi2-rc")]
+    #[serde(rename = "midi2-rc")]
+    Midi2PerNotePitch,
+}
+
+impl TuningMethod {
+    /// Returns whether the given Registered Parameter is controlled by the tuning method s.t. it must not be changed by the input.
+    pub fn reserves_parameter(self, parameter: ParameterNumber) -> bool {
+        let reserved_parameters: &[u16] = match self {
+            TuningMethod::FullKeyboard | TuningMethod::FullKeyboardRt => {
+                &[TUNING_PROGRAM_CHANGE, TUNING_BANK_SELECT]
+            }
+            TuningMethod::ChannelFineTuning => &[CHANNEL_FINE_TUNING, CHANNEL_COARSE_TUNING],
+            TuningMethod::PitchBend | TuningMethod::Mpe => &[PITCH_BEND_SENSITIVITY],
+            _ => &[],
+        };
+
+        matches!(parameter, ParameterNumber::Registered(number) if reserved_parameters.contains(&number))
+    }
+
+    /// Returns whether the tuning method emits MIDI 2.0 Universal MIDI Packets.
+    pub fn is_midi2(self) -> bool {
+        matches!(
+            self,
+            TuningMethod::Midi2PitchAttribute | TuningMethod::Midi2PerNotePitch
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Parser)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllocationArgs {
+    /// Describes what to do when a note is triggered that cannot be handled by any channel without tuning clashes.
+    /// [block] Do not accept the new note. It will remain silent.
+    /// [stop] Stop an old note and accept the new note.
+    /// [ignore] Neither block nor stop. Accept that an old note receives an arbitrary tuning update.
+    #[arg(long = "clash", value_enum, default_value_t = ClashMitigation::Stop)]
+    pub clash_mitigation: ClashMitigation,
+
+    /// Describes which old note to stop or ignore when a tuning clash occurs.
+    /// [oldest] The note that has been triggered first.
+    /// [quietest] The note with the lowest velocity.
+    #[arg(long = "steal", value_enum, default_value_t = VoiceStealing::Oldest)]
+    pub stealing: VoiceStealing,
+
+    /// Describes which notes are never stopped or ignored when a tuning clash occurs.
+    #[arg(long = "protect", value_enum, default_value_t = ProtectedNotes::None)]
+    pub protection: ProtectedNotes,
+
+    /// Assign free channels in cyclic order instead of reusing the channel that has been free for the longest time
+    #[arg(long = "round-robin")]
+    pub round_robin: bool,
+
+    /// Time in milliseconds during which a released channel is only reused if no other channel is free
+    #[arg(long = "release", default_value = "0")]
+    pub release_time_ms: u64,
+}
+
+impl Default for AllocationArgs {
+    fn default() -> Self {
+        Self {
+            clash_mitigation: ClashMitigation::Stop,
+            stealing: VoiceStealing::Oldest,
+            protection: ProtectedNotes::None,
+            round_robin: false,
+            release_time_ms: 0,
+        }
+    }
+}
+
+impl AllocationArgs {
+    pub fn to_allocation_policy(&self) -> AllocationPolicy {
+        AllocationPolicy {
+            pooling_mode: match self.clash_mitigation {
+                ClashMitigation::Block => PoolingMode::Block,
+                ClashMitigation::Stop => PoolingMode::Stop,
+                ClashMitigation::Ignore => PoolingMode::Ignore,
+            },
+            stealing: match self.stealing {
+                VoiceStealing::Oldest => StealingPolicy::Oldest,
+                VoiceStealing::Quietest => StealingPolicy::Quietest,
+            },
+            protection: match self.protection {
+                ProtectedNotes::None => NoteProtection::None,
+                ProtectedNotes::Lowest => NoteProtection::Lowest,
+                ProtectedNotes::Highest => NoteProtection::Highest,
+                ProtectedNotes::Both => NoteProtection::LowestAndHighest,
+            },
+            channel_order: match self.round_robin {
+                true => ChannelOrder::RoundRobin,
+                false => ChannelOrder::LeastRecentlyUsed,
+            },
+            release_time: Duration::from_millis(self.release_time_ms),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+pub enum ClashMitigation {
+    #[value(name = "block")]
+    #[serde(rename = "block")]
+    Block,
+    #[value(name = "stop")]
+    #[serde(rename = "stop")]
+    Stop,
+    #[value(name = "ignore")]
+    #[serde(rename = "ignore")]
+    Ignore,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+pub enum VoiceStealing {
+    #[value(name = "oldest")]
+    #[serde(rename = "oldest")]
+    Oldest,
+    #[value(name = "quietest")]
+    #[serde(rename = "quietest")]
+    Quietest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+pub enum ProtectedNotes {
+    #[value(name = "none")]
+    #[serde(rename = "none")]
+    None,
+    #[value(name = "lowest")]
+    #[serde(rename = "lowest")]
+    Lowest,
+    #[value(name = "highest")]
+    #[serde(rename = "highest")]
+    Highest,
+    #[value(name = "both")]
+    #[serde(rename = "both")]
+    Both,
 }
 
 pub type MidiResult<T> = Result<T, MidiError>;
@@ -3,7 +3,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use clap::Parser;
-use tune::key::PianoKey;
+use tune::note::Note;
 use tune::pitch::Ratio;
 use tune::pitch::RatioExpression;
 use tune::pitch::RatioExpressionVariant;
@@ -216,13 +216,13 @@ pub struct KbmOptions {
     #[command(flatten)]
     kbm_root: KbmRootOptions,
 
-    /// Lower key bound (inclusive)
+    /// Lower key bound (inclusive), e.g. 21 or A0
     #[arg(long = "lo-key", default_value = "21")]
-    lower_key_bound: i32,
+    lower_key_bound: Note,
 
-    /// Upper key bound (exclusive)
+    /// Upper key bound (exclusive), e.g. 109 or C#8
     #[arg(long = "up-key", default_value = "109")]
-    upper_key_bound: i32,
+    upper_key_bound: Note,
 
     /// Keyboard mapping entries, e.g. 0,x,1,x,2,3,x,4,x,5,x,6
     #[arg(long = "key-map", use_value_delimiter = true, value_parser = parse_item)]
@@ -251,10 +251,8 @@ fn parse_item(s: &str) -> Result<Item, &'static str> {
 
 impl KbmOptions {
     pub fn to_kbm(&self) -> CliResult<Kbm> {
-        let mut builder = Kbm::builder(self.kbm_root.to_kbm_root()).range(
-            PianoKey::from_midi_number(self.lower_key_bound)
-                ..PianoKey::from_midi_number(self.upper_key_bound),
-        );
+        let mut builder = Kbm::builder(self.kbm_root.to_kbm_root())
+            .range(self.lower_key_bound.as_piano_key()..self.upper_key_bound.as_piano_key());
         if let Some(items) = &self.items {
             for item in items {
                 match item {
@@ -278,19 +276,19 @@ impl KbmOptions {
 
 #[derive(Parser)]
 pub struct KbmRootOptions {
-    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz
+    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz, A4, C4@261.6Hz or A4+13.7c
     ref_note: KbmRoot,
 
-    /// root note / "middle note" of the scale if different from reference note
+    /// root note / "middle note" of the scale if different from reference note, e.g. 62 or D4
     #[arg(long = "root")]
-    root_note: Option<i16>,
+    root_note: Option<Note>,
 }
 
 impl KbmRootOptions {
     pub fn to_kbm_root(&self) -> KbmRoot {
         match self.root_note {
             Some(root_note) => KbmRoot {
-                root_offset: i32::from(root_note) - self.ref_note.ref_key.midi_number(),
+                root_offset: root_note.midi_number() - self.ref_note.ref_key.midi_number(),
                 ..self.ref_note
             },
             None => self.ref_note,
@@ -298,7 +296,7 @@ impl KbmRootOptions {
     }
 }
 
-fn import_scl_file(file_name: &Path) -> Result<Scl, String> {
+pub fn import_scl_file(file_name: &Path) -> Result<Scl, String> {
     File::open(file_name)
         .map_err(SclImportError::IoError)
         .and_then(Scl::import)
@@ -5,6 +5,9 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use tune::key::PianoKey;
+use tune::notation::JiNotation;
+use tune::notation::JiNote;
+use tune::note::Note;
 use tune::pitch::Pitch;
 use tune::pitch::Pitched;
 use tune::pitch::Ratio;
@@ -16,6 +19,8 @@ use tune::tuning::Tuning;
 use crate::App;
 use crate::CliError;
 use crate::CliResult;
+use crate::dto::PlaylistDto;
+use crate::dto::PlaylistItemDto;
 use crate::dto::ScaleDto;
 use crate::dto::ScaleItemDto;
 use crate::dto::TuneDto;
@@ -115,6 +120,20 @@ struct LimitOptions {
     /// Largest acceptable numerator or denominator (ignoring powers of two)
     #[arg(long = "lim", default_value = "11")]
     odd_limit: u16,
+
+    /// Label the approximated ratios with just-intonation note names relative to the root note.
+    /// [heji] Extended Helmholtz-Ellis notation, e.g. Bb↓7 for 7/4 above C.
+    /// [sagittal] ASCII Sagittal notation, e.g. Bb!) for 7/4 above C.
+    #[arg(long = "notation", value_parser = parse_notation)]
+    notation: Option<JiNotation>,
+}
+
+fn parse_notation(src: &str) -> Result<JiNotation, &'static str> {
+    Ok(match &*src.to_lowercase() {
+        "heji" => JiNotation::Heji,
+        "sagittal" => JiNotation::Sagittal,
+        _ => return Err("Invalid notation. Should be `heji` or `sagittal`"),
+    })
 }
 
 pub(crate) struct Scale {
@@ -148,7 +167,23 @@ impl Scale {
         })
     }
 
-    fn from_scale_file(scale_file_location: &Path) -> CliResult<Self> {
+    pub fn from_scl_and_kbm_files(
+        scl_file_location: &Path,
+        kbm_file_location: &Path,
+    ) -> CliResult<Self> {
+        let scl = scala::import_scl_file(scl_file_location)?;
+        let kbm = scala::import_kbm_file(kbm_file_location)?;
+        Ok(Scale {
+            origin: kbm
+                .kbm_root()
+                .ref_key
+                .plus_steps(kbm.kbm_root().root_offset),
+            keys: kbm.range_iter().collect(),
+            tuning: Box::new((scl, kbm)),
+        })
+    }
+
+    pub fn from_scale_file(scale_file_location: &Path) -> CliResult<Self> {
         let file =
             File::open(scale_file_location).display_err::<CliError>("Could not read scale file")?;
         let scale_dto = ScaleDto::read(file)?;
@@ -159,6 +194,30 @@ impl Scale {
         })
     }
 
+    pub fn from_playlist_file(playlist_file_location: &Path) -> CliResult<Vec<Self>> {
+        let file = File::open(playlist_file_location)
+            .display_err::<CliError>("Could not read playlist file")?;
+        let playlist_dir = playlist_file_location.parent().unwrap_or(Path::new(""));
+
+        PlaylistDto::read(file)?
+            .scales
+            .iter()
+            .map(|item| Scale::from_playlist_item(item, playlist_dir))
+            .collect()
+    }
+
+    /// Loads the given playlist item. Relative file locations are resolved wrt. `base_dir`.
+    pub fn from_playlist_item(item: &PlaylistItemDto, base_dir: &Path) -> CliResult<Self> {
+        match item {
+            PlaylistItemDto::SclAndKbm { scl, kbm } => {
+                Scale::from_scl_and_kbm_files(&base_dir.join(scl), &base_dir.join(kbm))
+            }
+            PlaylistItemDto::ScaleFile { scale_file } => {
+                Scale::from_scale_file(&base_dir.join(scale_file))
+            }
+        }
+    }
+
     fn from_stdin(app: &mut App) -> CliResult<Self> {
         let scale_dto = ScaleDto::read(app.read())?;
         Ok(Scale {
@@ -223,6 +282,7 @@ impl DumpOptions {
             root_key: scale.origin,
             root_pitch: scale.tuning.maybe_pitch_of(scale.origin),
             odd_limit: self.limit.odd_limit,
+            notation: self.limit.notation,
         };
 
         printer.print_table_header()?;
@@ -256,6 +316,7 @@ impl DiffOptions {
             root_pitch: source_scale.tuning.maybe_pitch_of(source_scale.origin),
             root_key: source_scale.origin,
             odd_limit: self.limit.odd_limit,
+            notation: self.limit.notation,
         };
 
         printer.print_table_header()?;
@@ -309,16 +370,22 @@ struct ScaleTablePrinter<'a, 'b> {
     root_key: PianoKey,
     root_pitch: Option<Pitch>,
     odd_limit: u16,
+    notation: Option<JiNotation>,
 }
 
 impl ScaleTablePrinter<'_, '_> {
     fn print_table_header(&mut self) -> io::Result<()> {
-        self.app.writeln(format_args!(
+        self.app.write(format_args!(
             "  {source:-^33} ‖ {pitch:-^14} ‖ {target:-^28}",
             source = "Source Scale",
             pitch = "Pitch",
             target = "Target Scale"
-        ))
+        ))?;
+        if self.notation.is_some() {
+            self.app
+                .write(format_args!(" ‖ {ji_name:-^14}", ji_name = "JI Name"))?;
+        }
+        self.app.writeln("")
     }
 
     fn print_table_row(
@@ -339,7 +406,7 @@ impl ScaleTablePrinter<'_, '_> {
         let nearest_fraction = Ratio::between_pitches(self.root_pitch.unwrap_or(pitch), pitch)
             .nearest_fraction(self.odd_limit);
 
-        self.app.writeln(format_args!(
+        self.app.write(format_args!(
             "{source_midi:>3} | IDX {source_index:>4} | \
              {numer:>2}/{denom:<2} {fract_deviation:>+4.0}¢ {fract_octaves:>+3}o ‖ \
              {pitch:>11.3} Hz ‖ {target_midi:>4} | {target_index} | {deviation:>+8.3}¢",
@@ -353,6 +420,26 @@ impl ScaleTablePrinter<'_, '_> {
             target_midi = target_midi,
             target_index = target_index,
             deviation = deviation.as_cents(),
-        ))
+        ))?;
+
+        if let Some(notation) = self.notation {
+            let (numer, denom) = match nearest_fraction.num_octaves {
+                num_octaves @ 0.. => (
+                    u128::from(nearest_fraction.numer) << num_octaves,
+                    u128::from(nearest_fraction.denom),
+                ),
+                num_octaves => (
+                    u128::from(nearest_fraction.numer),
+                    u128::from(nearest_fraction.denom) << -num_octaves,
+                ),
+            };
+            let ji_name = JiNote::from_fraction(Note::from_piano_key(self.root_key), numer, denom)
+                .and_then(|ji_note| ji_note.render(notation))
+                .unwrap_or_else(|| "-".to_owned());
+
+            self.app.write(format_args!(" ‖ {ji_name:>14}"))?;
+        }
+
+        self.app.writeln("")
     }
 }
@@ -12,3 +12,6 @@ pub mod portable {
 pub mod scala {
     pub use crate::scala::*;
 }
+pub mod smf {
+    pub use crate::smf::*;
+}
@@ -0,0 +1,359 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+use clap::Parser;
+use midly::Arena;
+use midly::Format;
+use midly::Header;
+use midly::MetaMessage;
+use midly::MidiMessage;
+use midly::PitchBend;
+use midly::Smf;
+use midly::Timing;
+use midly::TrackEvent;
+use midly::TrackEventKind;
+use midly::num::u4;
+use midly::num::u7;
+use midly::num::u28;
+use tune::midi::ChannelMessage;
+use tune::midi::ChannelMessageType;
+use tune::tuner::MidiTunerMessage;
+use tune::tuner::MidiTunerMessageHandler;
+use tune::tuner::MidiTunerWarning;
+
+use crate::App;
+use crate::CliError;
+use crate::CliResult;
+use crate::error::ResultExt;
+use crate::live::LiveMode;
+use crate::live::LiveRouter;
+use crate::live::LiveScales;
+use crate::live::Route;
+use crate::midi::MidiInArgs;
+use crate::midi::MidiOutArgs;
+use crate::midi::TuningMethod;
+
+#[derive(Parser)]
+pub(crate) struct SmfOptions {
+    /// Standard MIDI File (format 0 or 1) to retune
+    input_file: PathBuf,
+
+    /// Location of the retuned Standard MIDI File
+    output_file: PathBuf,
+
+    #[command(flatten)]
+    midi_in_args: MidiInArgs,
+
+    #[command(flatten)]
+    midi_out_args: MidiOutArgs,
+
+    #[command(subcommand)]
+    mode: LiveMode,
+}
+
+impl SmfOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        if matches!(
+            self.mode.method(),
+            TuningMethod::Midi2PitchAttribute | TuningMethod::Midi2PerNotePitch
+        ) {
+            return Err(
+                "MIDI 2.0 tuning methods cannot be written to a Standard MIDI File"
+                    .to_owned()
+                    .into(),
+            );
+        }
+
+        let input_data = fs::read(&self.input_file)?;
+        let input_smf = parse_smf(&input_data)?;
+
+        let handler = SmfHandler::default();
+
+        let source = self.midi_in_args.get_midi_source()?;
+        let target = self.midi_out_args.get_midi_target(handler.clone())?;
+        let scales = LiveScales::new(vec![self.mode.scale().to_scale(app)?], vec![None]);
+        let tuner =
+            self.mode
+                .create_tuner(app, target, &self.midi_out_args, Route::default(), &scales)?;
+        let mut router = LiveRouter::new(scales, vec![tuner]);
+
+        let arena = Arena::new();
+        let mut output_tracks = vec![Vec::new(); input_smf.tracks.len()];
+        let mut end_of_track_ticks = vec![0; input_smf.tracks.len()];
+
+        // Tuning messages sent ahead of time are placed at the beginning of the first track
+        if let Some(first_track) = output_tracks.first_mut() {
+            handler.drain_into(first_track, 0, &arena);
+        }
+
+        let mut clock = SmfClock::new(input_smf.header.timing);
+        for (tick, track_index, kind) in merge_tracks(&input_smf.tracks) {
+            router.set_time(clock.time_at(tick));
+            let output_track = &mut output_tracks[track_index];
+            match kind {
+                TrackEventKind::Midi { channel, message } => {
+                    let channel = channel.as_int();
+                    if source.channels.contains(&channel) {
+                        router.process(
+                            0,
+                            to_channel_message_type(message),
+                            channel,
+                            source.get_offset(channel),
+                        );
+                        handler.drain_into(output_track, tick, &arena);
+                    } else {
+                        output_track.push((tick, kind));
+                    }
+                }
+                TrackEventKind::Meta(MetaMessage::EndOfTrack) => {
+                    end_of_track_ticks[track_index] = tick;
+                }
+                TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => {
+                    clock.set_tempo(tick, tempo.as_int());
+                    output_track.push((tick, kind));
+                }
+                kind => output_track.push((tick, kind)),
+            }
+        }
+
+        for warning in handler.warnings.borrow().iter() {
+            app.errln(format_args!("Warning: {warning}"))?;
+        }
+
+        let mut output_smf = Smf::new(Header::new(
+            input_smf.header.format,
+            input_smf.header.timing,
+        ));
+        output_smf.tracks = output_tracks
+            .into_iter()
+            .zip(end_of_track_ticks)
+            .map(|(events, end_of_track_tick)| to_track(events, end_of_track_tick))
+            .collect();
+        output_smf.save(&self.output_file)?;
+
+        app.writeln(format_args!(
+            "Wrote retuned MIDI file to {}",
+            self.output_file.display()
+        ))?;
+
+        Ok(())
+    }
+}
+
+/// Reads all channel messages of a Standard MIDI File (format 0 or 1) together with their playback time.
+pub fn read_channel_messages(data: &[u8]) -> CliResult<Vec<(Duration, ChannelMessage)>> {
+    let smf = parse_smf(data)?;
+
+    let mut messages = Vec::new();
+    let mut clock = SmfClock::new(smf.header.timing);
+    for (tick, _, kind) in merge_tracks(&smf.tracks) {
+        match kind {
+            TrackEventKind::Midi { channel, message } => {
+                if let Some(message) = to_channel_message_type(message).in_channel(channel.as_int())
+                {
+                    messages.push((clock.time_at(tick), message));
+                }
+            }
+            TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => {
+                clock.set_tempo(tick, tempo.as_int());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(messages)
+}
+
+fn parse_smf(data: &[u8]) -> CliResult<Smf<'_>> {
+    let smf = Smf::parse(data).display_err::<CliError>("Could not parse MIDI file")?;
+
+    if smf.header.format == Format::Sequential {
+        return Err("Only Standard MIDI Files of format 0 or 1 are supported"
+            .to_owned()
+            .into());
+    }
+
+    Ok(smf)
+}
+
+/// Collects the output of the tuner s.t. it can be inserted at the position of the triggering event.
+#[derive(Clone, Default)]
+struct SmfHandler {
+    messages: Rc<RefCell<Vec<Vec<u8>>>>,
+    warnings: Rc<RefCell<Vec<MidiTunerWarning>>>,
+}
+
+impl MidiTunerMessageHandler for SmfHandler {
+    fn handle(&mut self, message: MidiTunerMessage) {
+        message.send_to(|bytes| self.messages.borrow_mut().push(bytes.to_vec()));
+    }
+
+    fn handle_warning(&mut self, warning: MidiTunerWarning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+}
+
+impl SmfHandler {
+    fn drain_into<'a>(
+        &self,
+        track: &mut Vec<(u64, TrackEventKind<'a>)>,
+        tick: u64,
+        arena: &'a Arena,
+    ) {
+        for bytes in self.messages.borrow_mut().drain(..) {
+            let kind = match bytes.first() {
+                // SysEx events in SMFs do not contain the leading 0xf0 byte
+                Some(0xf0) => TrackEventKind::SysEx(arena.add(&bytes[1..])),
+                _ => match ChannelMessage::from_raw_message(&bytes) {
+                    Some(message) => to_track_event_kind(message),
+                    None => continue,
+                },
+            };
+            track.push((tick, kind));
+        }
+    }
+}
+
+/// Converts absolute ticks into playback time taking tempo changes into account.
+struct SmfClock {
+    timing: Timing,
+    tempo_tick: u64,
+    tempo_time: Duration,
+    micros_per_beat: u32,
+}
+
+impl SmfClock {
+    const DEFAULT_MICROS_PER_BEAT: u32 = 500_000;
+
+    fn new(timing: Timing) -> Self {
+        Self {
+            timing,
+            tempo_tick: 0,
+            tempo_time: Duration::ZERO,
+            micros_per_beat: Self::DEFAULT_MICROS_PER_BEAT,
+        }
+    }
+
+    fn time_at(&self, tick: u64) -> Duration {
+        let elapsed_ticks = tick.saturating_sub(self.tempo_tick);
+        self.tempo_time
+            + match self.timing {
+                Timing::Metrical(ticks_per_beat) => Duration::from_micros(
+                    elapsed_ticks * u64::from(self.micros_per_beat)
+                        / u64::from(ticks_per_beat.as_int().max(1)),
+                ),
+                Timing::Timecode(fps, ticks_per_frame) => Duration::from_secs_f64(
+                    elapsed_ticks as f64
+                        / (f64::from(fps.as_f32()) * f64::from(ticks_per_frame.max(1))),
+                ),
+            }
+    }
+
+    fn set_tempo(&mut self, tick: u64, micros_per_beat: u32) {
+        self.tempo_time = self.time_at(tick);
+        self.tempo_tick = tick;
+        self.micros_per_beat = micros_per_beat;
+    }
+}
+
+/// Merges all tracks into a single sequence of `(absolute tick, track index, event)` triples ordered by time.
+///
+/// Events with the same tick are ordered by track index and their position within the track.
+fn merge_tracks<'a>(tracks: &[Vec<TrackEvent<'a>>]) -> Vec<(u64, usize, TrackEventKind<'a>)> {
+    let mut events = Vec::new();
+    for (track_index, track) in tracks.iter().enumerate() {
+        let mut tick = 0;
+        for event in track {
+            tick += u64::from(event.delta.as_int());
+            events.push((tick, track_index, event.kind));
+        }
+    }
+    events.sort_by_key(|&(tick, track_index, _)| (tick, track_index));
+    events
+}
+
+/// Creates a track from `(absolute tick, event)` pairs and terminates it at the original length of the input track.
+fn to_track(events: Vec<(u64, TrackEventKind)>, end_of_track_tick: u64) -> Vec<TrackEvent> {
+    let mut last_tick = 0;
+    let mut track: Vec<_> = events
+        .into_iter()
+        .map(|(tick, kind)| {
+            let delta = u28::new(u32::try_from(tick - last_tick).unwrap_or(u32::MAX));
+            last_tick = tick;
+            TrackEvent { delta, kind }
+        })
+        .collect();
+    track.push(TrackEvent {
+        delta: u28::new(
+            u32::try_from(end_of_track_tick.saturating_sub(last_tick)).unwrap_or(u32::MAX),
+        ),
+        kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+    });
+    track
+}
+
+fn to_channel_message_type(message: MidiMessage) -> ChannelMessageType {
+    match message {
+        MidiMessage::NoteOff { key, vel } => ChannelMessageType::NoteOff {
+            key: key.as_int(),
+            velocity: vel.as_int(),
+        },
+        MidiMessage::NoteOn { key, vel } => ChannelMessageType::NoteOn {
+            key: key.as_int(),
+            velocity: vel.as_int(),
+        },
+        MidiMessage::Aftertouch { key, vel } => ChannelMessageType::PolyphonicKeyPressure {
+            key: key.as_int(),
+            pressure: vel.as_int(),
+        },
+        MidiMessage::Controller { controller, value } => ChannelMessageType::ControlChange {
+            controller: controller.as_int(),
+            value: value.as_int(),
+        },
+        MidiMessage::ProgramChange { program } => ChannelMessageType::ProgramChange {
+            program: program.as_int(),
+        },
+        MidiMessage::ChannelAftertouch { vel } => ChannelMessageType::ChannelPressure {
+            pressure: vel.as_int(),
+        },
+        MidiMessage::PitchBend { bend } => ChannelMessageType::PitchBendChange {
+            value: bend.as_int(),
+        },
+    }
+}
+
+fn to_track_event_kind(message: ChannelMessage) -> TrackEventKind<'static> {
+    let channel = u4::new(message.channel());
+    let message = match message.message_type() {
+        ChannelMessageType::NoteOff { key, velocity } => MidiMessage::NoteOff {
+            key: u7::new(key),
+            vel: u7::new(velocity),
+        },
+        ChannelMessageType::NoteOn { key, velocity } => MidiMessage::NoteOn {
+            key: u7::new(key),
+            vel: u7::new(velocity),
+        },
+        ChannelMessageType::PolyphonicKeyPressure { key, pressure } => MidiMessage::Aftertouch {
+            key: u7::new(key),
+            vel: u7::new(pressure),
+        },
+        ChannelMessageType::ControlChange { controller, value } => MidiMessage::Controller {
+            controller: u7::new(controller),
+            value: u7::new(value),
+        },
+        ChannelMessageType::ProgramChange { program } => MidiMessage::ProgramChange {
+            program: u7::new(program),
+        },
+        ChannelMessageType::ChannelPressure { pressure } => MidiMessage::ChannelAftertouch {
+            vel: u7::new(pressure),
+        },
+        ChannelMessageType::PitchBendChange { value } => MidiMessage::PitchBend {
+            bend: PitchBend::from_int(value),
+        },
+    };
+
+    TrackEventKind::Midi { channel, message }
+}
@@ -1,5 +1,7 @@
 use std::env;
 use std::fs;
+use std::path::PathBuf;
+use std::process;
 use std::process::Command;
 use std::process::Output;
 use std::process::Stdio;
@@ -15,6 +17,11 @@ fn check_output(actual: &[u8], expected: &str, file_name: &str) {
     assert_eq!(String::from_utf8_lossy(actual), expected);
 }
 
+/// Returns a path in the temp directory that is unique per test process s.t. concurrent test runs do not interfere.
+fn temp_file(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("tune-cli-test-{}-{name}", process::id()))
+}
+
 fn call_cli(args: &[&str]) -> Output {
     Command::new(env!("CARGO_BIN_EXE_tune"))
         .args(args)
@@ -68,6 +75,65 @@ fn dump_7_edo_with_root() {
     check_output!("snapshots/dump_7_edo_with_root.stdout", output.stdout);
 }
 
+#[test]
+fn dump_7_edo_with_root_note_names() {
+    let output = call_cli(&["dump", "ref-note", "D4", "--root", "C4", "steps", "1/7:2"]);
+    check_output!("snapshots/dump_7_edo_with_root.stdout", output.stdout);
+}
+
+#[test]
+fn dump_12_edo_with_microtonal_ref_note() {
+    let output = call_cli(&[
+        "dump", "ref-note", "a'+13.7c", "--lo-key", "F4", "--up-key", "C#5", "steps", "1/12:2",
+    ]);
+    check_output!(
+        "snapshots/dump_12_edo_with_microtonal_ref_note.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_harmonics_with_heji_notation() {
+    let output = call_cli(&[
+        "dump",
+        "--notation",
+        "heji",
+        "ref-note",
+        "60",
+        "--lo-key",
+        "60",
+        "--up-key",
+        "69",
+        "harm",
+        "8",
+    ]);
+    check_output!(
+        "snapshots/dump_harmonics_with_heji_notation.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_harmonics_with_sagittal_notation() {
+    let output = call_cli(&[
+        "dump",
+        "--notation",
+        "sagittal",
+        "ref-note",
+        "60",
+        "--lo-key",
+        "60",
+        "--up-key",
+        "69",
+        "harm",
+        "8",
+    ]);
+    check_output!(
+        "snapshots/dump_harmonics_with_sagittal_notation.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn diff_quarter_comma_and_31_edo() {
     let output = call_cli_piped(
@@ -264,6 +330,24 @@ fn create_scl() {
     check_output!("snapshots/README_create_scl.stdout", output.stdout);
 }
 
+#[test]
+fn create_scl_with_extended_expressions() {
+    let output = call_cli(&[
+        "scl",
+        "steps",
+        "3/2*3/4",
+        "2\\12",
+        "(3/2)^4/4",
+        "5\\13<3>",
+        "660Hz/440Hz",
+        "2",
+    ]);
+    check_output!(
+        "snapshots/create_scl_with_extended_expressions.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn create_harmonics_scale() {
     let output = call_cli(&["scl", "harm", "-u", "37", "74", "--neji=13"]);
@@ -298,3 +382,205 @@ fn crate_kbm() {
     ]);
     check_output!("snapshots/README_create_kbm.stdout", output.stdout);
 }
+
+#[test]
+fn render_hex_layout_svg() {
+    let output = call_cli(&[
+        "layout", "svg", "--cols", "4", "--rows", "3", "steps", "1/12:2",
+    ]);
+    check_output!("snapshots/render_hex_layout_svg.stdout", output.stdout);
+}
+
+#[test]
+fn render_scale_strip_svg() {
+    let output = call_cli(&[
+        "layout",
+        "svg",
+        "--chart",
+        "strip",
+        "--labels",
+        "degree,ratio",
+        "steps",
+        "1/7:2",
+    ]);
+    check_output!("snapshots/render_scale_strip_svg.stdout", output.stdout);
+}
+
+#[test]
+fn retune_smf_with_pitch_bend() {
+    use midly::num::{u4, u7, u28};
+    use midly::{
+        Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind,
+    };
+
+    let note_event = |delta, key, on| TrackEvent {
+        delta: u28::new(delta),
+        kind: TrackEventKind::Midi {
+            channel: u4::new(0),
+            message: if on {
+                MidiMessage::NoteOn {
+                    key: u7::new(key),
+                    vel: u7::new(100),
+                }
+            } else {
+                MidiMessage::NoteOff {
+                    key: u7::new(key),
+                    vel: u7::new(0),
+                }
+            },
+        },
+    };
+    let end_of_track = |delta| TrackEvent {
+        delta: u28::new(delta),
+        kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+    };
+
+    let mut input_smf = Smf::new(Header::new(Format::Parallel, Timing::Metrical(480.into())));
+    input_smf.tracks.push(vec![
+        TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::Tempo(500000.into())),
+        },
+        // Meta-only tail
+        end_of_track(1920),
+    ]);
+    input_smf.tracks.push(vec![
+        note_event(0, 62, true),
+        note_event(240, 63, true),
+        note_event(240, 62, false),
+        note_event(0, 63, false),
+        // Trailing silence
+        end_of_track(480),
+    ]);
+
+    let input_file = temp_file("retune-smf-input.mid");
+    let output_file = temp_file("retune-smf-output.mid");
+    input_smf.save(&input_file).unwrap();
+
+    let output = call_cli(&[
+        "smf",
+        input_file.to_str().unwrap(),
+        output_file.to_str().unwrap(),
+        "--out-chans",
+        "2",
+        "jit",
+        "pitch-bend",
+        "ref-note",
+        "62",
+        "steps",
+        "1/16:2",
+    ]);
+    assert!(output.status.success());
+
+    let output_data = fs::read(&output_file).unwrap();
+    let output_smf = Smf::parse(&output_data).unwrap();
+    fs::remove_file(&input_file).unwrap();
+    fs::remove_file(&output_file).unwrap();
+
+    let mut listing = String::new();
+    for (track_index, track) in output_smf.tracks.iter().enumerate() {
+        let mut tick = 0;
+        for event in track {
+            tick += event.delta.as_int();
+            listing += &format!("track {track_index} tick {tick}: {:?}\n", event.kind);
+        }
+    }
+
+    check_output!(
+        "snapshots/retune_smf_with_pitch_bend.stdout",
+        listing.as_bytes()
+    );
+}
+
+#[test]
+fn override_live_config() {
+    let config_file = temp_file("override-live-config.yml");
+    fs::write(
+        &config_file,
+        "midi_in_device: Lumatone
+midi_in_args:
+  channel_offset: 31
+midi_out_device: FluidSynth
+midi_out_args:
+  num_out_channels: 4
+scale_switch_args:
+  switch_via_program_change: true
+  switch_key_range_start: A0
+mode:
+  jit:
+    allocation_args:
+      clash_mitigation: block
+    method: pitch-bend
+    scale: [ref-note, 62, steps, 1/31:2]
+",
+    )
+    .unwrap();
+
+    let output = call_cli(&[
+        "live",
+        "--config",
+        config_file.to_str().unwrap(),
+        "--out-chans",
+        "8",
+        "--switch-prog=false",
+        "--dump-config",
+    ]);
+    fs::remove_file(&config_file).unwrap();
+    check_output!("snapshots/override_live_config.stdout", output.stdout);
+}
+
+#[test]
+fn resolve_live_config_paths() {
+    let config_file = temp_file("resolve-live-config.yml");
+    fs::write(
+        &config_file,
+        "midi_in_device: Lumatone
+ump_out_file: \"-\"
+log_file: session.log
+mode:
+  aot:
+    method: midi2-attr
+    scale: [scale-file, scale.yml]
+",
+    )
+    .unwrap();
+
+    let output = call_cli(&[
+        "live",
+        "--config",
+        config_file.to_str().unwrap(),
+        "--dump-config",
+    ]);
+    fs::remove_file(&config_file).unwrap();
+
+    let config_dir = config_file.parent().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("ump_out_file: \"-\""));
+    assert!(stdout.contains(&format!(
+        "log_file: {}",
+        config_dir.join("session.log").display()
+    )));
+    assert!(stdout.contains(&format!("- {}", config_dir.join("scale.yml").display())));
+}
+
+#[test]
+fn reject_unknown_live_config_fields() {
+    let config_file = temp_file("reject-live-config.yml");
+    fs::write(
+        &config_file,
+        "midi_in_device: Lumatone
+midi_out_args:
+  num_out_chanels: 4
+",
+    )
+    .unwrap();
+
+    let output = call_cli(&["live", "--config", config_file.to_str().unwrap()]);
+    fs::remove_file(&config_file).unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unknown field `num_out_chanels`"),
+        "{stderr}"
+    );
+}